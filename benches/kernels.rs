@@ -0,0 +1,120 @@
+#![allow(clippy::needless_return)]
+
+/* scalar against SIMD kernels and single rays against packets, run with cargo bench */
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use rt_rs::direction3::Direction3;
+use rt_rs::hittable::Hittable;
+use rt_rs::matrix4::Matrix4;
+use rt_rs::packet::{PacketHittable, RayPacket};
+use rt_rs::point3::Point3;
+use rt_rs::ray::Ray;
+use rt_rs::scalar::{Float, Real};
+use rt_rs::simd::{self, Mat4};
+use rt_rs::sphere::Sphere;
+use rt_rs::triangle::Triangle;
+use rt_rs::vector4::Vector4;
+
+const ITERATIONS: u32 = 1_000_000;
+
+/* prints the average time of one call of f */
+fn bench<R>(name: &str, mut f: impl FnMut() -> R) {
+    for _ in 0..ITERATIONS / 10 {
+        black_box(f());
+    }
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(f());
+    }
+    let ns = start.elapsed().as_nanos() as f64 / ITERATIONS as f64;
+    println!("{:<40} {:>8.2} ns", name, ns);
+}
+
+fn random_matrix<T: Float>(rng: &mut StdRng) -> Mat4<T> {
+    return [[(); 4]; 4].map(|row| row.map(|_| T::from_f64(rng.gen_range(-1.0..1.0))));
+}
+
+fn kernels<T: Float>(precision: &str, rng: &mut StdRng) {
+    let a = random_matrix::<T>(rng);
+    let b = random_matrix::<T>(rng);
+    let v = a[0];
+
+    bench(&format!("mat4 * mat4, {} scalar", precision), || {
+        simd::scalar::mat4_mul(black_box(&a), black_box(&b))
+    });
+    bench(&format!("mat4 * mat4, {} dispatched", precision), || {
+        T::mat4_mul(black_box(&a), black_box(&b))
+    });
+    bench(&format!("mat4 * vec4, {} scalar", precision), || {
+        simd::scalar::mat4_mul_vec4(black_box(&a), black_box(&v))
+    });
+    bench(&format!("mat4 * vec4, {} dispatched", precision), || {
+        T::mat4_mul_vec4(black_box(&a), black_box(&v))
+    });
+}
+
+/* the old Vector4::magnitude against the current one */
+fn magnitude() {
+    let v = Vector4::new((1.0f32, 2.0, 3.0, 1.0));
+    bench("vec4 magnitude, powf", || {
+        let v = black_box(&v);
+        (v.get_x().powf(2.0) + v.get_y().powf(2.0) + v.get_z().powf(2.0) + v.get_w().powf(2.0))
+            .sqrt()
+    });
+    bench("vec4 magnitude, dispatched", || black_box(&v).magnitude());
+
+    let m: Matrix4 = Matrix4::rotate_y(30.0) * Matrix4::translate(1.0, 2.0, 3.0);
+    bench("Matrix4 * Vector4", || black_box(m) * black_box(v));
+}
+
+fn packets<S: Hittable + PacketHittable, const N: usize>(name: &str, shape: &S, rng: &mut StdRng) {
+    let rays = [(); N].map(|_| {
+        Ray::new(
+            Point3::origin(),
+            Direction3::new((rng.gen_range(-0.3..0.3), rng.gen_range(-0.3..0.3), -1.0)).unit(),
+        )
+    });
+    let packet = RayPacket::new(&rays);
+    let t_max = [Real::MAX; N];
+
+    bench(&format!("{} rays one by one against {}", N, name), || {
+        rays.each_ref()
+            .map(|ray| shape.hit(black_box(ray), 0.001, Real::MAX))
+    });
+    bench(&format!("{} ray packet against {}", N, name), || {
+        shape.hit_packet(black_box(&packet), 0.001, &t_max)
+    });
+    bench(
+        &format!("{} shadow rays one by one against {}", N, name),
+        || {
+            rays.each_ref()
+                .map(|ray| shape.hit(black_box(ray), 0.001, Real::MAX).is_some())
+        },
+    );
+    bench(&format!("{} shadow ray packet against {}", N, name), || {
+        shape.occluded_packet(black_box(&packet), 0.001, &t_max)
+    });
+}
+
+fn main() {
+    let mut rng = StdRng::seed_from_u64(15);
+    kernels::<f32>("f32", &mut rng);
+    kernels::<f64>("f64", &mut rng);
+    magnitude();
+
+    let sphere = Sphere::new(Point3::new((0.0, 0.0, -5.0)), 1.0);
+    let triangle = Triangle::new(
+        Point3::new((-1.0, -1.0, -5.0)),
+        Point3::new((1.0, -1.0, -5.0)),
+        Point3::new((0.0, 1.0, -5.0)),
+    );
+    packets::<_, 4>("a sphere", &sphere, &mut rng);
+    packets::<_, 8>("a sphere", &sphere, &mut rng);
+    packets::<_, 4>("a triangle", &triangle, &mut rng);
+    packets::<_, 8>("a triangle", &triangle, &mut rng);
+}
@@ -0,0 +1,216 @@
+use crate::direction3::Direction3;
+use crate::point3::Point3;
+use crate::ray::Ray;
+use crate::scalar::Real;
+
+/* axis-aligned bounding box, the empty box has m_min above m_max so any union replaces it */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub m_min: Point3<Real>,
+    pub m_max: Point3<Real>,
+}
+
+pub trait Instantiator {
+    fn into(self) -> Aabb;
+}
+
+/* Associative methods implementation block */
+impl Aabb {
+    pub fn is_empty(&self) -> bool {
+        return (0..3).any(|i| self.m_min[i] > self.m_max[i]);
+    }
+
+    pub fn union(&self, b: &Aabb) -> Aabb {
+        return Aabb {
+            m_min: self.m_min.min(&b.m_min),
+            m_max: self.m_max.max(&b.m_max),
+        };
+    }
+
+    pub fn union_point(&self, p: &Point3<Real>) -> Aabb {
+        return Aabb {
+            m_min: self.m_min.min(p),
+            m_max: self.m_max.max(p),
+        };
+    }
+
+    pub fn diagonal(&self) -> Direction3<Real> {
+        return self.m_max - self.m_min;
+    }
+
+    pub fn centroid(&self) -> Point3<Real> {
+        return self.m_min + self.diagonal() * 0.5;
+    }
+
+    /* 0 for empty boxes so they weigh nothing in the SAH */
+    pub fn surface_area(&self) -> Real {
+        if self.is_empty() {
+            return 0.0;
+        }
+        let d = self.diagonal();
+        return 2.0 * (d.get_x() * d.get_y() + d.get_y() * d.get_z() + d.get_z() * d.get_x());
+    }
+
+    /* axis along which the box is the longest */
+    pub fn max_extent(&self) -> usize {
+        let d = self.diagonal();
+        if d.get_x() > d.get_y() && d.get_x() > d.get_z() {
+            return 0;
+        }
+        if d.get_y() > d.get_z() {
+            return 1;
+        }
+        return 2;
+    }
+
+    /* position of p relative to the corners, 0 at m_min and 1 at m_max on each axis */
+    pub fn offset(&self, p: &Point3<Real>) -> Direction3<Real> {
+        let mut o = *p - self.m_min;
+        for i in 0..3 {
+            if self.m_max[i] > self.m_min[i] {
+                o.m_data[i] /= self.m_max[i] - self.m_min[i];
+            }
+        }
+        return o;
+    }
+
+    /* the eight corners */
+    pub fn corners(&self) -> [Point3<Real>; 8] {
+        return std::array::from_fn(|i| {
+            let pick = |axis: usize| {
+                if (i >> axis) & 1 == 0 {
+                    self.m_min[axis]
+                } else {
+                    self.m_max[axis]
+                }
+            };
+            Point3::new((pick(0), pick(1), pick(2)))
+        });
+    }
+
+    /* slab test, inv_direction is 1 / ray.m_direction per axis, computed once per ray. Axes the
+    ray is parallel to give infinities or NaN, which min and max skip */
+    pub fn hit(
+        &self,
+        ray: &Ray,
+        inv_direction: &Direction3<Real>,
+        t_min: Real,
+        t_max: Real,
+    ) -> bool {
+        let mut t0 = t_min;
+        let mut t1 = t_max;
+        for i in 0..3 {
+            let near = (self.m_min[i] - ray.m_origin[i]) * inv_direction[i];
+            let far = (self.m_max[i] - ray.m_origin[i]) * inv_direction[i];
+            t0 = t0.max(near.min(far));
+            t1 = t1.min(near.max(far));
+            if t0 > t1 {
+                return false;
+            }
+        }
+        return true;
+    }
+}
+
+/* Associative functions implementation block */
+impl Aabb {
+    /* Constructor */
+    pub fn new<A>(args: A) -> Aabb
+    where
+        A: Instantiator,
+    {
+        return args.into();
+    }
+
+    /* Static Constructors */
+    pub fn empty() -> Aabb {
+        return Aabb::new(());
+    }
+}
+
+impl Instantiator for () {
+    fn into(self) -> Aabb {
+        return Aabb {
+            m_min: Point3::new((Real::MAX, Real::MAX, Real::MAX)),
+            m_max: Point3::new((Real::MIN, Real::MIN, Real::MIN)),
+        };
+    }
+}
+
+/* the single point */
+impl Instantiator for &Point3<Real> {
+    fn into(self) -> Aabb {
+        return Aabb {
+            m_min: *self,
+            m_max: *self,
+        };
+    }
+}
+
+/* corners in any order */
+impl Instantiator for (&Point3<Real>, &Point3<Real>) {
+    fn into(self) -> Aabb {
+        return Aabb {
+            m_min: self.0.min(self.1),
+            m_max: self.0.max(self.1),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inverse(d: &Direction3<Real>) -> Direction3<Real> {
+        return Direction3::new((1.0 / d.get_x(), 1.0 / d.get_y(), 1.0 / d.get_z()));
+    }
+
+    #[test]
+    fn union_and_measures() {
+        let a = Aabb::new((&Point3::new((1.0, 1.0, 1.0)), &Point3::origin()));
+        let b = Aabb::empty().union_point(&Point3::new((2.0, 0.5, 0.5)));
+        let u = a.union(&b);
+        assert!(Aabb::empty().is_empty());
+        assert_eq!(Aabb::empty().union(&a), a);
+        assert_eq!(u.m_max, Point3::new((2.0, 1.0, 1.0)));
+        assert_eq!(u.surface_area(), 10.0);
+        assert_eq!(u.max_extent(), 0);
+        assert_eq!(u.centroid(), Point3::new((1.0, 0.5, 0.5)));
+        assert_eq!(
+            u.offset(&Point3::new((1.0, 0.25, 1.0))),
+            Direction3::new((0.5, 0.25, 1.0))
+        );
+        assert_eq!(Aabb::empty().surface_area(), 0.0);
+    }
+
+    #[test]
+    fn slab_test() {
+        let b = Aabb::new((
+            &Point3::new((-1.0, -1.0, -1.0)),
+            &Point3::new((1.0, 1.0, 1.0)),
+        ));
+        let hit = |origin: (Real, Real, Real), direction: (Real, Real, Real), t_max: Real| {
+            let ray = Ray::new(Point3::new(origin), Direction3::new(direction));
+            return b.hit(&ray, &inverse(&ray.m_direction), 0.0, t_max);
+        };
+        assert!(hit((0.0, 0.0, 5.0), (0.0, 0.0, -1.0), Real::MAX));
+        assert!(!hit((0.0, 0.0, 5.0), (0.0, 0.0, -1.0), 3.0));
+        assert!(!hit((0.0, 0.0, 5.0), (0.0, 0.0, 1.0), Real::MAX));
+        /* inside */
+        assert!(hit((0.0, 0.0, 0.0), (1.0, 0.0, 0.0), Real::MAX));
+        /* parallel to two slabs, inside and outside of them */
+        assert!(hit((-5.0, 0.5, 0.5), (1.0, 0.0, 0.0), Real::MAX));
+        assert!(!hit((-5.0, 1.5, 0.5), (1.0, 0.0, 0.0), Real::MAX));
+
+        /* a flat box, as around an axis aligned triangle */
+        let flat = Aabb::new((
+            &Point3::new((-1.0, -1.0, 0.0)),
+            &Point3::new((1.0, 1.0, 0.0)),
+        ));
+        let ray = Ray::new(
+            Point3::new((0.0, 0.0, 1.0)),
+            Direction3::new((0.0, 0.0, -1.0)),
+        );
+        assert!(flat.hit(&ray, &inverse(&ray.m_direction), 0.0, Real::MAX));
+    }
+}
@@ -0,0 +1,222 @@
+use crate::aabb::Aabb;
+use crate::hittable::{Hittable, HitRecord};
+use crate::normal3::Normal3;
+use crate::point3::Point3;
+use crate::ray::Ray;
+use crate::scalar::Real;
+use crate::triangle::Triangle;
+use crate::vector2::Vector2;
+
+/* axis-aligned box spanning m_min to m_max */
+#[derive(Debug)]
+pub struct AaBox {
+    pub m_min: Point3<Real>,
+    pub m_max: Point3<Real>,
+}
+
+/* Associative functions implementation block */
+impl AaBox {
+    /* Constructor, the corners may be given in any order */
+    pub fn new(a: Point3<Real>, b: Point3<Real>) -> AaBox {
+        return AaBox {
+            m_min: a.min(&b),
+            m_max: a.max(&b),
+        };
+    }
+}
+
+/* Associative methods implementation block */
+impl AaBox {
+    /* corner i takes the max coordinate on the axes of its set bits, x being bit 0 */
+    pub fn corner(&self, i: usize) -> Point3<Real> {
+        let pick = |axis: usize| match i >> axis & 1 {
+            0 => self.m_min.m_data[axis],
+            _ => self.m_max.m_data[axis],
+        };
+        return Point3::new((pick(0), pick(1), pick(2)));
+    }
+
+    /* two triangles per face, wound counter-clockwise seen from outside */
+    pub fn triangles(&self) -> Vec<Triangle> {
+        const FACES: [[usize; 4]; 6] = [
+            [0, 4, 6, 2],
+            [1, 3, 7, 5],
+            [0, 1, 5, 4],
+            [2, 6, 7, 3],
+            [0, 2, 3, 1],
+            [4, 5, 7, 6],
+        ];
+        return FACES
+            .iter()
+            .flat_map(|&[a, b, c, d]| {
+                let [a, b, c, d] = [a, b, c, d].map(|i| self.corner(i));
+                [Triangle::new(a, b, c), Triangle::new(a, c, d)]
+            })
+            .collect();
+    }
+}
+
+impl Hittable for AaBox {
+    /* slab method, keeping track of the axis each bound came from for the normal */
+    fn hit(&self, ray: &Ray, t_min: Real, t_max: Real) -> Option<HitRecord> {
+        let mut t_near = Real::NEG_INFINITY;
+        let mut t_far = Real::INFINITY;
+        let mut near_axis = 0;
+        let mut far_axis = 0;
+
+        for axis in 0..3 {
+            let origin = ray.m_origin.m_data[axis];
+            let direction = ray.m_direction.m_data[axis];
+            let min = self.m_min.m_data[axis];
+            let max = self.m_max.m_data[axis];
+
+            /* parallel to the slab, either always inside it or never */
+            if direction == 0.0 {
+                if origin < min || origin > max {
+                    return None;
+                }
+                continue;
+            }
+
+            let inv = 1.0 / direction;
+            let mut t0 = (min - origin) * inv;
+            let mut t1 = (max - origin) * inv;
+            if inv < 0.0 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+            if t0 > t_near {
+                t_near = t0;
+                near_axis = axis;
+            }
+            if t1 < t_far {
+                t_far = t1;
+                far_axis = axis;
+            }
+            if t_near > t_far {
+                return None;
+            }
+        }
+
+        /* take the entry point unless the ray starts inside the box */
+        let (t, axis) = if t_near >= t_min && t_near <= t_max {
+            (t_near, near_axis)
+        } else if t_far >= t_min && t_far <= t_max {
+            (t_far, far_axis)
+        } else {
+            return None;
+        };
+
+        let point = ray.at(t);
+        let mut outward_normal = Normal3::new((0.0, 0.0, 0.0));
+        let center = (self.m_min.m_data[axis] + self.m_max.m_data[axis]) * 0.5;
+        outward_normal.m_data[axis] = if point.m_data[axis] > center { 1.0 } else { -1.0 };
+
+        /* uv of the face, taken from the two remaining axes */
+        let (a, b) = ((axis + 1) % 3, (axis + 2) % 3);
+        let u = (point.m_data[a] - self.m_min.m_data[a]) / (self.m_max.m_data[a] - self.m_min.m_data[a]);
+        let v = (point.m_data[b] - self.m_min.m_data[b]) / (self.m_max.m_data[b] - self.m_min.m_data[b]);
+
+        return Some(HitRecord::new(ray, t, outward_normal, Vector2::new((u, v))));
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        return Some(Aabb::new((&self.m_min, &self.m_max)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::direction3::Direction3;
+
+    fn unit_box() -> AaBox {
+        return AaBox::new(
+            Point3::new((-1.0, -1.0, -1.0)),
+            Point3::new((1.0, 1.0, 1.0)),
+        );
+    }
+
+    #[test]
+    fn hit() {
+        let ray = Ray::new(
+            Point3::new((0.0, 0.0, 5.0)),
+            Direction3::new((0.0, 0.0, -1.0)),
+        );
+        let rec = unit_box().hit(&ray, 0.001, Real::MAX).unwrap();
+        assert!((rec.m_t - 4.0).abs() < 1e-5);
+        assert!(rec.m_front_face);
+        assert!((rec.m_normal.get_z() - 1.0).abs() < 1e-5);
+
+        let ray = Ray::new(
+            Point3::new((-5.0, 0.5, 0.0)),
+            Direction3::new((1.0, 0.0, 0.0)),
+        );
+        let rec = unit_box().hit(&ray, 0.001, Real::MAX).unwrap();
+        assert!((rec.m_normal.get_x() + 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn triangles() {
+        /* every face's triangles face away from the center and cover its 4 area */
+        let triangles = unit_box().triangles();
+        assert_eq!(triangles.len(), 12);
+        for t in &triangles {
+            let normal = (t.m_v1 - t.m_v0).cross(&(t.m_v2 - t.m_v0));
+            let center = (t.m_v0 - Point3::origin())
+                + (t.m_v1 - Point3::origin())
+                + (t.m_v2 - Point3::origin());
+            assert!(normal.dot(&center) > 0.0);
+            assert!((normal.magnitude() - 4.0).abs() < 1e-12);
+        }
+        assert_eq!(unit_box().corner(5), Point3::new((1.0, -1.0, 1.0)));
+    }
+
+    #[test]
+    fn hit_from_inside() {
+        let ray = Ray::new(Point3::origin(), Direction3::new((0.0, 1.0, 0.0)));
+        let rec = unit_box().hit(&ray, 0.001, Real::MAX).unwrap();
+        assert!((rec.m_t - 1.0).abs() < 1e-5);
+        assert!(!rec.m_front_face);
+        assert!((rec.m_normal.get_y() + 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn miss() {
+        let ray = Ray::new(
+            Point3::new((0.0, 2.0, 5.0)),
+            Direction3::new((0.0, 0.0, -1.0)),
+        );
+        assert!(unit_box().hit(&ray, 0.001, Real::MAX).is_none());
+        let ray = Ray::new(
+            Point3::new((0.0, 0.0, 5.0)),
+            Direction3::new((0.0, 0.0, 1.0)),
+        );
+        assert!(unit_box().hit(&ray, 0.001, Real::MAX).is_none());
+    }
+
+    #[test]
+    fn grazing() {
+        /* sliding along the top face */
+        let ray = Ray::new(
+            Point3::new((-5.0, 1.0, 0.0)),
+            Direction3::new((1.0, 0.0, 0.0)),
+        );
+        assert!(unit_box().hit(&ray, 0.001, Real::MAX).is_some());
+
+        /* just above the top face */
+        let ray = Ray::new(
+            Point3::new((-5.0, 1.001, 0.0)),
+            Direction3::new((1.0, 0.0, 0.0)),
+        );
+        assert!(unit_box().hit(&ray, 0.001, Real::MAX).is_none());
+
+        /* through an edge, diagonally */
+        let ray = Ray::new(
+            Point3::new((-2.0, 2.0, 0.0)),
+            Direction3::new((1.0, -1.0, 0.0)).unit(),
+        );
+        let rec = unit_box().hit(&ray, 0.001, Real::MAX).unwrap();
+        assert!((rec.m_point.get_x() + 1.0).abs() < 1e-5);
+        assert!((rec.m_point.get_y() - 1.0).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,368 @@
+use rand::Rng;
+use rand::rngs::StdRng;
+
+use crate::direction3::Direction3;
+use crate::hittable::HitRecord;
+use crate::light::{Light, LightSample};
+use crate::normal3::Normal3;
+use crate::point3::Point3;
+use crate::sampling::{Frame, unit_sphere};
+use crate::scalar::{Float, Real};
+use crate::triangle::Triangle;
+use crate::vector3::Vector3;
+
+/* emissive triangles, such as the ones of an emissive mesh or box, emitting m_radiance from
+their front faces */
+#[derive(Debug)]
+pub struct TriangleLight {
+    /* world space */
+    pub m_triangles: Vec<Triangle>,
+    /* W/(sr m^2) */
+    pub m_radiance: Vector3,
+    /* running sum of the triangle areas, the last one being the total */
+    m_areas: Vec<Real>,
+}
+
+/* emissive parallelogram spanned by two edges from a corner, emitting m_radiance toward
+m_u x m_v */
+#[derive(Debug)]
+pub struct QuadLight {
+    pub m_corner: Point3<Real>,
+    pub m_u: Direction3<Real>,
+    pub m_v: Direction3<Real>,
+    /* W/(sr m^2) */
+    pub m_radiance: Vector3,
+}
+
+/* emissive sphere, emitting m_radiance outward */
+#[derive(Debug)]
+pub struct SphereLight {
+    pub m_center: Point3<Real>,
+    pub m_radius: Real,
+    /* W/(sr m^2) */
+    pub m_radiance: Vector3,
+}
+
+/* Associative methods implementation block */
+impl TriangleLight {
+    pub fn area(&self) -> Real {
+        return self.m_areas.last().copied().unwrap_or(0.0);
+    }
+
+    /* triangles that make up the light's surface */
+    pub fn triangles(&self) -> &[Triangle] {
+        return &self.m_triangles;
+    }
+}
+
+/* Associative methods implementation block */
+impl QuadLight {
+    pub fn area(&self) -> Real {
+        return self.m_u.cross(&self.m_v).magnitude();
+    }
+
+    pub fn normal(&self) -> Normal3<Real> {
+        return Normal3::new(&self.m_u.cross(&self.m_v).unit());
+    }
+
+    /* the two triangles the quad is rendered as, facing the way it emits */
+    pub fn triangles(&self) -> [Triangle; 2] {
+        let (a, b) = (self.m_corner, self.m_corner + self.m_u);
+        let (c, d) = (b + self.m_v, self.m_corner + self.m_v);
+        return [Triangle::new(a, b, c), Triangle::new(a, c, d)];
+    }
+}
+
+/* Associative methods implementation block */
+impl SphereLight {
+    /* cosine of the half angle of the cone the sphere fills seen from a point at distance
+    squared d2 of its center, None from inside */
+    fn cone(&self, d2: Real) -> Option<Real> {
+        let r2 = self.m_radius * self.m_radius;
+        if d2 <= r2 {
+            return None;
+        }
+        return Some((1.0 - r2 / d2).max(0.0).sqrt());
+    }
+}
+
+/* Associative functions implementation block */
+impl TriangleLight {
+    /* Constructor, degenerate triangles are dropped, None without any triangle left */
+    pub fn new(triangles: Vec<Triangle>, radiance: Vector3) -> Option<TriangleLight> {
+        let triangles: Vec<Triangle> = triangles
+            .into_iter()
+            .filter(|t| triangle_area(t) > 0.0)
+            .collect();
+        if triangles.is_empty() {
+            return None;
+        }
+        let mut total = 0.0;
+        let areas = triangles
+            .iter()
+            .map(|t| {
+                total += triangle_area(t);
+                total
+            })
+            .collect();
+        return Some(TriangleLight {
+            m_triangles: triangles,
+            m_radiance: radiance,
+            m_areas: areas,
+        });
+    }
+}
+
+/* Associative functions implementation block */
+impl QuadLight {
+    /* Constructor */
+    pub fn new(
+        corner: Point3<Real>,
+        u: Direction3<Real>,
+        v: Direction3<Real>,
+        radiance: Vector3,
+    ) -> QuadLight {
+        return QuadLight {
+            m_corner: corner,
+            m_u: u,
+            m_v: v,
+            m_radiance: radiance,
+        };
+    }
+}
+
+/* Associative functions implementation block */
+impl SphereLight {
+    /* Constructor */
+    pub fn new(center: Point3<Real>, radius: Real, radiance: Vector3) -> SphereLight {
+        return SphereLight {
+            m_center: center,
+            m_radius: radius.abs(),
+            m_radiance: radiance,
+        };
+    }
+}
+
+impl Light for TriangleLight {
+    /* uniform over the area, triangles picked by their area */
+    fn sample(&self, point: &Point3<Real>, rng: &mut StdRng) -> Option<LightSample> {
+        let target = rng.gen::<Real>() * self.area();
+        let index = self
+            .m_areas
+            .partition_point(|&area| area <= target)
+            .min(self.m_triangles.len() - 1);
+        let triangle = &self.m_triangles[index];
+
+        /* folds the unit square onto the triangle */
+        let (mut u, mut v): (Real, Real) = (rng.gen(), rng.gen());
+        if u + v > 1.0 {
+            (u, v) = (1.0 - u, 1.0 - v);
+        }
+        let edge1 = triangle.m_v1 - triangle.m_v0;
+        let edge2 = triangle.m_v2 - triangle.m_v0;
+        let position = triangle.m_v0 + edge1 * u + edge2 * v;
+        let normal = Normal3::new(&edge1.cross(&edge2).unit());
+        return area_sample(point, &position, &normal, self.area(), self.m_radiance);
+    }
+
+    fn pdf(&self, origin: &Point3<Real>, rec: &HitRecord) -> Real {
+        return area_pdf(origin, rec, self.area());
+    }
+
+    fn power(&self) -> Real {
+        return Real::PI * self.area() * self.m_radiance.luminance() as Real;
+    }
+}
+
+impl Light for QuadLight {
+    fn sample(&self, point: &Point3<Real>, rng: &mut StdRng) -> Option<LightSample> {
+        let position = self.m_corner + self.m_u * rng.gen::<Real>() + self.m_v * rng.gen::<Real>();
+        return area_sample(
+            point,
+            &position,
+            &self.normal(),
+            self.area(),
+            self.m_radiance,
+        );
+    }
+
+    fn pdf(&self, origin: &Point3<Real>, rec: &HitRecord) -> Real {
+        return area_pdf(origin, rec, self.area());
+    }
+
+    fn power(&self) -> Real {
+        return Real::PI * self.area() * self.m_radiance.luminance() as Real;
+    }
+}
+
+impl Light for SphereLight {
+    /* uniform inside the cone of directions the sphere covers, or over its area from inside */
+    fn sample(&self, point: &Point3<Real>, rng: &mut StdRng) -> Option<LightSample> {
+        let to_center = self.m_center - *point;
+        let d2 = to_center.dot(&to_center);
+        let cos_max = match self.cone(d2) {
+            Some(cos_max) => cos_max,
+            None => {
+                let normal = Normal3::new(&unit_sphere(rng));
+                let position = self.m_center + Direction3::new(&normal) * self.m_radius;
+                let area = 4.0 * Real::PI * self.m_radius * self.m_radius;
+                return area_sample(point, &position, &normal, area, self.m_radiance);
+            }
+        };
+
+        let cos = 1.0 - rng.gen::<Real>() * (1.0 - cos_max);
+        let sin = (1.0 - cos * cos).max(0.0).sqrt();
+        let phi = 2.0 * Real::PI * rng.gen::<Real>();
+        let d = d2.sqrt();
+        let frame = Frame::new(&Normal3::new(&(to_center / d)));
+        let local = Direction3::new((sin * phi.cos(), sin * phi.sin(), cos));
+        let direction = frame.to_world(&local);
+        /* nearest intersection of the direction with the sphere */
+        let r2 = self.m_radius * self.m_radius;
+        let distance = d * cos - (r2 - d2 * sin * sin).max(0.0).sqrt();
+        return Some(LightSample {
+            m_direction: direction,
+            m_distance: distance,
+            m_radiance: self.m_radiance,
+            m_pdf: 1.0 / (2.0 * Real::PI * (1.0 - cos_max)),
+        });
+    }
+
+    fn pdf(&self, origin: &Point3<Real>, rec: &HitRecord) -> Real {
+        let to_center = self.m_center - *origin;
+        return match self.cone(to_center.dot(&to_center)) {
+            Some(cos_max) => 1.0 / (2.0 * Real::PI * (1.0 - cos_max)),
+            None => area_pdf(origin, rec, 4.0 * Real::PI * self.m_radius * self.m_radius),
+        };
+    }
+
+    fn power(&self) -> Real {
+        let area = 4.0 * Real::PI * self.m_radius * self.m_radius;
+        return Real::PI * area * self.m_radiance.luminance() as Real;
+    }
+}
+
+fn triangle_area(t: &Triangle) -> Real {
+    return 0.5 * (t.m_v1 - t.m_v0).cross(&(t.m_v2 - t.m_v0)).magnitude();
+}
+
+/* sample of a point picked uniformly over an area, None when point is behind it */
+fn area_sample(
+    point: &Point3<Real>,
+    position: &Point3<Real>,
+    normal: &Normal3<Real>,
+    area: Real,
+    radiance: Vector3,
+) -> Option<LightSample> {
+    let to_light = *position - *point;
+    let distance = to_light.magnitude();
+    if distance <= 0.0 {
+        return None;
+    }
+    let direction = to_light / distance;
+    let cos_light = -normal.dot(&direction);
+    if cos_light <= 0.0 {
+        return None;
+    }
+    return Some(LightSample {
+        m_direction: direction,
+        m_distance: distance,
+        m_radiance: radiance,
+        m_pdf: distance * distance / (cos_light * area),
+    });
+}
+
+/* solid angle density of uniform area sampling for the point rec found on the light */
+fn area_pdf(origin: &Point3<Real>, rec: &HitRecord, area: Real) -> Real {
+    let to_light = rec.m_point - *origin;
+    let d2 = to_light.dot(&to_light);
+    let cos_light = rec.m_normal.dot(&to_light).abs() / d2.sqrt();
+    if cos_light <= 0.0 {
+        return 0.0;
+    }
+    return d2 / (cos_light * area);
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::hittable::Hittable;
+    use crate::ray::Ray;
+    use crate::sphere::Sphere;
+
+    /* irradiance at the origin on a surface facing +y, and checks that every sample's density
+    is the one pdf gives for the point the sampled direction hits */
+    fn irradiance(light: &dyn Light, shape: &dyn Hittable, rng: &mut StdRng) -> Real {
+        let count = 40000;
+        let origin = Point3::origin();
+        let mut sum = 0.0;
+        for _ in 0..count {
+            let Some(sample) = light.sample(&origin, rng) else {
+                continue;
+            };
+            let rec = shape
+                .hit(&Ray::new(origin, sample.m_direction), 1e-6, Real::MAX)
+                .unwrap();
+            assert!((rec.m_t - sample.m_distance).abs() < 1e-6);
+            let pdf = light.pdf(&origin, &rec);
+            assert!((pdf - sample.m_pdf).abs() < 1e-6 * pdf);
+            let cos = sample.m_direction.get_y().max(0.0);
+            sum += sample.m_radiance.get_r() as Real * cos / sample.m_pdf;
+        }
+        return sum / count as Real;
+    }
+
+    #[test]
+    fn area_lights() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let white = Vector3::new((1.0, 1.0, 1.0));
+
+        /* a sphere of radius r at distance d lights a surface facing it with pi L (r / d)^2 */
+        let sphere = SphereLight::new(Point3::new((0.0, 2.0, 0.0)), 0.5, white);
+        let shape = Sphere::new(sphere.m_center, sphere.m_radius);
+        let expected = Real::PI * 0.25 / 4.0;
+        assert!((irradiance(&sphere, &shape, &mut rng) - expected).abs() < 0.01 * expected);
+        assert!((sphere.power() - 4.0 * Real::PI * Real::PI * 0.25).abs() < 1e-9);
+
+        /* the same square as a quad and as two triangles facing down */
+        let quad = QuadLight::new(
+            Point3::new((-0.5, 1.0, -0.5)),
+            Direction3::new((1.0, 0.0, 0.0)),
+            Direction3::new((0.0, 0.0, 1.0)),
+            white,
+        );
+        assert_eq!(quad.normal(), Normal3::new((0.0, -1.0, 0.0)));
+        assert_eq!(quad.area(), 1.0);
+        let [a, b] = quad.triangles();
+        let triangles = TriangleLight::new(
+            vec![
+                Triangle::new(a.m_v0, a.m_v1, a.m_v2),
+                Triangle::new(a.m_v0, a.m_v0, a.m_v2),
+                Triangle::new(b.m_v0, b.m_v1, b.m_v2),
+            ],
+            white,
+        )
+        .unwrap();
+        assert_eq!(triangles.triangles().len(), 2);
+        assert_eq!(triangles.area(), 1.0);
+        assert_eq!(triangles.power(), quad.power());
+
+        let shape: Vec<Box<dyn Hittable>> = quad
+            .triangles()
+            .into_iter()
+            .map(|t| Box::new(t) as Box<dyn Hittable>)
+            .collect();
+        let shape = crate::bvh::Bvh::new(shape);
+        let from_quad = irradiance(&quad, &shape, &mut rng);
+        let from_triangles = irradiance(&triangles, &shape, &mut rng);
+        assert!((from_quad - from_triangles).abs() < 0.01 * from_quad);
+
+        /* nothing reaches points behind the light */
+        let behind = Point3::new((0.0, 2.0, 0.0));
+        assert!(quad.sample(&behind, &mut rng).is_none());
+        assert!(triangles.sample(&behind, &mut rng).is_none());
+        assert!(TriangleLight::new(Vec::new(), white).is_none());
+    }
+}
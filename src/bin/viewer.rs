@@ -0,0 +1,196 @@
+#![allow(clippy::needless_return)]
+
+use std::time::Duration;
+
+use image::ColorType::Rgba8;
+use sdl2::event::Event;
+use sdl2::keyboard::Keycode;
+use sdl2::pixels::PixelFormatEnum;
+use sdl2::rect::Rect;
+
+use rt_rs::cli::{Options, USAGE};
+use rt_rs::direction3::Direction3;
+use rt_rs::logger;
+use rt_rs::renderer;
+use rt_rs::scene::Scene;
+
+fn main() {
+    let options = match Options::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            std::process::exit(2);
+        }
+    };
+    if options.m_help {
+        println!("{}", USAGE);
+        return;
+    }
+    logger::init(options.m_log_level);
+
+    sdl2(&options);
+}
+
+fn load_renderer(options: &Options) -> Option<renderer::Renderer> {
+    return match Scene::load(&options.m_scene) {
+        Ok(mut scene) => {
+            options.apply(&mut scene);
+            let mut s_renderer = renderer::Renderer::from_scene(&scene);
+            s_renderer.m_threads = options.m_threads;
+            s_renderer.m_tile_size = options.m_tile_size;
+            s_renderer.m_seed = options.m_seed;
+            Some(s_renderer)
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            None
+        }
+    };
+}
+
+/* translation, rotation and scale of every object of the scene file */
+fn print_transforms(options: &Options) {
+    let scene = match Scene::load(&options.m_scene) {
+        Ok(scene) => scene,
+        Err(e) => {
+            eprintln!("{}", e);
+            return;
+        }
+    };
+    for (i, object) in scene.m_objects.iter().enumerate() {
+        match object.transform().decompose() {
+            Some(transform) => println!("object {} ({}): {}", i, object.m_material, transform),
+            None => println!(
+                "object {} ({}): not an affine transform",
+                i, object.m_material
+            ),
+        }
+    }
+}
+
+/* progressive viewer, WASD/QE move the camera, R reloads the scene file, T prints the object transforms */
+fn sdl2(options: &Options) {
+    let mut s_renderer = match load_renderer(options) {
+        Some(s_renderer) => s_renderer,
+        None => std::process::exit(1),
+    };
+
+    // Initialize SDL2
+    let sdl_context = sdl2::init().unwrap();
+    let video_subsystem = sdl_context.video().unwrap();
+
+    let width = s_renderer.m_film.width();
+    let height = s_renderer.m_film.height();
+
+    // Create a window
+    let window = video_subsystem
+        .window("rt-rs", width, height)
+        .position_centered()
+        .build()
+        .unwrap();
+
+    // Create a canvas for rendering
+    let mut canvas = window.into_canvas().build().unwrap();
+
+    let mut raw_pixels: Vec<u8>;
+
+    // Main loop
+    let mut event_pump = sdl_context.event_pump().unwrap();
+
+    s_renderer.accumulate();
+    raw_pixels = s_renderer.image().into_raw();
+
+    'running: loop {
+        for event in event_pump.poll_iter() {
+            match event {
+                Event::Quit { .. }
+                | Event::KeyDown {
+                    keycode: Some(Keycode::Escape),
+                    ..
+                } => {
+                    /* save last image buffer to the output path before exiting */
+                    image::save_buffer(&options.m_output, &raw_pixels, width, height, Rgba8)
+                        .expect("COULDN'T SAVE IMAGE BUFFER BEFORE EXITING!");
+                    break 'running;
+                }
+                Event::KeyDown {
+                    keycode: Some(Keycode::R),
+                    ..
+                } => {
+                    /* keep the current scene if the file is broken */
+                    if let Some(reloaded) = load_renderer(options) {
+                        if reloaded.m_film.m_resolution == s_renderer.m_film.m_resolution {
+                            s_renderer = reloaded;
+                        } else {
+                            eprintln!("image size changed, restart to apply it");
+                        }
+                    }
+                }
+                Event::KeyDown {
+                    keycode: Some(Keycode::T),
+                    ..
+                } => print_transforms(options),
+                Event::KeyDown {
+                    keycode: Some(keycode),
+                    ..
+                } => {
+                    let step = 0.1;
+                    let offset = match keycode {
+                        Keycode::W => (0.0, 0.0, -step),
+                        Keycode::S => (0.0, 0.0, step),
+                        Keycode::A => (-step, 0.0, 0.0),
+                        Keycode::D => (step, 0.0, 0.0),
+                        Keycode::Q => (0.0, -step, 0.0),
+                        Keycode::E => (0.0, step, 0.0),
+                        _ => continue,
+                    };
+                    s_renderer.m_camera.translate(&Direction3::new(offset));
+                    s_renderer.reset();
+                }
+                _ => { /* do nothing */ }
+            }
+        }
+
+        s_renderer.accumulate();
+        raw_pixels = s_renderer.image().into_raw();
+        let surface = sdl2::surface::Surface::from_data(
+            &mut raw_pixels,
+            width,
+            height,
+            width * PixelFormatEnum::RGBA32.byte_size_per_pixel() as u32,
+            PixelFormatEnum::RGBA32,
+        )
+        .unwrap();
+        let texture_creator = canvas.texture_creator();
+        let texture = texture_creator
+            .create_texture_from_surface(surface)
+            .unwrap();
+
+        // Clear the canvas
+        canvas.set_draw_color(sdl2::pixels::Color::RGB(0, 0, 0));
+        canvas.clear();
+
+        // Draw the texture to the canvas
+        canvas
+            .copy(&texture, None, Some(Rect::new(0, 0, width, height)))
+            .unwrap();
+
+        // Report progress in the title bar
+        let samples_per_second = (width * height) as f64 / s_renderer.m_stats.m_total.as_secs_f64();
+        canvas
+            .window_mut()
+            .set_title(&format!(
+                "rt-rs - {} spp - {:.2} Msamples/s",
+                s_renderer.m_film.m_sample_count,
+                samples_per_second / 1_000_000.0
+            ))
+            .unwrap();
+        log::debug!("Render: {}", s_renderer.m_stats.summary());
+
+        // Update the window
+        canvas.present();
+
+        // Add a slight delay to avoid consuming too much CPU
+        std::thread::sleep(Duration::new(0, 1_000_000_000u32 / 240));
+    }
+}
@@ -0,0 +1,144 @@
+use crate::point2i::Point2i;
+
+/* half-open rectangle of pixels, m_min is inside and m_max is one past the last row and column */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Bounds2i {
+    pub m_min: Point2i,
+    pub m_max: Point2i,
+}
+
+pub trait Instantiator {
+    fn into(self) -> Bounds2i;
+}
+
+/* Associative methods implementation block */
+impl Bounds2i {
+    pub fn width(&self) -> i32 {
+        return (self.m_max.get_x() - self.m_min.get_x()).max(0);
+    }
+
+    pub fn height(&self) -> i32 {
+        return (self.m_max.get_y() - self.m_min.get_y()).max(0);
+    }
+
+    pub fn area(&self) -> usize {
+        return self.width() as usize * self.height() as usize;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.width() == 0 || self.height() == 0;
+    }
+
+    pub fn contains(&self, p: &Point2i) -> bool {
+        return p.get_x() >= self.m_min.get_x()
+            && p.get_x() < self.m_max.get_x()
+            && p.get_y() >= self.m_min.get_y()
+            && p.get_y() < self.m_max.get_y();
+    }
+
+    /* overlap of both bounds, empty when they are disjoint */
+    pub fn intersect(&self, b: &Bounds2i) -> Bounds2i {
+        let min = self.m_min.max(&b.m_min);
+        return Bounds2i {
+            m_min: min,
+            m_max: self.m_max.min(&b.m_max).max(&min),
+        };
+    }
+
+    /* row-major offset of p, which must be inside the bounds */
+    pub fn offset(&self, p: &Point2i) -> usize {
+        let d = *p - self.m_min;
+        return d.get_y() as usize * self.width() as usize + d.get_x() as usize;
+    }
+
+    /* every pixel in row-major order */
+    pub fn points(&self) -> impl Iterator<Item = Point2i> {
+        let (min, max) = (self.m_min, self.m_max);
+        return (min.get_y()..max.get_y())
+            .flat_map(move |y| (min.get_x()..max.get_x()).map(move |x| Point2i::new((x, y))));
+    }
+
+    /* splits the bounds row by row into tiles of at most size x size pixels */
+    pub fn tiles(&self, size: u32) -> Vec<Bounds2i> {
+        let size = size.max(1) as i32;
+        let mut tiles = Vec::new();
+        for y in (self.m_min.get_y()..self.m_max.get_y()).step_by(size as usize) {
+            for x in (self.m_min.get_x()..self.m_max.get_x()).step_by(size as usize) {
+                let min = Point2i::new((x, y));
+                tiles.push(Bounds2i::new((
+                    &min,
+                    &(min + Point2i::new((size, size))).min(&self.m_max),
+                )));
+            }
+        }
+        return tiles;
+    }
+}
+
+/* Associative functions implementation block */
+impl Bounds2i {
+    /* Constructor */
+    pub fn new<A>(args: A) -> Bounds2i
+    where
+        A: Instantiator,
+    {
+        return args.into();
+    }
+}
+
+/* corners in any order */
+impl Instantiator for (&Point2i, &Point2i) {
+    fn into(self) -> Bounds2i {
+        return Bounds2i {
+            m_min: self.0.min(self.1),
+            m_max: self.0.max(self.1),
+        };
+    }
+}
+
+/* the whole image of the given resolution */
+impl Instantiator for &Point2i {
+    fn into(self) -> Bounds2i {
+        return Bounds2i {
+            m_min: Point2i::zero(),
+            m_max: self.max(&Point2i::zero()),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tiles_cover_bounds() {
+        let bounds = Bounds2i::new(&Point2i::new((100, 70)));
+        let tiles = bounds.tiles(32);
+        assert_eq!(tiles.len(), 4 * 3);
+        let area: usize = tiles.iter().map(|t| t.area()).sum();
+        assert_eq!(area, 100 * 70);
+        assert_eq!(
+            *tiles.last().unwrap(),
+            Bounds2i::new((&Point2i::new((96, 64)), &Point2i::new((100, 70))))
+        );
+    }
+
+    #[test]
+    fn intersect_and_points() {
+        let a = Bounds2i::new((&Point2i::new((4, 4)), &Point2i::new((0, 0))));
+        let b = Bounds2i::new((&Point2i::new((2, 3)), &Point2i::new((6, 8))));
+        let overlap = a.intersect(&b);
+        assert_eq!((overlap.width(), overlap.height()), (2, 1));
+        assert!(overlap.contains(&Point2i::new((3, 3))));
+        assert!(!overlap.contains(&Point2i::new((4, 3))));
+
+        let points: Vec<Point2i> = a.points().collect();
+        assert_eq!(points.len(), a.area());
+        for (i, p) in points.iter().enumerate() {
+            assert_eq!(a.offset(p), i);
+        }
+
+        let far = Bounds2i::new((&Point2i::new((10, 10)), &Point2i::new((12, 12))));
+        assert!(a.intersect(&far).is_empty());
+    }
+}
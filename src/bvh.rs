@@ -0,0 +1,492 @@
+use crate::aabb::Aabb;
+use crate::direction3::Direction3;
+use crate::hittable::{HitRecord, Hittable, Object};
+use crate::point3::Point3;
+use crate::ray::Ray;
+use crate::scalar::Real;
+
+/* cost of visiting a node relative to intersecting one primitive */
+const TRAVERSAL_COST: Real = 0.125;
+/* the SAH is evaluated between this many buckets along the split axis */
+const BUCKETS: usize = 12;
+const MAX_LEAF_SIZE: usize = 4;
+/* deeper nodes become leaves whatever their size, bounds the traversal stack */
+const MAX_DEPTH: usize = 64;
+
+/* node of the flattened tree, stored depth first so the first child of an interior node directly
+follows it and only the second one needs an index */
+#[derive(Debug, Clone, Copy)]
+pub struct BvhNode {
+    pub m_bounds: Aabb,
+    /* first primitive of a leaf, second child of an interior node */
+    pub m_offset: u32,
+    /* primitives in a leaf, 0 for interior nodes */
+    pub m_count: u32,
+    /* split axis of an interior node, rays going down it visit the second child first */
+    pub m_axis: u8,
+}
+
+/* shape of the tree, logged when a scene is built */
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct BvhStats {
+    pub m_node_count: usize,
+    pub m_leaf_count: usize,
+    pub m_depth: usize,
+    /* expected cost of a ray through the tree in primitive intersections, n without it */
+    pub m_sah_cost: Real,
+    /* primitives without bounds, tested against every ray */
+    pub m_unbounded: usize,
+}
+
+/* bounding volume hierarchy over any hittable primitives, itself hittable so meshes can nest one
+inside the scene's */
+pub struct Bvh<P: Hittable = Object> {
+    m_nodes: Vec<BvhNode>,
+    /* in leaf order, each leaf refers to a contiguous range */
+    m_primitives: Vec<P>,
+    m_unbounded: Vec<P>,
+    m_stats: BvhStats,
+}
+
+/* what the build needs to know about a primitive */
+struct BuildPrimitive {
+    m_index: usize,
+    m_bounds: Aabb,
+    m_centroid: Point3<Real>,
+}
+
+/* Associative methods implementation block */
+impl<P: Hittable> Bvh<P> {
+    pub fn stats(&self) -> &BvhStats {
+        return &self.m_stats;
+    }
+
+    pub fn nodes(&self) -> &[BvhNode] {
+        return &self.m_nodes;
+    }
+
+    /* bounded and unbounded primitives */
+    pub fn len(&self) -> usize {
+        return self.m_primitives.len() + self.m_unbounded.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.len() == 0;
+    }
+
+    /* builds the subtree over primitives and returns the index of its root */
+    fn build(
+        &mut self,
+        primitives: &mut [BuildPrimitive],
+        depth: usize,
+        order: &mut Vec<usize>,
+    ) -> usize {
+        let bounds = primitives
+            .iter()
+            .fold(Aabb::empty(), |b, p| b.union(&p.m_bounds));
+        let index = self.m_nodes.len();
+        self.m_nodes.push(BvhNode {
+            m_bounds: bounds,
+            m_offset: order.len() as u32,
+            m_count: 0,
+            m_axis: 0,
+        });
+        self.m_stats.m_node_count += 1;
+        self.m_stats.m_depth = self.m_stats.m_depth.max(depth);
+
+        match Self::split(primitives, &bounds, depth) {
+            Some((axis, mid)) => {
+                let (first, second) = primitives.split_at_mut(mid);
+                self.build(first, depth + 1, order);
+                let second = self.build(second, depth + 1, order);
+                self.m_nodes[index].m_offset = second as u32;
+                self.m_nodes[index].m_axis = axis as u8;
+            }
+            None => {
+                order.extend(primitives.iter().map(|p| p.m_index));
+                self.m_nodes[index].m_count = primitives.len() as u32;
+                self.m_stats.m_leaf_count += 1;
+            }
+        }
+        return index;
+    }
+
+    /* expected cost of the whole tree, each node weighted by the chance a ray through the root
+    also goes through it */
+    fn sah_cost(&self) -> Real {
+        let root = match self.m_nodes.first() {
+            Some(root) => root.m_bounds.surface_area(),
+            None => return 0.0,
+        };
+        if root <= 0.0 {
+            return self.m_primitives.len() as Real;
+        }
+        return self
+            .m_nodes
+            .iter()
+            .map(|node| {
+                let cost = if node.m_count > 0 {
+                    node.m_count as Real
+                } else {
+                    TRAVERSAL_COST
+                };
+                cost * node.m_bounds.surface_area() / root
+            })
+            .sum();
+    }
+}
+
+/* Associative functions implementation block */
+impl<P: Hittable> Bvh<P> {
+    /* Constructor */
+    pub fn new(primitives: Vec<P>) -> Bvh<P> {
+        let mut bounded: Vec<Option<P>> = Vec::with_capacity(primitives.len());
+        let mut unbounded = Vec::new();
+        let mut build = Vec::with_capacity(primitives.len());
+        for primitive in primitives {
+            match primitive.bounds() {
+                Some(bounds) => {
+                    build.push(BuildPrimitive {
+                        m_index: bounded.len(),
+                        m_bounds: bounds,
+                        m_centroid: bounds.centroid(),
+                    });
+                    bounded.push(Some(primitive));
+                }
+                None => unbounded.push(primitive),
+            }
+        }
+
+        let mut bvh = Bvh {
+            m_nodes: Vec::with_capacity(2 * build.len()),
+            m_primitives: Vec::with_capacity(build.len()),
+            m_stats: BvhStats {
+                m_unbounded: unbounded.len(),
+                ..BvhStats::default()
+            },
+            m_unbounded: unbounded,
+        };
+        let mut order = Vec::with_capacity(build.len());
+        if !build.is_empty() {
+            bvh.build(&mut build, 1, &mut order);
+        }
+        bvh.m_primitives = order
+            .into_iter()
+            .map(|i| bounded[i].take().unwrap())
+            .collect();
+        bvh.m_stats.m_sah_cost = bvh.sah_cost();
+        return bvh;
+    }
+
+    /* axis and position where to split the primitives, partitioning them around it, None when
+    a leaf is cheaper */
+    fn split(
+        primitives: &mut [BuildPrimitive],
+        bounds: &Aabb,
+        depth: usize,
+    ) -> Option<(usize, usize)> {
+        let n = primitives.len();
+        if n == 1 || depth >= MAX_DEPTH {
+            return None;
+        }
+
+        let centroids = primitives
+            .iter()
+            .fold(Aabb::empty(), |b, p| b.union_point(&p.m_centroid));
+        let axis = centroids.max_extent();
+        let (lo, hi) = (centroids.m_min[axis], centroids.m_max[axis]);
+        /* every centroid in the same place, nothing to sort by */
+        if hi <= lo {
+            if n <= MAX_LEAF_SIZE {
+                return None;
+            }
+            return Some((axis, n / 2));
+        }
+
+        let bucket = |p: &BuildPrimitive| {
+            let b = (p.m_centroid[axis] - lo) / (hi - lo) * BUCKETS as Real;
+            return (b as usize).min(BUCKETS - 1);
+        };
+        let mut counts = [0usize; BUCKETS];
+        let mut boxes = [Aabb::empty(); BUCKETS];
+        for p in primitives.iter() {
+            let b = bucket(p);
+            counts[b] += 1;
+            boxes[b] = boxes[b].union(&p.m_bounds);
+        }
+
+        /* cost of splitting after each bucket, sweeping from both ends. The first and the last
+        bucket hold a centroid each so both sides are never empty */
+        let mut below = [(0usize, Aabb::empty()); BUCKETS];
+        let mut acc = (0, Aabb::empty());
+        for b in 0..BUCKETS {
+            acc = (acc.0 + counts[b], acc.1.union(&boxes[b]));
+            below[b] = acc;
+        }
+        let area = bounds.surface_area().max(Real::MIN_POSITIVE);
+        let mut best = (Real::MAX, 0);
+        let mut above = (0, Aabb::empty());
+        for b in (0..BUCKETS - 1).rev() {
+            above = (above.0 + counts[b + 1], above.1.union(&boxes[b + 1]));
+            let cost = TRAVERSAL_COST
+                + (below[b].0 as Real * below[b].1.surface_area()
+                    + above.0 as Real * above.1.surface_area())
+                    / area;
+            if cost < best.0 {
+                best = (cost, b);
+            }
+        }
+
+        if n <= MAX_LEAF_SIZE && n as Real <= best.0 {
+            return None;
+        }
+
+        let mut mid = 0;
+        for i in 0..n {
+            if bucket(&primitives[i]) <= best.1 {
+                primitives.swap(i, mid);
+                mid += 1;
+            }
+        }
+        return Some((axis, mid));
+    }
+}
+
+/* Associative methods implementation block */
+impl BvhStats {
+    pub fn summary(&self) -> String {
+        return format!(
+            "{} nodes, {} leaves, depth {}, SAH cost {:.2}, {} unbounded",
+            self.m_node_count, self.m_leaf_count, self.m_depth, self.m_sah_cost, self.m_unbounded
+        );
+    }
+}
+
+impl<P: Hittable> Hittable for Bvh<P> {
+    /* front to back with a small stack, skipping nodes farther than the closest hit so far */
+    fn hit(&self, ray: &Ray, t_min: Real, t_max: Real) -> Option<HitRecord> {
+        let mut closest: Option<HitRecord> = None;
+        let mut closest_t = t_max;
+        for primitive in &self.m_unbounded {
+            if let Some(rec) = primitive.hit(ray, t_min, closest_t) {
+                closest_t = rec.m_t;
+                closest = Some(rec);
+            }
+        }
+        if self.m_nodes.is_empty() {
+            return closest;
+        }
+
+        let d = &ray.m_direction;
+        let inv_direction = Direction3::new((1.0 / d.get_x(), 1.0 / d.get_y(), 1.0 / d.get_z()));
+        let negative = inv_direction.m_data.map(|v| v < 0.0);
+
+        let mut stack = [0usize; MAX_DEPTH];
+        let mut top = 0;
+        let mut current = 0;
+        loop {
+            let node = &self.m_nodes[current];
+            if node.m_bounds.hit(ray, &inv_direction, t_min, closest_t) {
+                if node.m_count > 0 {
+                    let first = node.m_offset as usize;
+                    for primitive in &self.m_primitives[first..first + node.m_count as usize] {
+                        if let Some(rec) = primitive.hit(ray, t_min, closest_t) {
+                            closest_t = rec.m_t;
+                            closest = Some(rec);
+                        }
+                    }
+                } else {
+                    /* the child on the side the ray comes from first, the other one later */
+                    let (near, far) = if negative[node.m_axis as usize] {
+                        (node.m_offset as usize, current + 1)
+                    } else {
+                        (current + 1, node.m_offset as usize)
+                    };
+                    stack[top] = far;
+                    top += 1;
+                    current = near;
+                    continue;
+                }
+            }
+            if top == 0 {
+                break;
+            }
+            top -= 1;
+            current = stack[top];
+        }
+        return closest;
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        if !self.m_unbounded.is_empty() {
+            return None;
+        }
+        return self.m_nodes.first().map(|root| root.m_bounds);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use super::*;
+    use crate::aabox::AaBox;
+    use crate::disk::Disk;
+    use crate::instance::Instance;
+    use crate::matrix4::Matrix4;
+    use crate::normal3::Normal3;
+    use crate::plane::Plane;
+    use crate::sphere::Sphere;
+    use crate::triangle::Triangle;
+
+    /* the same random scene for a given seed, the material index identifies the object */
+    fn scene(seed: u64, n: usize) -> Vec<Object> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut point = |range: Real| {
+            Point3::new((
+                rng.gen_range(-range..range),
+                rng.gen_range(-range..range),
+                rng.gen_range(-range..range),
+            ))
+        };
+        let mut objects = Vec::new();
+        for i in 0..n {
+            let center = point(20.0);
+            let offset = point(1.0) - Point3::origin();
+            let other = point(1.0) - Point3::origin();
+            let shape: Box<dyn Hittable> = match i % 5 {
+                0 => Box::new(Sphere::new(center, offset.magnitude() * 0.5 + 0.05)),
+                1 => Box::new(Triangle::new(center, center + offset, center + other)),
+                2 => Box::new(Disk::new(center, Normal3::new(&offset), 0.7)),
+                3 => Box::new(AaBox::new(center, center + offset)),
+                _ => Box::new(
+                    Instance::new(
+                        Box::new(Sphere::new(Point3::origin(), 0.5)),
+                        Matrix4::translate(center.get_x(), center.get_y(), center.get_z())
+                            * Matrix4::rotate_z(30.0)
+                            * Matrix4::scale(2.0, 0.5, 1.0),
+                    )
+                    .unwrap(),
+                ),
+            };
+            objects.push(Object::new(shape, i));
+        }
+        return objects;
+    }
+
+    fn brute_force(objects: &[Object], ray: &Ray) -> Option<HitRecord> {
+        let mut closest: Option<HitRecord> = None;
+        let mut closest_t = Real::MAX;
+        for object in objects {
+            if let Some(rec) = object.hit(ray, 0.001, closest_t) {
+                closest_t = rec.m_t;
+                closest = Some(rec);
+            }
+        }
+        return closest;
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        for (seed, n) in [(1, 1), (2, 7), (3, 100), (4, 1000)] {
+            let mut objects = scene(seed, n);
+            let mut reference = scene(seed, n);
+            /* a floor, outside of the tree */
+            objects.push(Object::new(
+                Box::new(Plane::new(
+                    Point3::new((0.0, -25.0, 0.0)),
+                    Normal3::new((0.0, 1.0, 0.0)),
+                )),
+                n,
+            ));
+            reference.push(Object::new(
+                Box::new(Plane::new(
+                    Point3::new((0.0, -25.0, 0.0)),
+                    Normal3::new((0.0, 1.0, 0.0)),
+                )),
+                n,
+            ));
+            let bvh = Bvh::new(objects);
+            assert_eq!(bvh.len(), n + 1);
+
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut hits = 0;
+            for _ in 0..2000 {
+                let mut value = |range: Real| rng.gen_range(-range..range);
+                let ray = Ray::new(
+                    Point3::new((value(30.0), value(30.0), value(30.0))),
+                    Direction3::new((value(1.0), value(1.0), value(1.0))).unit(),
+                );
+                match (
+                    bvh.hit(&ray, 0.001, Real::MAX),
+                    brute_force(&reference, &ray),
+                ) {
+                    (Some(a), Some(b)) => {
+                        assert_eq!(a.m_material, b.m_material);
+                        assert_eq!(a.m_t, b.m_t);
+                        assert_eq!(a.m_normal, b.m_normal);
+                        hits += 1;
+                    }
+                    (None, None) => {}
+                    (a, b) => panic!("{:?}: {:?} and {:?}", ray, a, b),
+                }
+            }
+            assert!(hits > 0);
+        }
+    }
+
+    #[test]
+    fn stats() {
+        let bvh = Bvh::new(scene(5, 1000));
+        let stats = bvh.stats();
+        assert_eq!(stats.m_node_count, bvh.nodes().len());
+        assert_eq!(stats.m_node_count, 2 * stats.m_leaf_count - 1);
+        assert!(stats.m_depth > 1 && stats.m_depth <= MAX_DEPTH);
+        /* far better than testing all of them */
+        assert!(stats.m_sah_cost < 100.0, "{}", stats.summary());
+        assert_eq!(stats.m_unbounded, 0);
+        assert!(bvh.bounds().is_some());
+
+        let empty: Bvh = Bvh::new(Vec::new());
+        assert!(empty.is_empty());
+        let ray = Ray::new(Point3::origin(), Direction3::new((0.0, 0.0, 1.0)));
+        assert!(empty.hit(&ray, 0.001, Real::MAX).is_none());
+    }
+
+    #[test]
+    fn coincident_centroids() {
+        let spheres = (0..100)
+            .map(|i| Object::new(Box::new(Sphere::new(Point3::origin(), 1.0 + i as Real)), i))
+            .collect();
+        let bvh = Bvh::new(spheres);
+        let ray = Ray::new(
+            Point3::new((0.0, 0.0, 500.0)),
+            Direction3::new((0.0, 0.0, -1.0)),
+        );
+        let rec = bvh.hit(&ray, 0.001, Real::MAX).unwrap();
+        assert_eq!(rec.m_material, 99);
+        assert!(bvh.stats().m_depth < MAX_DEPTH);
+    }
+
+    #[test]
+    fn large_leaf_at_depth_limit() {
+        /* more primitives than a u16 holds, all forced into one leaf */
+        let count = u16::MAX as usize + 2;
+        let mut primitives: Vec<BuildPrimitive> = (0..count)
+            .map(|i| {
+                let center = Point3::new((i as Real, 0.0, 0.0));
+                return BuildPrimitive {
+                    m_index: i,
+                    m_bounds: Aabb::new(&center),
+                    m_centroid: center,
+                };
+            })
+            .collect();
+        let mut bvh: Bvh<Object> = Bvh::new(Vec::new());
+        let mut order = Vec::new();
+        let root = bvh.build(&mut primitives, MAX_DEPTH, &mut order);
+        assert_eq!(bvh.nodes()[root].m_count as usize, count);
+        assert_eq!(order.len(), count);
+    }
+}
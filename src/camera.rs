@@ -0,0 +1,190 @@
+use crate::direction3::Direction3;
+use crate::matrix4::Matrix4;
+use crate::point3::Point3;
+use crate::ray::Ray;
+use crate::scalar::Real;
+use crate::vector2::Vector2;
+use crate::vector4::Vector4;
+
+/* depth range of the projections built by the convenience constructors */
+pub const NEAR: Real = 0.001;
+pub const FAR: Real = 1000.0;
+
+/* generates primary rays by unprojecting image plane positions through a view and a projection matrix */
+#[derive(Debug)]
+pub struct Camera {
+    /* world to camera, see Matrix4::look_at */
+    pub m_view: Matrix4<Real>,
+    /* camera to clip space, see Matrix4::perspective and Matrix4::orthographic */
+    pub m_projection: Matrix4<Real>,
+    m_camera_to_world: Matrix4<Real>,
+    m_clip_to_camera: Matrix4<Real>,
+}
+
+/* Associative methods implementation block */
+impl Camera {
+    /* uv is in [0, 1]^2, (0, 0) being the bottom left corner of the image plane */
+    pub fn get_ray(&self, uv: &Vector2<Real>) -> Ray {
+        let x = 2.0 * uv.get_u() - 1.0;
+        let y = 2.0 * uv.get_v() - 1.0;
+        let near = self.unproject(x, y, -1.0);
+        let far = self.unproject(x, y, 1.0);
+        return Ray::new(
+            self.m_camera_to_world.transform_point(&near),
+            self.m_camera_to_world
+                .transform_direction(&(far - near))
+                .unit(),
+        );
+    }
+
+    /* moves the camera without changing where it looks */
+    pub fn translate(&mut self, offset: &Direction3<Real>) {
+        self.m_camera_to_world = Matrix4::translate(offset.get_x(), offset.get_y(), offset.get_z())
+            * self.m_camera_to_world;
+        self.m_view *= Matrix4::translate(-offset.get_x(), -offset.get_y(), -offset.get_z());
+    }
+
+    /* position of the camera in the world */
+    pub fn origin(&self) -> Point3<Real> {
+        return self.m_camera_to_world.transform_point(&Point3::origin());
+    }
+
+    /* camera space point at the normalized device coordinates x, y, z */
+    fn unproject(&self, x: Real, y: Real, z: Real) -> Point3<Real> {
+        return Point3::new(&(self.m_clip_to_camera * Vector4::new((x, y, z, 1.0))));
+    }
+}
+
+/* Associative functions implementation block */
+impl Camera {
+    /* Constructor, None when either matrix is singular */
+    pub fn new(view: Matrix4<Real>, projection: Matrix4<Real>) -> Option<Camera> {
+        return Some(Camera {
+            m_view: view,
+            m_projection: projection,
+            m_camera_to_world: view.inverse()?,
+            m_clip_to_camera: projection.inverse()?,
+        });
+    }
+
+    /* fov is the vertical field of view in degrees */
+    pub fn perspective(
+        eye: &Point3<Real>,
+        target: &Point3<Real>,
+        up: &Direction3<Real>,
+        fov: Real,
+        aspect: Real,
+    ) -> Option<Camera> {
+        return Camera::new(
+            Matrix4::look_at(eye, target, up),
+            Matrix4::perspective(fov, aspect, NEAR, FAR),
+        );
+    }
+
+    /* height is the extent of the view in world units, rays are all parallel */
+    pub fn orthographic(
+        eye: &Point3<Real>,
+        target: &Point3<Real>,
+        up: &Direction3<Real>,
+        height: Real,
+        aspect: Real,
+    ) -> Option<Camera> {
+        let half_height = height / 2.0;
+        let half_width = aspect * half_height;
+        return Camera::new(
+            Matrix4::look_at(eye, target, up),
+            Matrix4::orthographic(
+                -half_width,
+                half_width,
+                -half_height,
+                half_height,
+                NEAR,
+                FAR,
+            ),
+        );
+    }
+}
+
+impl Default for Camera {
+    /* at the origin looking down -z with a 90 degree square view */
+    fn default() -> Camera {
+        return Camera::perspective(
+            &Point3::origin(),
+            &Point3::new((0.0, 0.0, -1.0)),
+            &Direction3::new((0.0, 1.0, 0.0)),
+            90.0,
+            1.0,
+        )
+        .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn perspective_rays() {
+        let eye = Point3::new((0.0, 1.0, 5.0));
+        let up = Direction3::new((0.0, 1.0, 0.0));
+        let camera =
+            Camera::perspective(&eye, &Point3::new((0.0, 1.0, 0.0)), &up, 90.0, 2.0).unwrap();
+
+        let ray = camera.get_ray(&Vector2::new((0.5, 0.5)));
+        assert!(ray.m_origin.approx_eq(&eye, 1e-2));
+        assert!(
+            ray.m_direction
+                .approx_eq(&Direction3::new((0.0, 0.0, -1.0)), 1e-5)
+        );
+
+        /* a 90 degree vertical fov puts the top edge at 45 degrees, the right edge twice as far out */
+        let ray = camera.get_ray(&Vector2::new((1.0, 1.0)));
+        assert!(
+            ray.m_direction
+                .approx_eq(&Direction3::new((2.0, 1.0, -1.0)).unit(), 1e-4)
+        );
+        assert!(camera.origin().approx_eq(&eye, 1e-5));
+    }
+
+    #[test]
+    fn orthographic_rays() {
+        let eye = Point3::new((0.0, 0.0, 0.0));
+        let up = Direction3::new((0.0, 0.0, -1.0));
+        /* looking straight down */
+        let camera =
+            Camera::orthographic(&eye, &Point3::new((0.0, -1.0, 0.0)), &up, 4.0, 1.0).unwrap();
+
+        let a = camera.get_ray(&Vector2::new((0.0, 0.0)));
+        let b = camera.get_ray(&Vector2::new((1.0, 1.0)));
+        assert!(
+            a.m_direction
+                .approx_eq(&Direction3::new((0.0, -1.0, 0.0)), 1e-5)
+        );
+        assert!(b.m_direction.approx_eq(&a.m_direction, 1e-5));
+        assert!((b.m_origin - a.m_origin).approx_eq(&Direction3::new((4.0, 0.0, -4.0)), 1e-4));
+    }
+
+    #[test]
+    fn translate() {
+        let mut camera = Camera::default();
+        camera.translate(&Direction3::new((1.0, 2.0, 3.0)));
+        assert!(
+            camera
+                .origin()
+                .approx_eq(&Point3::new((1.0, 2.0, 3.0)), 1e-5)
+        );
+        assert!(
+            camera
+                .get_ray(&Vector2::new((0.5, 0.5)))
+                .m_direction
+                .approx_eq(&Direction3::new((0.0, 0.0, -1.0)), 1e-5)
+        );
+        assert!(
+            camera
+                .m_view
+                .transform_point(&Point3::new((1.0, 2.0, 3.0)))
+                .approx_eq(&Point3::origin(), 1e-5)
+        );
+        assert!(Camera::new(Matrix4::zero(), Matrix4::identity()).is_none());
+    }
+}
@@ -0,0 +1,206 @@
+use log::LevelFilter;
+
+use crate::scene::{IntegratorDesc, Scene};
+
+pub const USAGE: &str = "usage: rt-rs [options]
+       rt-rs-viewer [options]
+
+options:
+  --scene <path>      scene file to render (default res/scene/primitives.json)
+  --output <path>     image to write, format taken from the extension (default output.png)
+  --width <n>         override the image width of the scene
+  --height <n>        override the image height of the scene
+  --spp <n>           override the samples per pixel of the scene
+  --integrator <name> override the integrator of the scene, whitted or path
+  --threads <n>       render threads, 0 uses every core (default 0)
+  --tile-size <n>     edge length of the square tiles in pixels (default 32)
+  --seed <n>          seed of the sample pattern (default 0)
+  --log-level <level> off, error, warn, info, debug or trace (default warn)
+  --self-test         print the vector and matrix self tests first
+  --help              print this message";
+
+/* command line options of the rt-rs binary */
+#[derive(Debug, PartialEq)]
+pub struct Options {
+    pub m_scene: String,
+    pub m_output: String,
+    pub m_width: Option<u32>,
+    pub m_height: Option<u32>,
+    pub m_spp: Option<u32>,
+    pub m_integrator: Option<IntegratorDesc>,
+    pub m_threads: usize,
+    pub m_tile_size: u32,
+    pub m_seed: u64,
+    pub m_log_level: LevelFilter,
+    pub m_self_test: bool,
+    pub m_help: bool,
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        return Options {
+            m_scene: String::from("res/scene/primitives.json"),
+            m_output: String::from("output.png"),
+            m_width: None,
+            m_height: None,
+            m_spp: None,
+            m_integrator: None,
+            m_threads: 0,
+            m_tile_size: 32,
+            m_seed: 0,
+            m_log_level: LevelFilter::Warn,
+            m_self_test: false,
+            m_help: false,
+        };
+    }
+}
+
+/* Associative functions implementation block */
+impl Options {
+    /* args excludes the program name */
+    pub fn parse<I>(args: I) -> Result<Options, String>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut options = Options::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--scene" => options.m_scene = value(&arg, args.next())?,
+                "--output" => options.m_output = value(&arg, args.next())?,
+                "--width" => options.m_width = Some(size(&arg, args.next())?),
+                "--height" => options.m_height = Some(size(&arg, args.next())?),
+                "--spp" => options.m_spp = Some(number(&arg, args.next())?),
+                "--integrator" => {
+                    options.m_integrator = Some(match value(&arg, args.next())?.as_str() {
+                        "whitted" => IntegratorDesc::Whitted,
+                        "path" => IntegratorDesc::Path,
+                        other => {
+                            return Err(format!("{} expects whitted or path, got {}", arg, other));
+                        }
+                    })
+                }
+                "--threads" => options.m_threads = number(&arg, args.next())?,
+                "--tile-size" => options.m_tile_size = size(&arg, args.next())?,
+                "--seed" => options.m_seed = number(&arg, args.next())?,
+                "--log-level" => {
+                    let level = value(&arg, args.next())?;
+                    options.m_log_level = level
+                        .parse()
+                        .map_err(|_| format!("{} expects a log level, got {}", arg, level))?
+                }
+                "--self-test" => options.m_self_test = true,
+                "--help" | "-h" => options.m_help = true,
+                _ => return Err(format!("unknown argument {}", arg)),
+            }
+        }
+        return Ok(options);
+    }
+}
+
+/* Associative methods implementation block */
+impl Options {
+    /* applies the overrides to the loaded scene */
+    pub fn apply(&self, scene: &mut Scene) {
+        if let Some(width) = self.m_width {
+            scene.m_image.m_width = width;
+        }
+        if let Some(height) = self.m_height {
+            scene.m_image.m_height = height;
+        }
+        if let Some(spp) = self.m_spp {
+            scene.m_image.m_samples_per_pixel = spp;
+        }
+        if let Some(integrator) = self.m_integrator {
+            scene.m_image.m_integrator = integrator;
+        }
+    }
+}
+
+fn value(arg: &str, next: Option<String>) -> Result<String, String> {
+    return next.ok_or_else(|| format!("{} expects a value", arg));
+}
+
+fn number<T: std::str::FromStr>(arg: &str, next: Option<String>) -> Result<T, String> {
+    let next = value(arg, next)?;
+    return next
+        .parse()
+        .map_err(|_| format!("{} expects a number, got {}", arg, next));
+}
+
+/* a number of pixels, an empty image or tile would render nothing */
+fn size(arg: &str, next: Option<String>) -> Result<u32, String> {
+    let size: u32 = number(arg, next)?;
+    if size == 0 {
+        return Err(format!("{} expects at least 1", arg));
+    }
+    return Ok(size);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Options, String> {
+        return Options::parse(args.iter().map(|a| String::from(*a)));
+    }
+
+    #[test]
+    fn defaults() {
+        assert_eq!(parse(&[]).unwrap(), Options::default());
+    }
+
+    #[test]
+    fn every_option() {
+        let options = parse(&[
+            "--scene",
+            "a.json",
+            "--output",
+            "b.exr",
+            "--width",
+            "64",
+            "--height",
+            "32",
+            "--spp",
+            "16",
+            "--integrator",
+            "path",
+            "--threads",
+            "3",
+            "--tile-size",
+            "8",
+            "--seed",
+            "42",
+            "--log-level",
+            "debug",
+            "--self-test",
+        ])
+        .unwrap();
+        assert_eq!(options.m_scene, "a.json");
+        assert_eq!(options.m_output, "b.exr");
+        assert_eq!(options.m_width, Some(64));
+        assert_eq!(options.m_height, Some(32));
+        assert_eq!(options.m_spp, Some(16));
+        assert_eq!(options.m_integrator, Some(IntegratorDesc::Path));
+        assert_eq!(options.m_threads, 3);
+        assert_eq!(options.m_tile_size, 8);
+        assert_eq!(options.m_seed, 42);
+        assert_eq!(options.m_log_level, LevelFilter::Debug);
+        assert!(options.m_self_test);
+    }
+
+    #[test]
+    fn errors() {
+        assert!(parse(&["--width"]).is_err());
+        assert!(parse(&["--width", "wide"]).is_err());
+        assert!(parse(&["--bogus"]).is_err());
+        assert_eq!(
+            parse(&["--width", "0"]),
+            Err(String::from("--width expects at least 1"))
+        );
+        assert!(parse(&["--height", "0"]).is_err());
+        assert!(parse(&["--tile-size", "0"]).is_err());
+        assert!(parse(&["--log-level", "loud"]).is_err());
+        assert!(parse(&["--integrator", "photon"]).is_err());
+    }
+}
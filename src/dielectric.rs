@@ -0,0 +1,122 @@
+use rand::Rng;
+use rand::rngs::StdRng;
+
+use crate::direction3::Direction3;
+use crate::hittable::HitRecord;
+use crate::material::{Lobe, Material, Scatter};
+use crate::ray::Ray;
+use crate::scalar::Real;
+use crate::vector3::Vector3;
+
+/* clear material such as glass or water, refracting what it does not reflect */
+#[derive(Debug)]
+pub struct Dielectric {
+    /* index of refraction against the vacuum outside of it */
+    pub m_ior: Real,
+    /* multiplies every ray crossing or leaving the surface, white for clear glass */
+    pub m_tint: Vector3,
+}
+
+/* Associative functions implementation block */
+impl Dielectric {
+    /* Constructor */
+    pub fn new(ior: Real, tint: Vector3) -> Dielectric {
+        return Dielectric {
+            m_ior: ior,
+            m_tint: tint,
+        };
+    }
+
+    /* Schlick's approximation of the fraction of light reflected at an incidence of cos_i, eta
+    being the ratio of the refractive indices */
+    pub fn reflectance(cos_i: Real, eta: Real) -> Real {
+        let r0 = ((1.0 - eta) / (1.0 + eta)).powi(2);
+        return r0 + (1.0 - r0) * (1.0 - cos_i).powi(5);
+    }
+}
+
+impl Material for Dielectric {
+    /* reflects with the Fresnel reflectance as probability and refracts otherwise, so either
+    ray carries the full tint. Past the critical angle everything is reflected */
+    fn scatter(&self, ray: &Ray, rec: &HitRecord, rng: &mut StdRng) -> Option<Scatter> {
+        let eta = if rec.m_front_face {
+            1.0 / self.m_ior
+        } else {
+            self.m_ior
+        };
+        let unit = ray.m_direction.unit();
+        let cos_i = (-rec.m_normal.dot(&unit)).min(1.0);
+        let direction = match unit.refract(&rec.m_normal, eta) {
+            Some(refracted) if Self::reflectance(cos_i, eta) <= rng.gen::<Real>() => refracted,
+            _ => unit.reflect(&rec.m_normal),
+        };
+        return Some(Scatter {
+            m_ray: Ray::new(rec.m_point, direction),
+            m_attenuation: self.m_tint,
+            m_lobe: Lobe::Specular,
+        });
+    }
+
+    fn eval(&self, _ray: &Ray, _rec: &HitRecord, _direction: &Direction3<Real>) -> Vector3 {
+        return Vector3::zero();
+    }
+
+    fn pdf(&self, _ray: &Ray, _rec: &HitRecord, _direction: &Direction3<Real>) -> Real {
+        return 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::normal3::Normal3;
+    use crate::point3::Point3;
+    use crate::vector2::Vector2;
+
+    #[test]
+    fn fresnel() {
+        /* 4% at normal incidence for glass, everything at grazing angles */
+        assert!((Dielectric::reflectance(1.0, 1.0 / 1.5) - 0.04).abs() < 1e-12);
+        assert!((Dielectric::reflectance(0.0, 1.0 / 1.5) - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn refraction_and_total_internal_reflection() {
+        let glass = Dielectric::new(1.5, Vector3::new((1.0, 1.0, 1.0)));
+        let mut rng = StdRng::seed_from_u64(5);
+        let d = Direction3::new((1.0, -1.0, 0.0)).unit();
+        let n = Normal3::new((0.0, 1.0, 0.0));
+
+        /* entering at 45 degrees, about 5% of the rays are reflected */
+        let ray = Ray::new(Point3::new((-1.0, 1.0, 0.0)), d);
+        let rec = HitRecord::new(&ray, 1.0, n, Vector2::zero());
+        let count = 10000;
+        let mut reflected = 0;
+        for _ in 0..count {
+            let scatter = glass.scatter(&ray, &rec, &mut rng).unwrap();
+            let out = scatter.m_ray.m_direction;
+            if out.get_y() > 0.0 {
+                reflected += 1;
+            } else {
+                assert!((out.get_x() * 1.5 - d.get_x()).abs() < 1e-12);
+            }
+        }
+        let expected = Dielectric::reflectance(d.get_y().abs(), 1.0 / 1.5);
+        assert!((reflected as Real / count as Real - expected).abs() < 0.01);
+
+        /* leaving the glass at 45 degrees is past the critical angle of 41.8 */
+        let ray = Ray::new(Point3::new((-1.0, 1.0, 0.0)), d);
+        let rec = HitRecord::new(&ray, 1.0, -n, Vector2::zero());
+        assert!(!rec.m_front_face);
+        for _ in 0..100 {
+            let out = glass
+                .scatter(&ray, &rec, &mut rng)
+                .unwrap()
+                .m_ray
+                .m_direction;
+            assert!(out.approx_eq(&Direction3::new((1.0, 1.0, 0.0)).unit(), 1e-12));
+        }
+    }
+}
@@ -0,0 +1,278 @@
+use std::ops::{Add, AddAssign, Div, DivAssign, Index, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use crate::normal3::Normal3;
+use crate::scalar::Float;
+use crate::vector3::Vector3;
+use crate::vector4::Vector4;
+
+/* displacement or ray direction, w = 0 in homogeneous coordinates so Matrix4 does not translate it */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Direction3<T: Float = f32> {
+    pub m_data: [T; 3],
+}
+
+pub type Direction3d = Direction3<f64>;
+
+pub trait Instantiator<T: Float> {
+    fn into(self) -> Direction3<T>;
+}
+
+/* Associative methods implementation block */
+impl<T: Float> Direction3<T> {
+    /* Accessors */
+    pub fn get_x(&self) -> T {
+        return self.m_data[0];
+    }
+
+    pub fn get_y(&self) -> T {
+        return self.m_data[1];
+    }
+
+    pub fn get_z(&self) -> T {
+        return self.m_data[2];
+    }
+
+    pub fn magnitude(&self) -> T {
+        return self.dot(self).sqrt();
+    }
+
+    pub fn dot(&self, d: &Direction3<T>) -> T {
+        return self.vector().dot(&d.vector());
+    }
+
+    pub fn cross(&self, d: &Direction3<T>) -> Direction3<T> {
+        return Direction3::new(&self.vector().cross(&d.vector()));
+    }
+
+    pub fn normalize(&mut self) {
+        *self = self.unit();
+    }
+
+    /* non-mutating counterpart of normalize() */
+    pub fn unit(&self) -> Direction3<T> {
+        return Direction3::new(&self.vector().unit());
+    }
+
+    /* any unit direction orthogonal to this one */
+    pub fn perpendicular(&self) -> Direction3<T> {
+        return Direction3::new(&self.vector().perpendicular());
+    }
+
+    /* mirrored about the plane of normal n, which must be unit length */
+    pub fn reflect(&self, n: &Normal3<T>) -> Direction3<T> {
+        let n = Direction3::new(n);
+        return *self - n * (T::from_f64(2.0) * self.dot(&n));
+    }
+
+    /* unit direction bent through the surface of normal n by Snell's law, both unit length with
+    n against this direction. eta is the ratio of the refractive indices, incoming over outgoing.
+    None under total internal reflection */
+    pub fn refract(&self, n: &Normal3<T>, eta: T) -> Option<Direction3<T>> {
+        let n = Direction3::new(n);
+        let cos_i = (-self.dot(&n)).min(T::ONE);
+        let sin2_t = eta * eta * (T::ONE - cos_i * cos_i);
+        if sin2_t > T::ONE {
+            return None;
+        }
+        let cos_t = (T::ONE - sin2_t).sqrt();
+        return Some(*self * eta + n * (eta * cos_i - cos_t));
+    }
+
+    /* the components as an untyped vector */
+    pub fn vector(&self) -> Vector3<T> {
+        return Vector3::new((self.m_data[0], self.m_data[1], self.m_data[2]));
+    }
+
+    /* homogeneous coordinates, w = 0 */
+    pub fn homogeneous(&self) -> Vector4<T> {
+        return Vector4::new((self.m_data[0], self.m_data[1], self.m_data[2], T::ZERO));
+    }
+
+    /* same direction in another precision */
+    pub fn cast<U: Float>(&self) -> Direction3<U> {
+        return Direction3 {
+            m_data: self.m_data.map(|v| U::from_f64(v.to_f64())),
+        };
+    }
+
+    /* true when every component is within epsilon of d's */
+    pub fn approx_eq(&self, d: &Direction3<T>, epsilon: T) -> bool {
+        return self.vector().approx_eq(&d.vector(), epsilon);
+    }
+}
+
+/* Associative functions implementation block */
+impl<T: Float> Direction3<T> {
+    /* Constructor */
+    pub fn new<A>(args: A) -> Direction3<T>
+    where
+        A: Instantiator<T>,
+    {
+        return args.into();
+    }
+
+    /* Static Constructors */
+    pub fn zero() -> Direction3<T> {
+        return Direction3::new(());
+    }
+}
+
+/* Operator overloads */
+impl<T: Float> Add for Direction3<T> {
+    type Output = Direction3<T>;
+
+    fn add(self, d: Direction3<T>) -> Direction3<T> {
+        return Direction3::new(&(self.vector() + d.vector()));
+    }
+}
+
+impl<T: Float> Sub for Direction3<T> {
+    type Output = Direction3<T>;
+
+    fn sub(self, d: Direction3<T>) -> Direction3<T> {
+        return Direction3::new(&(self.vector() - d.vector()));
+    }
+}
+
+impl<T: Float> Mul<T> for Direction3<T> {
+    type Output = Direction3<T>;
+
+    fn mul(self, s: T) -> Direction3<T> {
+        return Direction3::new(&(self.vector() * s));
+    }
+}
+
+impl<T: Float> Div<T> for Direction3<T> {
+    type Output = Direction3<T>;
+
+    fn div(self, s: T) -> Direction3<T> {
+        return Direction3::new(&(self.vector() / s));
+    }
+}
+
+impl Mul<Direction3<f32>> for f32 {
+    type Output = Direction3<f32>;
+
+    fn mul(self, d: Direction3<f32>) -> Direction3<f32> {
+        return d * self;
+    }
+}
+
+impl Mul<Direction3<f64>> for f64 {
+    type Output = Direction3<f64>;
+
+    fn mul(self, d: Direction3<f64>) -> Direction3<f64> {
+        return d * self;
+    }
+}
+
+impl<T: Float> Neg for Direction3<T> {
+    type Output = Direction3<T>;
+
+    fn neg(self) -> Direction3<T> {
+        return Direction3::new(&(-self.vector()));
+    }
+}
+
+impl<T: Float> AddAssign for Direction3<T> {
+    fn add_assign(&mut self, d: Direction3<T>) {
+        *self = *self + d;
+    }
+}
+
+impl<T: Float> SubAssign for Direction3<T> {
+    fn sub_assign(&mut self, d: Direction3<T>) {
+        *self = *self - d;
+    }
+}
+
+impl<T: Float> MulAssign<T> for Direction3<T> {
+    fn mul_assign(&mut self, s: T) {
+        *self = *self * s;
+    }
+}
+
+impl<T: Float> DivAssign<T> for Direction3<T> {
+    fn div_assign(&mut self, s: T) {
+        *self = *self / s;
+    }
+}
+
+impl<T: Float> Index<usize> for Direction3<T> {
+    type Output = T;
+
+    fn index(&self, i: usize) -> &T {
+        return &self.m_data[i];
+    }
+}
+
+impl<T: Float> Instantiator<T> for () {
+    fn into(self) -> Direction3<T> {
+        return Direction3 {
+            m_data: [T::ZERO; 3],
+        };
+    }
+}
+
+impl<T: Float> Instantiator<T> for (T, T, T) {
+    fn into(self) -> Direction3<T> {
+        return Direction3 {
+            m_data: [self.0, self.1, self.2],
+        };
+    }
+}
+
+impl<T: Float> Instantiator<T> for &Vector3<T> {
+    fn into(self) -> Direction3<T> {
+        return Direction3 {
+            m_data: { self.m_data },
+        };
+    }
+}
+
+/* the normal as a direction, e.g. to offset a point along it */
+impl<T: Float> Instantiator<T> for &Normal3<T> {
+    fn into(self) -> Direction3<T> {
+        return Direction3 {
+            m_data: { self.m_data },
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operators() {
+        let x = Direction3::new((1.0, 0.0, 0.0));
+        let y = Direction3::new((0.0, 1.0, 0.0));
+        assert_eq!(x.cross(&y), Direction3::new((0.0, 0.0, 1.0)));
+        assert_eq!(x.dot(&y), 0.0);
+        assert_eq!((x + y) * 2.0, Direction3::new((2.0, 2.0, 0.0)));
+        assert_eq!(-(x - y), Direction3::new((-1.0, 1.0, 0.0)));
+        assert_eq!((x * 3.0).unit(), x);
+        assert_eq!(x.homogeneous().get_w(), 0.0);
+        assert_eq!(x.perpendicular().dot(&x), 0.0);
+    }
+
+    #[test]
+    fn reflect_and_refract() {
+        let n = Normal3::new((0.0, 1.0, 0.0));
+        let d = Direction3::new((1.0, -1.0, 0.0)).unit();
+        assert!(
+            d.reflect(&n)
+                .approx_eq(&Direction3::new((1.0, 1.0, 0.0)).unit(), 1e-12)
+        );
+
+        /* Snell's law, sin_i = eta * sin_t */
+        let t = d.refract(&n, 1.0 / 1.5).unwrap();
+        assert!((t.magnitude() - 1.0).abs() < 1e-12);
+        assert!((t.get_x() * 1.5 - d.get_x()).abs() < 1e-12);
+        assert!(t.get_y() < 0.0);
+        assert_eq!(d.refract(&n, 1.0), Some(d));
+
+        /* past the critical angle of glass to air */
+        assert!(d.refract(&n, 1.5).is_none());
+    }
+}
@@ -0,0 +1,130 @@
+use crate::aabb::Aabb;
+use crate::direction3::Direction3;
+use crate::hittable::{Hittable, HitRecord};
+use crate::normal3::Normal3;
+use crate::point3::Point3;
+use crate::ray::Ray;
+use crate::scalar::{Float, Real};
+use crate::vector2::Vector2;
+
+#[derive(Debug)]
+pub struct Disk {
+    pub m_center: Point3<Real>,
+    pub m_normal: Normal3<Real>,
+    pub m_radius: Real,
+}
+
+/* Associative functions implementation block */
+impl Disk {
+    /* Constructor */
+    pub fn new(center: Point3<Real>, normal: Normal3<Real>, radius: Real) -> Disk {
+        return Disk {
+            m_center: center,
+            m_normal: normal.unit(),
+            m_radius: radius,
+        };
+    }
+}
+
+impl Hittable for Disk {
+    fn hit(&self, ray: &Ray, t_min: Real, t_max: Real) -> Option<HitRecord> {
+        let denom = self.m_normal.dot(&ray.m_direction);
+        if denom.abs() < 1e-8 {
+            return None;
+        }
+
+        let t = self.m_normal.dot(&(self.m_center - ray.m_origin)) / denom;
+        if t < t_min || t > t_max {
+            return None;
+        }
+
+        let local = ray.at(t) - self.m_center;
+        let distance = local.magnitude();
+        if distance > self.m_radius {
+            return None;
+        }
+
+        /* polar coordinates, u is the angle and v the distance from the center */
+        let normal = Direction3::new(&self.m_normal);
+        let tangent = normal.perpendicular();
+        let bitangent = normal.cross(&tangent);
+        let phi = local.dot(&bitangent).atan2(local.dot(&tangent)) + Real::PI;
+
+        return Some(HitRecord::new(
+            ray,
+            t,
+            self.m_normal,
+            Vector2::new((phi / (2.0 * Real::PI), distance / self.m_radius)),
+        ));
+    }
+
+    /* the rim extends r * sqrt(1 - n_i^2) along each axis */
+    fn bounds(&self) -> Option<Aabb> {
+        let extent = self
+            .m_normal
+            .m_data
+            .map(|n| self.m_radius * (1.0 - n * n).max(0.0).sqrt());
+        let e = Direction3::new((extent[0], extent[1], extent[2]));
+        return Some(Aabb::new((&(self.m_center - e), &(self.m_center + e))));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn disk() -> Disk {
+        return Disk::new(
+            Point3::new((0.0, 0.0, -3.0)),
+            Normal3::new((0.0, 0.0, 1.0)),
+            1.0,
+        );
+    }
+
+    #[test]
+    fn hit() {
+        let ray = Ray::new(
+            Point3::new((0.5, 0.0, 0.0)),
+            Direction3::new((0.0, 0.0, -1.0)),
+        );
+        let rec = disk().hit(&ray, 0.001, Real::MAX).unwrap();
+        assert!((rec.m_t - 3.0).abs() < 1e-5);
+        assert!(rec.m_front_face);
+        assert!((rec.m_uv.get_v() - 0.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn miss() {
+        let ray = Ray::new(
+            Point3::new((1.5, 0.0, 0.0)),
+            Direction3::new((0.0, 0.0, -1.0)),
+        );
+        assert!(disk().hit(&ray, 0.001, Real::MAX).is_none());
+        let ray = Ray::new(Point3::origin(), Direction3::new((0.0, 0.0, 1.0)));
+        assert!(disk().hit(&ray, 0.001, Real::MAX).is_none());
+    }
+
+    #[test]
+    fn grazing() {
+        /* parallel to the disk's plane */
+        let ray = Ray::new(
+            Point3::new((-5.0, 0.0, -3.0)),
+            Direction3::new((1.0, 0.0, 0.0)),
+        );
+        assert!(disk().hit(&ray, 0.001, Real::MAX).is_none());
+
+        /* right on the rim */
+        let ray = Ray::new(
+            Point3::new((0.0, 1.0, 0.0)),
+            Direction3::new((0.0, 0.0, -1.0)),
+        );
+        assert!(disk().hit(&ray, 0.001, Real::MAX).is_some());
+
+        /* just outside the rim */
+        let ray = Ray::new(
+            Point3::new((0.0, 1.001, 0.0)),
+            Direction3::new((0.0, 0.0, -1.0)),
+        );
+        assert!(disk().hit(&ray, 0.001, Real::MAX).is_none());
+    }
+}
@@ -0,0 +1,78 @@
+use rand::rngs::StdRng;
+
+use crate::direction3::Direction3;
+use crate::hittable::HitRecord;
+use crate::material::{Material, Scatter};
+use crate::ray::Ray;
+use crate::scalar::Real;
+use crate::vector3::Vector3;
+
+/* black surface glowing with the same radiance in every direction from its front face, the
+material of area lights */
+#[derive(Debug)]
+pub struct Emissive {
+    /* W/(sr m^2), so a face of area A emits pi * A * m_radiance watts */
+    pub m_radiance: Vector3,
+}
+
+/* Associative functions implementation block */
+impl Emissive {
+    /* Constructor */
+    pub fn new(radiance: Vector3) -> Emissive {
+        return Emissive {
+            m_radiance: radiance,
+        };
+    }
+}
+
+impl Material for Emissive {
+    fn scatter(&self, _ray: &Ray, _rec: &HitRecord, _rng: &mut StdRng) -> Option<Scatter> {
+        return None;
+    }
+
+    fn eval(&self, _ray: &Ray, _rec: &HitRecord, _direction: &Direction3<Real>) -> Vector3 {
+        return Vector3::zero();
+    }
+
+    fn pdf(&self, _ray: &Ray, _rec: &HitRecord, _direction: &Direction3<Real>) -> Real {
+        return 0.0;
+    }
+
+    fn emitted(&self, _ray: &Ray, rec: &HitRecord) -> Vector3 {
+        if !rec.m_front_face {
+            return Vector3::zero();
+        }
+        return self.m_radiance;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::normal3::Normal3;
+    use crate::point3::Point3;
+    use crate::vector2::Vector2;
+
+    #[test]
+    fn front_face_only() {
+        let light = Emissive::new(Vector3::new((4.0, 3.0, 2.0)));
+        let down = Ray::new(
+            Point3::new((0.0, 1.0, 0.0)),
+            Direction3::new((0.0, -1.0, 0.0)),
+        );
+        let up = Ray::new(
+            Point3::new((0.0, -1.0, 0.0)),
+            Direction3::new((0.0, 1.0, 0.0)),
+        );
+        let normal = Normal3::new((0.0, 1.0, 0.0));
+        let front = HitRecord::new(&down, 1.0, normal, Vector2::zero());
+        let back = HitRecord::new(&up, 1.0, normal, Vector2::zero());
+        assert_eq!(light.emitted(&down, &front), light.m_radiance);
+        assert_eq!(light.emitted(&up, &back), Vector3::zero());
+
+        let mut rng = StdRng::seed_from_u64(0);
+        assert!(light.scatter(&down, &front, &mut rng).is_none());
+    }
+}
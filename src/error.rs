@@ -0,0 +1,208 @@
+use std::fmt;
+
+/* everything that can go wrong while loading res/ files */
+#[derive(Debug)]
+pub enum Error {
+    /* the file could not be opened or read */
+    Io {
+        m_path: String,
+        m_source: std::io::Error,
+    },
+    /* the file is not valid JSON */
+    Syntax {
+        m_path: String,
+        m_line: usize,
+        m_column: usize,
+        m_message: String,
+    },
+    /* valid JSON that does not match the expected structure */
+    Schema {
+        m_path: String,
+        m_line: usize,
+        m_column: usize,
+        m_message: String,
+    },
+    /* a required field is absent, m_pointer is a JSON pointer to it */
+    Missing {
+        m_path: String,
+        m_pointer: String,
+    },
+    /* an array holds the wrong number of entries */
+    Length {
+        m_path: String,
+        m_pointer: String,
+        m_expected: usize,
+        m_found: usize,
+    },
+    /* an entry that should be an array is not */
+    NotArray {
+        m_path: String,
+        m_pointer: String,
+    },
+    /* an entry that should be a number is not */
+    NotNumeric {
+        m_path: String,
+        m_pointer: String,
+    },
+    /* a size that should be at least 1 is 0 */
+    NotPositive {
+        m_path: String,
+        m_pointer: String,
+    },
+    /* an object refers to a material that is not declared */
+    UnknownMaterial {
+        m_path: String,
+        m_pointer: String,
+        m_name: String,
+    },
+    /* two fields that cannot be given together */
+    Conflict {
+        m_path: String,
+        m_pointer: String,
+        m_other: String,
+    },
+    /* a transform that cannot be inverted, it would flatten the object */
+    Singular {
+        m_path: String,
+        m_pointer: String,
+    },
+    /* a malformed line of a text model file, m_line counts from 1 */
+    Parse {
+        m_path: String,
+        m_line: usize,
+        m_message: String,
+    },
+    /* a model file that does not match its own layout, or needs what the importer lacks */
+    Malformed {
+        m_path: String,
+        m_message: String,
+    },
+    /* a model file whose format is not known from its extension */
+    Unsupported {
+        m_path: String,
+    },
+}
+
+/* Associative functions implementation block */
+impl Error {
+    pub fn io(path: &str, source: std::io::Error) -> Error {
+        return Error::Io {
+            m_path: String::from(path),
+            m_source: source,
+        };
+    }
+
+    pub fn parse(path: &str, line: usize, message: String) -> Error {
+        return Error::Parse {
+            m_path: String::from(path),
+            m_line: line,
+            m_message: message,
+        };
+    }
+
+    /* splits serde_json errors into syntax and structure errors */
+    pub fn json(path: &str, source: serde_json::Error) -> Error {
+        let m_path = String::from(path);
+        let m_line = source.line();
+        let m_column = source.column();
+        let m_message = source.to_string();
+        return match source.classify() {
+            serde_json::error::Category::Data => Error::Schema {
+                m_path,
+                m_line,
+                m_column,
+                m_message,
+            },
+            serde_json::error::Category::Io => Error::io(path, source.into()),
+            _ => Error::Syntax {
+                m_path,
+                m_line,
+                m_column,
+                m_message,
+            },
+        };
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            Error::Io { m_path, m_source } => write!(f, "{}: {}", m_path, m_source),
+            Error::Syntax {
+                m_path,
+                m_line,
+                m_column,
+                m_message,
+            } => write!(
+                f,
+                "{}:{}:{}: syntax error: {}",
+                m_path, m_line, m_column, m_message
+            ),
+            Error::Schema {
+                m_path,
+                m_line,
+                m_column,
+                m_message,
+            } => write!(f, "{}:{}:{}: {}", m_path, m_line, m_column, m_message),
+            Error::Missing { m_path, m_pointer } => {
+                write!(f, "{}: missing {}", m_path, m_pointer)
+            }
+            Error::Length {
+                m_path,
+                m_pointer,
+                m_expected,
+                m_found,
+            } => write!(
+                f,
+                "{}: {} should have {} entries, found {}",
+                m_path, m_pointer, m_expected, m_found
+            ),
+            Error::NotArray { m_path, m_pointer } => {
+                write!(f, "{}: {} is not an array", m_path, m_pointer)
+            }
+            Error::NotNumeric { m_path, m_pointer } => {
+                write!(f, "{}: {} is not a number", m_path, m_pointer)
+            }
+            Error::NotPositive { m_path, m_pointer } => {
+                write!(f, "{}: {} should be at least 1", m_path, m_pointer)
+            }
+            Error::UnknownMaterial {
+                m_path,
+                m_pointer,
+                m_name,
+            } => write!(
+                f,
+                "{}: {} refers to unknown material \"{}\"",
+                m_path, m_pointer, m_name
+            ),
+            Error::Conflict {
+                m_path,
+                m_pointer,
+                m_other,
+            } => write!(
+                f,
+                "{}: {} cannot be combined with {}",
+                m_path, m_pointer, m_other
+            ),
+            Error::Singular { m_path, m_pointer } => {
+                write!(f, "{}: {} is not invertible", m_path, m_pointer)
+            }
+            Error::Parse {
+                m_path,
+                m_line,
+                m_message,
+            } => write!(f, "{}:{}: {}", m_path, m_line, m_message),
+            Error::Malformed { m_path, m_message } => write!(f, "{}: {}", m_path, m_message),
+            Error::Unsupported { m_path } => write!(f, "{}: unsupported model format", m_path),
+        };
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        return match self {
+            Error::Io { m_source, .. } => Some(m_source),
+            _ => None,
+        };
+    }
+}
@@ -0,0 +1,120 @@
+use image::{Rgba, RgbaImage, imageops};
+
+use crate::bounds2i::Bounds2i;
+use crate::point2i::Point2i;
+use crate::scalar::Float;
+use crate::vector2::Vector2;
+use crate::vector3::Vector3;
+
+/* accumulates the samples of every pixel and turns their average into an image */
+#[derive(Debug, Clone)]
+pub struct Film {
+    pub m_resolution: Point2i,
+    /* running sum of every sample in row-major order */
+    pub m_pixels: Vec<Vector3>,
+    pub m_sample_count: u32,
+}
+
+/* Associative methods implementation block */
+impl Film {
+    pub fn width(&self) -> u32 {
+        return self.m_resolution.get_x() as u32;
+    }
+
+    pub fn height(&self) -> u32 {
+        return self.m_resolution.get_y() as u32;
+    }
+
+    pub fn bounds(&self) -> Bounds2i {
+        return Bounds2i::new(&self.m_resolution);
+    }
+
+    /* maps a position on the film to [0, 1]^2 */
+    pub fn uv<T: Float>(&self, position: &Vector2<T>) -> Vector2<T> {
+        return *position / Vector2::new(&self.m_resolution);
+    }
+
+    /* drops the accumulated samples */
+    pub fn reset(&mut self) {
+        self.m_pixels = vec![Vector3::zero(); self.bounds().area()];
+        self.m_sample_count = 0;
+    }
+
+    /* adds one sample to every pixel of the tile, given in row-major order */
+    pub fn add_tile(&mut self, tile: &Bounds2i, samples: &[Vector3]) {
+        let bounds = self.bounds();
+        for (p, color) in tile.points().zip(samples) {
+            self.m_pixels[bounds.offset(&p)] += *color;
+        }
+    }
+
+    /* to be called once every pixel got its sample of the pass */
+    pub fn end_pass(&mut self) {
+        self.m_sample_count += 1;
+    }
+
+    /* running average of the accumulated samples, gamma encoded */
+    pub fn image(&self) -> RgbaImage {
+        let scale = 1.0 / self.m_sample_count.max(1) as f32;
+        let bounds = self.bounds();
+        let image = RgbaImage::from_fn(self.width(), self.height(), |x, y| {
+            Self::to_rgba(&(self.m_pixels[bounds.offset(&Point2i::new((x, y)))] * scale))
+        });
+
+        /* correct uv coordinates */
+        return imageops::flip_vertical(&image);
+    }
+}
+
+/* Associative functions implementation block */
+impl Film {
+    /* Constructor */
+    pub fn new(resolution: &Point2i) -> Film {
+        let mut film = Film {
+            m_resolution: resolution.max(&Point2i::zero()),
+            m_pixels: Vec::new(),
+            m_sample_count: 0,
+        };
+        film.reset();
+        return film;
+    }
+
+    /* gamma encodes the linear color */
+    fn to_rgba(color: &Vector3) -> Rgba<u8> {
+        let encode = |c: f32| (c.clamp(0.0, 1.0).powf(1.0 / 2.2) * 255.0) as u8;
+        return image::Rgba([
+            encode(color.get_r()),
+            encode(color.get_g()),
+            encode(color.get_b()),
+            255,
+        ]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn averages_and_flips() {
+        let mut film = Film::new(&Point2i::new((4, 2)));
+        let bottom = Bounds2i::new((&Point2i::new((0, 0)), &Point2i::new((4, 1))));
+        for value in [0.0, 1.0] {
+            film.add_tile(&film.bounds(), &[Vector3::zero(); 8]);
+            film.add_tile(&bottom, &[Vector3::new((value, value, value)); 4]);
+            film.end_pass();
+        }
+        let image = film.image();
+        assert_eq!(film.m_sample_count, 2);
+        /* row 0 of the film ends up at the bottom of the image */
+        assert_eq!(image.get_pixel(0, 0), &Rgba([0, 0, 0, 255]));
+        assert_eq!(
+            image.get_pixel(3, 1)[0],
+            (0.5f32.powf(1.0 / 2.2) * 255.0) as u8
+        );
+        assert_eq!(film.uv(&Vector2::new((2.0, 1.0))), Vector2::new((0.5, 0.5)));
+
+        film.reset();
+        assert_eq!(film.image().get_pixel(3, 1)[0], 0);
+    }
+}
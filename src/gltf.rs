@@ -0,0 +1,1308 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde::Deserialize;
+
+use crate::direction3::Direction3;
+use crate::error::Error;
+use crate::loader;
+use crate::matrix4::Matrix4;
+use crate::mesh::{
+    Mesh, Model, ModelCamera, ModelLight, ModelLightKind, ModelMaterial, ModelPart, ModelProjection,
+};
+use crate::normal3::Normal3;
+use crate::point3::Point3;
+use crate::quaternion::Quaternion;
+use crate::scalar::{Float, Real};
+use crate::texture::Texture;
+use crate::transform::Transform;
+use crate::vector2::Vector2;
+use crate::vector3::Vector3;
+
+/* the parts of a glTF 2.0 document the importer reads, see
+https://registry.khronos.org/glTF/specs/2.0/glTF-2.0.html */
+#[derive(Debug, Deserialize)]
+struct Document {
+    #[serde(rename = "asset")]
+    m_asset: Asset,
+    #[serde(rename = "extensionsRequired", default)]
+    m_extensions_required: Vec<String>,
+    #[serde(rename = "scene", default)]
+    m_scene: Option<usize>,
+    #[serde(rename = "scenes", default)]
+    m_scenes: Vec<SceneDef>,
+    #[serde(rename = "nodes", default)]
+    m_nodes: Vec<Node>,
+    #[serde(rename = "meshes", default)]
+    m_meshes: Vec<MeshDef>,
+    #[serde(rename = "accessors", default)]
+    m_accessors: Vec<Accessor>,
+    #[serde(rename = "bufferViews", default)]
+    m_buffer_views: Vec<BufferView>,
+    #[serde(rename = "buffers", default)]
+    m_buffers: Vec<Buffer>,
+    #[serde(rename = "materials", default)]
+    m_materials: Vec<MaterialDef>,
+    #[serde(rename = "textures", default)]
+    m_textures: Vec<TextureDef>,
+    #[serde(rename = "images", default)]
+    m_images: Vec<Image>,
+    #[serde(rename = "cameras", default)]
+    m_cameras: Vec<CameraDef>,
+    #[serde(rename = "extensions", default)]
+    m_extensions: Extensions,
+}
+
+#[derive(Debug, Deserialize)]
+struct Asset {
+    #[serde(rename = "version")]
+    m_version: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SceneDef {
+    #[serde(rename = "nodes", default)]
+    m_nodes: Vec<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Node {
+    #[serde(rename = "children", default)]
+    m_children: Vec<usize>,
+    #[serde(rename = "mesh", default)]
+    m_mesh: Option<usize>,
+    #[serde(rename = "camera", default)]
+    m_camera: Option<usize>,
+    /* column-major, exclusive with the TRS fields */
+    #[serde(rename = "matrix", default)]
+    m_matrix: Option<[Real; 16]>,
+    #[serde(rename = "translation", default)]
+    m_translation: Option<[Real; 3]>,
+    /* x, y, z, w */
+    #[serde(rename = "rotation", default)]
+    m_rotation: Option<[Real; 4]>,
+    #[serde(rename = "scale", default)]
+    m_scale: Option<[Real; 3]>,
+    #[serde(rename = "extensions", default)]
+    m_extensions: NodeExtensions,
+}
+
+#[derive(Debug, Deserialize)]
+struct MeshDef {
+    #[serde(rename = "primitives")]
+    m_primitives: Vec<Primitive>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Primitive {
+    /* accessor of each vertex attribute */
+    #[serde(rename = "attributes")]
+    m_attributes: HashMap<String, usize>,
+    #[serde(rename = "indices", default)]
+    m_indices: Option<usize>,
+    #[serde(rename = "material", default)]
+    m_material: Option<usize>,
+    /* 4 triangles, 5 triangle strip, 6 triangle fan, points and lines are skipped */
+    #[serde(rename = "mode", default = "default_mode")]
+    m_mode: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct Accessor {
+    /* all zeros when absent */
+    #[serde(rename = "bufferView", default)]
+    m_buffer_view: Option<usize>,
+    #[serde(rename = "byteOffset", default)]
+    m_byte_offset: usize,
+    #[serde(rename = "componentType")]
+    m_component_type: u32,
+    #[serde(rename = "normalized", default)]
+    m_normalized: bool,
+    #[serde(rename = "count")]
+    m_count: usize,
+    #[serde(rename = "type")]
+    m_type: String,
+    #[serde(rename = "sparse", default)]
+    m_sparse: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BufferView {
+    #[serde(rename = "buffer")]
+    m_buffer: usize,
+    #[serde(rename = "byteOffset", default)]
+    m_byte_offset: usize,
+    #[serde(rename = "byteLength")]
+    m_byte_length: usize,
+    /* tightly packed when absent */
+    #[serde(rename = "byteStride", default)]
+    m_byte_stride: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Buffer {
+    /* the binary chunk of a GLB file when absent */
+    #[serde(rename = "uri", default)]
+    m_uri: Option<String>,
+    #[serde(rename = "byteLength")]
+    m_byte_length: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct MaterialDef {
+    #[serde(rename = "name", default)]
+    m_name: Option<String>,
+    #[serde(rename = "pbrMetallicRoughness", default)]
+    m_pbr: Pbr,
+}
+
+#[derive(Debug, Deserialize)]
+struct Pbr {
+    /* linear rgba */
+    #[serde(rename = "baseColorFactor", default = "default_factor")]
+    m_base_color_factor: [f32; 4],
+    #[serde(rename = "baseColorTexture", default)]
+    m_base_color_texture: Option<TextureInfo>,
+    #[serde(rename = "metallicFactor", default = "default_scalar_factor")]
+    m_metallic_factor: f32,
+    #[serde(rename = "roughnessFactor", default = "default_scalar_factor")]
+    m_roughness_factor: f32,
+    #[serde(rename = "metallicRoughnessTexture", default)]
+    m_metallic_roughness_texture: Option<TextureInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TextureInfo {
+    #[serde(rename = "index")]
+    m_index: usize,
+    #[serde(rename = "texCoord", default)]
+    m_tex_coord: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct TextureDef {
+    #[serde(rename = "source", default)]
+    m_source: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Image {
+    /* a file relative to the document or a data: URI */
+    #[serde(rename = "uri", default)]
+    m_uri: Option<String>,
+    /* the encoded image inside a buffer, when there is no uri */
+    #[serde(rename = "bufferView", default)]
+    m_buffer_view: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CameraDef {
+    #[serde(rename = "type")]
+    m_type: String,
+    #[serde(rename = "perspective", default)]
+    m_perspective: Option<Perspective>,
+    #[serde(rename = "orthographic", default)]
+    m_orthographic: Option<Orthographic>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Perspective {
+    /* radians */
+    #[serde(rename = "yfov")]
+    m_yfov: Real,
+    #[serde(rename = "aspectRatio", default)]
+    m_aspect_ratio: Option<Real>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Orthographic {
+    /* half extents of the view */
+    #[serde(rename = "xmag")]
+    m_xmag: Real,
+    #[serde(rename = "ymag")]
+    m_ymag: Real,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Extensions {
+    #[serde(rename = "KHR_lights_punctual", default)]
+    m_lights_punctual: Option<LightsPunctual>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LightsPunctual {
+    #[serde(rename = "lights", default)]
+    m_lights: Vec<LightDef>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LightDef {
+    /* point, spot or directional */
+    #[serde(rename = "type")]
+    m_type: String,
+    #[serde(rename = "color", default = "default_color")]
+    m_color: [f32; 3],
+    /* candela for point and spot lights, lux for directional ones. The renderer has no exposure
+    to map photometric units with, so they are taken as W/sr and W/m^2 */
+    #[serde(rename = "intensity", default = "default_intensity")]
+    m_intensity: f32,
+    #[serde(rename = "spot", default)]
+    m_spot: Option<SpotDef>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SpotDef {
+    /* radians from the axis */
+    #[serde(rename = "innerConeAngle", default)]
+    m_inner_cone_angle: Real,
+    #[serde(rename = "outerConeAngle", default = "default_outer_cone_angle")]
+    m_outer_cone_angle: Real,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct NodeExtensions {
+    #[serde(rename = "KHR_lights_punctual", default)]
+    m_light: Option<NodeLight>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NodeLight {
+    #[serde(rename = "light")]
+    m_light: usize,
+}
+
+fn default_mode() -> u32 {
+    return 4;
+}
+
+fn default_factor() -> [f32; 4] {
+    return [1.0; 4];
+}
+
+fn default_color() -> [f32; 3] {
+    return [1.0; 3];
+}
+
+fn default_scalar_factor() -> f32 {
+    return 1.0;
+}
+
+fn default_intensity() -> f32 {
+    return 1.0;
+}
+
+fn default_outer_cone_angle() -> Real {
+    return Real::PI / 4.0;
+}
+
+/* a white rough metal, glTF's default, when pbrMetallicRoughness is absent */
+impl Default for Pbr {
+    fn default() -> Pbr {
+        return Pbr {
+            m_base_color_factor: default_factor(),
+            m_base_color_texture: None,
+            m_metallic_factor: default_scalar_factor(),
+            m_roughness_factor: default_scalar_factor(),
+            m_metallic_roughness_texture: None,
+        };
+    }
+}
+
+/* extensions a document may require, the ones it only uses are ignored when unknown */
+const SUPPORTED_EXTENSIONS: [&str; 1] = ["KHR_lights_punctual"];
+
+/* values of an accessor without a buffer view, its zeros are not backed by any data to bound
+their count */
+const MAX_ZEROED_VALUES: usize = 1 << 24;
+
+/* chunk types of a GLB container */
+const GLB_JSON: u32 = 0x4E4F534A;
+const GLB_BIN: u32 = 0x004E4942;
+
+/* reads a .gltf document or a binary .glb container */
+pub fn load(path: &str) -> Result<Model, Error> {
+    return parse(&loader::read_bytes(path)?, path);
+}
+
+/* bytes is the content of the file at path, external buffers and images are found relative to it.
+Every scene node becomes a part placed by the product of the transforms from the root down,
+every camera and punctual light a model camera and light placed the same way */
+pub fn parse(bytes: &[u8], path: &str) -> Result<Model, Error> {
+    let (json, bin) = if bytes.starts_with(b"glTF") {
+        glb(bytes, path)?
+    } else {
+        (bytes, None)
+    };
+    let document: Document = serde_json::from_slice(json).map_err(|e| Error::json(path, e))?;
+    let importer = Importer::new(document, bin, path)?;
+    return importer.model();
+}
+
+/* splits a GLB container into its JSON chunk and the binary chunk that may follow it */
+fn glb<'a>(bytes: &'a [u8], path: &str) -> Result<(&'a [u8], Option<&'a [u8]>), Error> {
+    let malformed = |message: String| Error::Malformed {
+        m_path: String::from(path),
+        m_message: message,
+    };
+    let word = |at: usize| {
+        bytes
+            .get(at..at + 4)
+            .map(|w| u32::from_le_bytes([w[0], w[1], w[2], w[3]]))
+    };
+    let (version, length) = match (word(4), word(8)) {
+        (Some(version), Some(length)) => (version, length as usize),
+        _ => return Err(malformed(String::from("too short for a GLB header"))),
+    };
+    if version != 2 {
+        return Err(malformed(format!("GLB version {}, 2 expected", version)));
+    }
+    if length > bytes.len() {
+        return Err(malformed(format!(
+            "the header announces {} bytes, found {}",
+            length,
+            bytes.len()
+        )));
+    }
+
+    let mut chunks = Vec::new();
+    let mut at = 12;
+    while at + 8 <= length {
+        let size = word(at).unwrap_or(0) as usize;
+        let kind = word(at + 4).unwrap_or(0);
+        let data = bytes
+            .get(at + 8..at + 8 + size)
+            .filter(|_| at + 8 + size <= length)
+            .ok_or_else(|| malformed(format!("the chunk at byte {} runs past the end", at)))?;
+        chunks.push((kind, data));
+        at += 8 + size;
+    }
+    return match chunks.as_slice() {
+        [(GLB_JSON, json), rest @ ..] => Ok((
+            json,
+            rest.first()
+                .filter(|(kind, _)| *kind == GLB_BIN)
+                .map(|(_, data)| *data),
+        )),
+        _ => Err(malformed(String::from("the first chunk is not JSON"))),
+    };
+}
+
+/* a parsed document with the content of its buffers */
+struct Importer<'a> {
+    m_path: &'a str,
+    m_document: Document,
+    m_buffers: Vec<Vec<u8>>,
+}
+
+/* Associative functions implementation block */
+impl<'a> Importer<'a> {
+    /* Constructor, checks the version and required extensions then reads every buffer */
+    fn new(document: Document, bin: Option<&[u8]>, path: &'a str) -> Result<Importer<'a>, Error> {
+        let malformed = |message: String| Error::Malformed {
+            m_path: String::from(path),
+            m_message: message,
+        };
+        if !document.m_asset.m_version.starts_with("2.") {
+            return Err(malformed(format!(
+                "glTF version {}, 2.x expected",
+                document.m_asset.m_version
+            )));
+        }
+        if let Some(extension) = document
+            .m_extensions_required
+            .iter()
+            .find(|e| !SUPPORTED_EXTENSIONS.contains(&e.as_str()))
+        {
+            return Err(malformed(format!(
+                "requires the unsupported extension {}",
+                extension
+            )));
+        }
+
+        let mut buffers = Vec::with_capacity(document.m_buffers.len());
+        for (i, buffer) in document.m_buffers.iter().enumerate() {
+            let data = match &buffer.m_uri {
+                Some(uri) => resolve(uri, path)?,
+                None if i == 0 => bin
+                    .ok_or_else(|| {
+                        malformed(String::from("/buffers/0 has no uri and no GLB chunk"))
+                    })?
+                    .to_vec(),
+                None => {
+                    return Err(Error::Missing {
+                        m_path: String::from(path),
+                        m_pointer: format!("/buffers/{}/uri", i),
+                    });
+                }
+            };
+            if data.len() < buffer.m_byte_length {
+                return Err(malformed(format!(
+                    "/buffers/{} should have {} bytes, found {}",
+                    i,
+                    buffer.m_byte_length,
+                    data.len()
+                )));
+            }
+            buffers.push(data);
+        }
+        return Ok(Importer {
+            m_path: path,
+            m_document: document,
+            m_buffers: buffers,
+        });
+    }
+}
+
+/* Associative methods implementation block */
+impl Importer<'_> {
+    fn model(&self) -> Result<Model, Error> {
+        let document = &self.m_document;
+        let mut model = Model::default();
+
+        let mut textures = HashMap::new();
+        for (i, material) in document.m_materials.iter().enumerate() {
+            model
+                .m_materials
+                .push(self.material(i, material, &mut textures)?);
+        }
+        let mut meshes = Vec::with_capacity(document.m_meshes.len());
+        for (i, mesh) in document.m_meshes.iter().enumerate() {
+            let mut primitives = Vec::new();
+            for (j, primitive) in mesh.m_primitives.iter().enumerate() {
+                let pointer = format!("/meshes/{}/primitives/{}", i, j);
+                if let Some(material) = primitive.m_material {
+                    self.get(&document.m_materials, material, "materials")?;
+                }
+                if let Some(mesh) = self.primitive(primitive, &pointer)? {
+                    primitives.push((Arc::new(mesh), primitive.m_material));
+                }
+            }
+            meshes.push(primitives);
+        }
+
+        /* the default scene, or the nodes no other node holds when there is none */
+        let roots: Vec<usize> = match document.m_scene.or(if document.m_scenes.is_empty() {
+            None
+        } else {
+            Some(0)
+        }) {
+            Some(scene) => self
+                .get(&document.m_scenes, scene, "scenes")?
+                .m_nodes
+                .clone(),
+            None => (0..document.m_nodes.len())
+                .filter(|i| !document.m_nodes.iter().any(|n| n.m_children.contains(i)))
+                .collect(),
+        };
+
+        /* depth first with a stack of its own, deep hierarchies would overflow the call stack.
+        The nodes form disjoint trees, a node reached twice closes a cycle or has two parents */
+        let mut reached = vec![false; document.m_nodes.len()];
+        let mut stack: Vec<(usize, Matrix4<Real>)> = roots
+            .into_iter()
+            .rev()
+            .map(|root| (root, Matrix4::identity()))
+            .collect();
+        while let Some((index, parent)) = stack.pop() {
+            let node = self.get(&document.m_nodes, index, "nodes")?;
+            if std::mem::replace(&mut reached[index], true) {
+                return Err(self.malformed(format!(
+                    "/nodes/{} is its own ancestor or has several parents",
+                    index
+                )));
+            }
+            let world = parent * local(node);
+            self.visit(index, &world, &meshes, &mut model)?;
+            stack.extend(node.m_children.iter().rev().map(|&child| (child, world)));
+        }
+        return Ok(model);
+    }
+
+    /* adds what node holds to the model, world being its world transform */
+    fn visit(
+        &self,
+        index: usize,
+        world: &Matrix4<Real>,
+        meshes: &[Vec<(Arc<Mesh>, Option<usize>)>],
+        model: &mut Model,
+    ) -> Result<(), Error> {
+        let document = &self.m_document;
+        let node = &document.m_nodes[index];
+
+        if let Some(mesh) = node.m_mesh {
+            self.get(&document.m_meshes, mesh, "meshes")?;
+            for (mesh, material) in &meshes[mesh] {
+                model.m_parts.push(ModelPart {
+                    m_mesh: mesh.clone(),
+                    m_material: *material,
+                    m_transform: *world,
+                });
+            }
+        }
+        if let Some(camera) = node.m_camera {
+            model.m_cameras.push(self.camera(camera, world)?);
+        }
+        if let Some(light) = &node.m_extensions.m_light {
+            let lights = document
+                .m_extensions
+                .m_lights_punctual
+                .as_ref()
+                .map_or(&[][..], |l| &l.m_lights[..]);
+            let light = self.get(
+                lights,
+                light.m_light,
+                "extensions/KHR_lights_punctual/lights",
+            )?;
+            let kind = match (light.m_type.as_str(), &light.m_spot) {
+                ("point", _) => Some(ModelLightKind::Point),
+                ("directional", _) => Some(ModelLightKind::Directional),
+                ("spot", spot) => {
+                    let (inner, outer) = spot
+                        .as_ref()
+                        .map_or((0.0, default_outer_cone_angle()), |spot| {
+                            (spot.m_inner_cone_angle, spot.m_outer_cone_angle)
+                        });
+                    Some(ModelLightKind::Spot(inner.to_degrees(), outer.to_degrees()))
+                }
+                _ => None,
+            };
+            match kind {
+                /* lights shine down the -z axis of their node */
+                Some(kind) => model.m_lights.push(ModelLight {
+                    m_kind: kind,
+                    m_position: world.transform_point(&Point3::origin()),
+                    m_direction: world
+                        .transform_direction(&Direction3::new((0.0, 0.0, -1.0)))
+                        .unit(),
+                    m_color: Vector3::new((light.m_color[0], light.m_color[1], light.m_color[2])),
+                    m_intensity: light.m_intensity,
+                }),
+                None => log::warn!(
+                    "{}: /nodes/{} has a {} light, which is not imported",
+                    self.m_path,
+                    index,
+                    light.m_type
+                ),
+            }
+        }
+        return Ok(());
+    }
+
+    /* the triangles of a primitive, None for points and lines */
+    fn primitive(&self, primitive: &Primitive, pointer: &str) -> Result<Option<Mesh>, Error> {
+        if !(4..=6).contains(&primitive.m_mode) {
+            log::warn!(
+                "{}: {} has mode {}, only triangles are imported",
+                self.m_path,
+                pointer,
+                primitive.m_mode
+            );
+            return Ok(None);
+        }
+        let attribute =
+            |name: &str, components: &[usize]| -> Result<Option<(Vec<Real>, usize)>, Error> {
+                let Some(&accessor) = primitive.m_attributes.get(name) else {
+                    return Ok(None);
+                };
+                let (values, n) = self.accessor(accessor)?;
+                if !components.contains(&n) {
+                    return Err(self.malformed(format!(
+                        "{}/attributes/{} has {} components",
+                        pointer, name, n
+                    )));
+                }
+                return Ok(Some((values, n)));
+            };
+
+        let (positions, _) = attribute("POSITION", &[3])?.ok_or_else(|| Error::Missing {
+            m_path: String::from(self.m_path),
+            m_pointer: format!("{}/attributes/POSITION", pointer),
+        })?;
+        let positions: Vec<Point3<Real>> = positions
+            .chunks_exact(3)
+            .map(|p| Point3::new((p[0], p[1], p[2])))
+            .collect();
+        let count = positions.len();
+        /* without normals the triangles keep their flat ones, as the specification asks */
+        let normals = attribute("NORMAL", &[3])?.map_or(Vec::new(), |(n, _)| {
+            n.chunks_exact(3)
+                .map(|n| Normal3::new((n[0], n[1], n[2])))
+                .collect()
+        });
+        /* glTF puts v = 0 at the top of the image, the textures at the bottom */
+        let uvs = attribute("TEXCOORD_0", &[2])?.map_or(Vec::new(), |(uv, _)| {
+            uv.chunks_exact(2)
+                .map(|uv| Vector2::new((uv[0], 1.0 - uv[1])))
+                .collect()
+        });
+        let colors = attribute("COLOR_0", &[3, 4])?.map_or(Vec::new(), |(c, n)| {
+            c.chunks_exact(n)
+                .map(|c| Vector3::new((c[0] as f32, c[1] as f32, c[2] as f32)))
+                .collect()
+        });
+
+        let indices: Vec<u32> = match primitive.m_indices {
+            Some(accessor) => self
+                .accessor(accessor)?
+                .0
+                .iter()
+                .map(|&i| i as u32)
+                .collect(),
+            None => (0..count as u32).collect(),
+        };
+        if let Some(i) = indices.iter().find(|&&i| i as usize >= count) {
+            return Err(self.malformed(format!(
+                "{}/indices: index {} of {} vertices",
+                pointer, i, count
+            )));
+        }
+        let triangles: Vec<[u32; 3]> = match primitive.m_mode {
+            4 => indices
+                .chunks_exact(3)
+                .map(|t| [t[0], t[1], t[2]])
+                .collect(),
+            /* every other triangle of a strip is flipped back to counter-clockwise */
+            5 => (2..indices.len())
+                .map(|i| match i % 2 {
+                    0 => [indices[i - 2], indices[i - 1], indices[i]],
+                    _ => [indices[i - 1], indices[i - 2], indices[i]],
+                })
+                .collect(),
+            _ => (2..indices.len())
+                .map(|i| [indices[0], indices[i - 1], indices[i]])
+                .collect(),
+        };
+
+        let mut mesh = Mesh::new(positions, normals, uvs, triangles);
+        mesh.m_colors = colors;
+        if !mesh.is_valid() {
+            return Err(self.malformed(format!(
+                "{}: the attributes do not have one entry per position",
+                pointer
+            )));
+        }
+        return Ok(Some(mesh));
+    }
+
+    /* the elements of an accessor flattened into reals, with the number of components of each.
+    Normalized integers map to [0, 1], or [-1, 1] when signed */
+    fn accessor(&self, index: usize) -> Result<(Vec<Real>, usize), Error> {
+        let accessor = self.get(&self.m_document.m_accessors, index, "accessors")?;
+        if accessor.m_sparse.is_some() {
+            return Err(self.malformed(format!(
+                "/accessors/{}: sparse accessors are not supported",
+                index
+            )));
+        }
+        let components = match accessor.m_type.as_str() {
+            "SCALAR" => 1,
+            "VEC2" => 2,
+            "VEC3" => 3,
+            "VEC4" => 4,
+            other => {
+                return Err(self.malformed(format!(
+                    "/accessors/{}: {} accessors are not supported",
+                    index, other
+                )));
+            }
+        };
+        let size = match accessor.m_component_type {
+            5120 | 5121 => 1,
+            5122 | 5123 => 2,
+            5125 | 5126 => 4,
+            other => {
+                return Err(self.malformed(format!(
+                    "/accessors/{}: unknown component type {}",
+                    index, other
+                )));
+            }
+        };
+        let count = accessor.m_count;
+        let Some(view) = accessor.m_buffer_view else {
+            return match count.checked_mul(components) {
+                Some(length) if length <= MAX_ZEROED_VALUES => Ok((vec![0.0; length], components)),
+                _ => Err(self.malformed(format!(
+                    "/accessors/{}: {} elements without a buffer view",
+                    index, count
+                ))),
+            };
+        };
+
+        let data = self.view(view)?;
+        let element_size = components * size;
+        let stride = self.m_document.m_buffer_views[view]
+            .m_byte_stride
+            .unwrap_or(element_size);
+        /* overlapping elements would let a short view claim any count */
+        if stride < element_size {
+            return Err(self.malformed(format!(
+                "/bufferViews/{}: stride {} below the {} bytes of /accessors/{}",
+                view, stride, element_size, index
+            )));
+        }
+        /* every offset is checked, byteOffset and count come straight from the file */
+        let offset = |element: usize, c: usize| {
+            return stride
+                .checked_mul(element)
+                .and_then(|o| o.checked_add(accessor.m_byte_offset))
+                .and_then(|o| o.checked_add(c * size));
+        };
+        let end = match count {
+            0 => Some(0),
+            _ => offset(count - 1, components).filter(|&end| end <= data.len()),
+        };
+        let past_end = || {
+            return self.malformed(format!(
+                "/accessors/{} runs past the end of /bufferViews/{}",
+                index, view
+            ));
+        };
+        end.ok_or_else(past_end)?;
+
+        let mut values = Vec::with_capacity(count * components);
+        for element in 0..count {
+            for c in 0..components {
+                let bytes = offset(element, c)
+                    .and_then(|at| data.get(at..at.checked_add(size)?))
+                    .ok_or_else(past_end)?;
+                values.push(component(
+                    accessor.m_component_type,
+                    accessor.m_normalized,
+                    bytes,
+                ));
+            }
+        }
+        return Ok((values, components));
+    }
+
+    /* the bytes of a buffer view */
+    fn view(&self, index: usize) -> Result<&[u8], Error> {
+        let view = self.get(&self.m_document.m_buffer_views, index, "bufferViews")?;
+        let buffer = self
+            .m_buffers
+            .get(view.m_buffer)
+            .ok_or_else(|| self.malformed(format!("no /buffers/{}", view.m_buffer)))?;
+        return buffer
+            .get(view.m_byte_offset..view.m_byte_offset.saturating_add(view.m_byte_length))
+            .ok_or_else(|| {
+                self.malformed(format!(
+                    "/bufferViews/{} runs past the end of /buffers/{}",
+                    index, view.m_buffer
+                ))
+            });
+    }
+
+    /* the base color, metallic and roughness factors of the metallic-roughness model. glTF
+    multiplies the base color by the vertex colors of the primitives that have them */
+    fn material(
+        &self,
+        index: usize,
+        material: &MaterialDef,
+        textures: &mut HashMap<usize, Arc<Texture>>,
+    ) -> Result<ModelMaterial, Error> {
+        let [r, g, b, _] = material.m_pbr.m_base_color_factor;
+        let mut result = ModelMaterial::new(
+            material
+                .m_name
+                .clone()
+                .unwrap_or_else(|| format!("material {}", index)),
+            Vector3::new((r, g, b)),
+        );
+        result.m_vertex_colors = true;
+        result.m_metallic_roughness = Some((
+            material.m_pbr.m_metallic_factor.clamp(0.0, 1.0),
+            material.m_pbr.m_roughness_factor.clamp(0.0, 1.0),
+        ));
+        if material.m_pbr.m_metallic_roughness_texture.is_some() {
+            log::warn!(
+                "{}: /materials/{} has a metallicRoughnessTexture, only the factors are imported",
+                self.m_path,
+                index
+            );
+        }
+        if let Some(info) = &material.m_pbr.m_base_color_texture {
+            if info.m_tex_coord != 0 {
+                log::warn!(
+                    "{}: /materials/{} uses TEXCOORD_{}, only TEXCOORD_0 is imported",
+                    self.m_path,
+                    index,
+                    info.m_tex_coord
+                );
+            }
+            let texture = self.get(&self.m_document.m_textures, info.m_index, "textures")?;
+            if let Some(source) = texture.m_source {
+                let texture = match textures.get(&source) {
+                    Some(texture) => texture.clone(),
+                    None => Arc::new(self.image(source)?),
+                };
+                textures.insert(source, texture.clone());
+                result.m_texture = Some(texture);
+            }
+        }
+        return Ok(result);
+    }
+
+    /* decodes an image from a file, a data: URI or a buffer view */
+    fn image(&self, index: usize) -> Result<Texture, Error> {
+        let image = self.get(&self.m_document.m_images, index, "images")?;
+        let bytes = match (&image.m_uri, image.m_buffer_view) {
+            (Some(uri), _) => resolve(uri, self.m_path)?,
+            (None, Some(view)) => self.view(view)?.to_vec(),
+            (None, None) => {
+                return Err(Error::Missing {
+                    m_path: String::from(self.m_path),
+                    m_pointer: format!("/images/{}/uri", index),
+                });
+            }
+        };
+        let decoded = image::load_from_memory(&bytes)
+            .map_err(|e| self.malformed(format!("/images/{}: {}", index, e)))?;
+        return Ok(Texture::new(&decoded.to_rgba8()));
+    }
+
+    fn camera(&self, index: usize, world: &Matrix4<Real>) -> Result<ModelCamera, Error> {
+        let camera = self.get(&self.m_document.m_cameras, index, "cameras")?;
+        let missing = |field: &str| Error::Missing {
+            m_path: String::from(self.m_path),
+            m_pointer: format!("/cameras/{}/{}", index, field),
+        };
+        let (projection, aspect) = match camera.m_type.as_str() {
+            "perspective" => {
+                let p = camera
+                    .m_perspective
+                    .as_ref()
+                    .ok_or_else(|| missing("perspective"))?;
+                if !(p.m_yfov > 0.0 && p.m_yfov < Real::PI) {
+                    return Err(self.malformed(format!(
+                        "/cameras/{}/perspective/yfov is {}, between 0 and pi expected",
+                        index, p.m_yfov
+                    )));
+                }
+                if let Some(aspect) = p.m_aspect_ratio.filter(|a| !(a.is_finite() && *a > 0.0)) {
+                    return Err(self.malformed(format!(
+                        "/cameras/{}/perspective/aspectRatio is {}, a positive number expected",
+                        index, aspect
+                    )));
+                }
+                (
+                    ModelProjection::Perspective(p.m_yfov.to_degrees()),
+                    p.m_aspect_ratio,
+                )
+            }
+            "orthographic" => {
+                let o = camera
+                    .m_orthographic
+                    .as_ref()
+                    .ok_or_else(|| missing("orthographic"))?;
+                (
+                    ModelProjection::Orthographic(2.0 * o.m_ymag),
+                    Some(o.m_xmag / o.m_ymag).filter(|a| a.is_finite() && *a > 0.0),
+                )
+            }
+            other => {
+                return Err(
+                    self.malformed(format!("/cameras/{} has unknown type {}", index, other))
+                );
+            }
+        };
+        return Ok(ModelCamera {
+            m_transform: *world,
+            m_projection: projection,
+            m_aspect: aspect,
+        });
+    }
+
+    /* list[index], named by its JSON pointer when out of range */
+    fn get<'b, T>(&self, list: &'b [T], index: usize, name: &str) -> Result<&'b T, Error> {
+        return list
+            .get(index)
+            .ok_or_else(|| self.malformed(format!("no /{}/{}", name, index)));
+    }
+
+    fn malformed(&self, message: String) -> Error {
+        return Error::Malformed {
+            m_path: String::from(self.m_path),
+            m_message: message,
+        };
+    }
+}
+
+/* the matrix of a node, or its translation * rotation * scale */
+fn local(node: &Node) -> Matrix4<Real> {
+    if let Some(values) = node.m_matrix {
+        return Matrix4::new(values).transpose();
+    }
+    let [tx, ty, tz] = node.m_translation.unwrap_or([0.0; 3]);
+    let [x, y, z, w] = node.m_rotation.unwrap_or([0.0, 0.0, 0.0, 1.0]);
+    let [sx, sy, sz] = node.m_scale.unwrap_or([1.0; 3]);
+    return Transform::trs(
+        Vector3::new((tx, ty, tz)),
+        Quaternion::new((x, y, z, w)).unit(),
+        Vector3::new((sx, sy, sz)),
+    )
+    .to_matrix4();
+}
+
+/* one little endian component of an accessor */
+fn component(kind: u32, normalized: bool, b: &[u8]) -> Real {
+    let (value, range) = match kind {
+        5120 => (b[0] as i8 as Real, 127.0),
+        5121 => (b[0] as Real, 255.0),
+        5122 => (i16::from_le_bytes([b[0], b[1]]) as Real, 32767.0),
+        5123 => (u16::from_le_bytes([b[0], b[1]]) as Real, 65535.0),
+        5125 => (
+            u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as Real,
+            4294967295.0,
+        ),
+        _ => return f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as Real,
+    };
+    return if normalized {
+        (value / range).max(-1.0)
+    } else {
+        value
+    };
+}
+
+/* the content a uri of the document at path refers to, either a data: URI or a relative file */
+fn resolve(uri: &str, path: &str) -> Result<Vec<u8>, Error> {
+    let Some(data) = uri.strip_prefix("data:") else {
+        return loader::read_bytes(&loader::relative_to(path, &unescape(uri)));
+    };
+    let malformed = |message: &str| Error::Malformed {
+        m_path: String::from(path),
+        m_message: format!("{} in a data URI", message),
+    };
+    return match data.split_once(',') {
+        Some((header, payload)) if header.ends_with(";base64") => {
+            base64(payload).ok_or_else(|| malformed("invalid base64"))
+        }
+        Some(_) => Err(malformed("no base64 encoding")),
+        None => Err(malformed("no data")),
+    };
+}
+
+/* decodes the %XX escapes of a relative URI */
+fn unescape(uri: &str) -> String {
+    let bytes = uri.as_bytes();
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .filter(|_| bytes[i] == b'%')
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                result.push(byte);
+                i += 3;
+            }
+            None => {
+                result.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    return String::from_utf8_lossy(&result).into_owned();
+}
+
+/* standard or URL-safe alphabet, padding optional. None on any other character */
+fn base64(text: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(text.len() / 4 * 3);
+    let (mut bits, mut count) = (0u32, 0);
+    for c in text.trim_end_matches('=').bytes() {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            _ => return None,
+        };
+        bits = (bits << 6 | value as u32) & 0xffffff;
+        count += 6;
+        if count >= 8 {
+            count -= 8;
+            bytes.push((bits >> count) as u8);
+        }
+    }
+    return Some(bytes);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::malformed;
+
+    #[test]
+    fn fixtures() {
+        let model = load("res/gltf/fixture.gltf").unwrap();
+        for path in ["res/gltf/fixture.glb", "res/gltf/fixture_embedded.gltf"] {
+            let other = load(path).unwrap();
+            assert_eq!(other.m_materials, model.m_materials);
+            assert_eq!(other.m_cameras, model.m_cameras);
+            assert_eq!(other.m_lights, model.m_lights);
+            for (a, b) in model.m_parts.iter().zip(&other.m_parts) {
+                assert_eq!(a.m_transform, b.m_transform);
+                assert_eq!(a.m_mesh.m_positions, b.m_mesh.m_positions);
+            }
+        }
+
+        /* cube and sign under the root, then the floor */
+        let [cube, sign, floor] = &model.m_parts[..] else {
+            panic!("unexpected {:?}", model.m_parts);
+        };
+        assert_eq!(cube.m_mesh.m_triangles.len(), 12);
+        assert_eq!(
+            cube.m_mesh.m_colors[0],
+            Vector3::new((200.0, 40.0, 40.0)) / 255.0
+        );
+        assert_eq!(floor.m_transform, Matrix4::scale(4.0, 1.0, 4.0));
+        assert_eq!(floor.m_mesh.m_uvs[0], Vector2::new((0.0, -1.0)));
+
+        /* root * arm * sign, the arm given as a column-major matrix */
+        let expected = Matrix4::rotate_y(30.0)
+            * Matrix4::translate(1.5, 0.0, 0.0)
+            * Matrix4::scale(0.5, 0.5, 0.5)
+            * Matrix4::translate(0.0, 1.0, 0.0);
+        assert!(sign.m_transform.approx_eq(&expected, 1e-6));
+        /* the strip's second triangle keeps facing +z */
+        assert_eq!(sign.m_mesh.m_triangles, vec![[0, 1, 2], [2, 1, 3]]);
+        assert!(sign.m_mesh.m_normals.is_empty());
+
+        let materials: Vec<(&str, bool)> = model
+            .m_materials
+            .iter()
+            .map(|m| (m.m_name.as_str(), m.m_texture.is_some()))
+            .collect();
+        assert_eq!(
+            materials,
+            [("checker", true), ("cube", false), ("sign", false)]
+        );
+        assert_eq!(model.m_materials[2].m_color, Vector3::new((0.2, 0.8, 0.2)));
+        assert_eq!(model.m_materials[1].m_metallic_roughness, Some((0.0, 1.0)));
+        /* without pbrMetallicRoughness, glTF's white rough metal */
+        let default: MaterialDef = serde_json::from_str("{}").unwrap();
+        assert_eq!(
+            (
+                default.m_pbr.m_metallic_factor,
+                default.m_pbr.m_roughness_factor
+            ),
+            (1.0, 1.0)
+        );
+        assert_eq!(floor.m_material, Some(0));
+
+        let camera = &model.m_cameras[0];
+        assert_eq!(
+            camera.m_projection,
+            ModelProjection::Perspective(0.8f64.to_degrees())
+        );
+        assert_eq!(camera.m_aspect, Some(1.5));
+        let forward = camera
+            .m_transform
+            .transform_direction(&Direction3::new((0.0, 0.0, -1.0)));
+        assert!(forward.get_y() < 0.0 && forward.get_z() < 0.0);
+        assert_eq!(
+            model.m_lights,
+            [ModelLight {
+                m_kind: ModelLightKind::Point,
+                m_position: Point3::new((2.0, 4.0, 3.0)),
+                m_direction: Direction3::new((0.0, 0.0, -1.0)),
+                m_color: Vector3::new((1.0, 1.0, 1.0)),
+                m_intensity: 100.0,
+            }]
+        );
+    }
+
+    #[test]
+    fn lights() {
+        /* a spot turned to shine down and a directional light along the default -z */
+        let source = r#"{"asset": {"version": "2.0"},
+            "extensions": {"KHR_lights_punctual": {"lights": [
+                {"type": "spot", "intensity": 5, "spot": {"innerConeAngle": 0.25, "outerConeAngle": 0.5}},
+                {"type": "directional", "color": [1, 0.5, 0]},
+                {"type": "area"}]}},
+            "nodes": [
+                {"translation": [0, 3, 0], "rotation": [-0.70710678, 0, 0, 0.70710678],
+                 "extensions": {"KHR_lights_punctual": {"light": 0}}},
+                {"extensions": {"KHR_lights_punctual": {"light": 1}}},
+                {"extensions": {"KHR_lights_punctual": {"light": 2}}}]}"#;
+        let model = parse(source.as_bytes(), "lights.gltf").unwrap();
+        assert_eq!(model.m_lights.len(), 2);
+
+        let spot = &model.m_lights[0];
+        assert_eq!(
+            spot.m_kind,
+            ModelLightKind::Spot(0.25f64.to_degrees(), 0.5f64.to_degrees())
+        );
+        assert_eq!(spot.m_intensity, 5.0);
+        assert!(
+            spot.m_position
+                .approx_eq(&Point3::new((0.0, 3.0, 0.0)), 1e-6)
+        );
+        assert!(
+            spot.m_direction
+                .approx_eq(&Direction3::new((0.0, -1.0, 0.0)), 1e-6)
+        );
+
+        let sun = &model.m_lights[1];
+        assert_eq!(sun.m_kind, ModelLightKind::Directional);
+        assert_eq!(sun.m_direction, Direction3::new((0.0, 0.0, -1.0)));
+        assert_eq!(sun.m_color, Vector3::new((1.0, 0.5, 0.0)));
+    }
+
+    #[test]
+    fn errors() {
+        let message = |source: &str| malformed(parse(source.as_bytes(), "bad.gltf"));
+        assert_eq!(
+            message(r#"{"asset": {"version": "1.0"}}"#),
+            "glTF version 1.0, 2.x expected"
+        );
+        assert_eq!(
+            message(
+                r#"{"asset": {"version": "2.0"}, "extensionsRequired": ["KHR_draco_mesh_compression"]}"#
+            ),
+            "requires the unsupported extension KHR_draco_mesh_compression"
+        );
+        assert_eq!(
+            message(
+                r#"{"asset": {"version": "2.0"}, "nodes": [{"children": [0]}], "scenes": [{"nodes": [0]}]}"#
+            ),
+            "/nodes/0 is its own ancestor or has several parents"
+        );
+        assert_eq!(
+            message(
+                r#"{"asset": {"version": "2.0"}, "nodes": [{"children": [1]}, {"children": [2]}, {}],
+                    "scenes": [{"nodes": [0, 2]}]}"#
+            ),
+            "/nodes/2 is its own ancestor or has several parents"
+        );
+        /* three zero positions, without a buffer view */
+        let primitive = |extra: &str| {
+            format!(
+                r#"{{"asset": {{"version": "2.0"}}, "meshes": [{{"primitives": [{{{}}}]}}],
+                    "accessors": [{{"componentType": 5126, "count": 3, "type": "VEC3"}},
+                                  {{"componentType": 5126, "count": 3, "type": "SCALAR", "sparse": {{}}}}]}}"#,
+                extra
+            )
+        };
+        assert_eq!(
+            message(&primitive(r#""attributes": {"POSITION": 0}, "indices": 2"#)),
+            "no /accessors/2"
+        );
+        assert_eq!(
+            message(&primitive(r#""attributes": {"POSITION": 1}"#)),
+            "/accessors/1: sparse accessors are not supported"
+        );
+        assert!(matches!(
+            parse(primitive(r#""attributes": {"NORMAL": 0}"#).as_bytes(), "bad.gltf"),
+            Err(Error::Missing { m_pointer, .. }) if m_pointer == "/meshes/0/primitives/0/attributes/POSITION"
+        ));
+        assert!(
+            parse(
+                primitive(r#""attributes": {"POSITION": 0}"#).as_bytes(),
+                "ok.gltf"
+            )
+            .is_ok()
+        );
+
+        /* a chain far deeper than the call stack allows, then the same chain closed into a cycle */
+        let chain = |last: &str| {
+            let mut nodes: Vec<String> = (1..100000)
+                .map(|i| format!(r#"{{"children": [{}]}}"#, i))
+                .collect();
+            nodes.push(String::from(last));
+            format!(
+                r#"{{"asset": {{"version": "2.0"}}, "nodes": [{}], "scenes": [{{"nodes": [0]}}]}}"#,
+                nodes.join(",")
+            )
+        };
+        assert!(parse(chain("{}").as_bytes(), "deep.gltf").is_ok());
+        assert_eq!(
+            message(&chain(r#"{"children": [0]}"#)),
+            "/nodes/0 is its own ancestor or has several parents"
+        );
+
+        /* offsets and counts from the file that would overflow or allocate without bound */
+        let viewed = |accessor: &str| {
+            format!(
+                r#"{{"asset": {{"version": "2.0"}}, "meshes": [{{"primitives": [{{"attributes": {{"POSITION": 0}}}}]}}],
+                    "buffers": [{{"byteLength": 12, "uri": "data:application/octet-stream;base64,AAAAAAAAAAAAAAAA"}}],
+                    "bufferViews": [{{"buffer": 0, "byteLength": 12}}, {{"buffer": 0, "byteLength": 12, "byteStride": 4}}],
+                    "accessors": [{{"componentType": 5126, "type": "VEC3", {}}}]}}"#,
+                accessor
+            )
+        };
+        let past_end = "/accessors/0 runs past the end of /bufferViews/0";
+        assert_eq!(
+            message(&viewed(
+                r#""bufferView": 0, "byteOffset": 18446744073709551615, "count": 1"#
+            )),
+            past_end
+        );
+        assert_eq!(
+            message(&viewed(r#""bufferView": 0, "count": 18446744073709551615"#)),
+            past_end
+        );
+        assert_eq!(
+            message(&viewed(r#""bufferView": 1, "count": 3"#)),
+            "/bufferViews/1: stride 4 below the 12 bytes of /accessors/0"
+        );
+        assert_eq!(
+            message(&viewed(r#""count": 6148914691236517206"#)),
+            "/accessors/0: 6148914691236517206 elements without a buffer view"
+        );
+        assert_eq!(
+            message(&viewed(r#""count": 100000000"#)),
+            "/accessors/0: 100000000 elements without a buffer view"
+        );
+
+        let mut glb = std::fs::read("res/gltf/fixture.glb").unwrap();
+        glb[4] = 1;
+        assert!(matches!(
+            parse(&glb, "v1.glb"),
+            Err(Error::Malformed { .. })
+        ));
+        glb[4] = 2;
+        glb.truncate(100);
+        assert!(matches!(
+            parse(&glb, "short.glb"),
+            Err(Error::Malformed { .. })
+        ));
+
+        /* perspective cameras without a usable field of view or aspect ratio */
+        let camera = |perspective: &str| {
+            format!(
+                r#"{{"asset": {{"version": "2.0"}}, "nodes": [{{"camera": 0}}],
+                    "cameras": [{{"type": "perspective", "perspective": {{"znear": 0.1, {}}}}}]}}"#,
+                perspective
+            )
+        };
+        assert_eq!(
+            message(&camera(r#""yfov": 0"#)),
+            "/cameras/0/perspective/yfov is 0, between 0 and pi expected"
+        );
+        assert!(message(&camera(r#""yfov": 3.5"#)).contains("/yfov is 3.5"));
+        assert_eq!(
+            message(&camera(r#""yfov": 0.8, "aspectRatio": -1"#)),
+            "/cameras/0/perspective/aspectRatio is -1, a positive number expected"
+        );
+        assert!(message(&camera(r#""yfov": 0.8, "aspectRatio": 0"#)).contains("/aspectRatio is 0"));
+    }
+
+    #[test]
+    fn uris() {
+        assert_eq!(base64("TWFu").unwrap(), b"Man");
+        assert_eq!(base64("TWE=").unwrap(), b"Ma");
+        assert_eq!(base64("TQ").unwrap(), b"M");
+        assert_eq!(base64("-_8=").unwrap(), [0xfb, 0xff]);
+        assert!(base64("TW!u").is_none());
+        assert_eq!(unescape("checker%20board.png"), "checker board.png");
+        assert_eq!(unescape("100%.png"), "100%.png");
+        assert_eq!(
+            resolve("data:text/plain;base64,TWFu", "a.gltf").unwrap(),
+            b"Man"
+        );
+        assert!(resolve("data:text/plain,Man", "a.gltf").is_err());
+    }
+}
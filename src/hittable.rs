@@ -0,0 +1,100 @@
+use crate::aabb::Aabb;
+use crate::normal3::Normal3;
+use crate::point3::Point3;
+use crate::ray::Ray;
+use crate::scalar::Real;
+use crate::vector2::Vector2;
+use crate::vector3::Vector3;
+
+#[derive(Debug)]
+pub struct HitRecord {
+    pub m_point: Point3<Real>,
+    /* always faces against the incoming ray, see m_front_face */
+    pub m_normal: Normal3<Real>,
+    pub m_t: Real,
+    /* true when the ray hit the outside of the surface */
+    pub m_front_face: bool,
+    /* surface parameterization, used as texture coordinates */
+    pub m_uv: Vector2<Real>,
+    /* interpolated vertex color, for meshes that have them */
+    pub m_color: Option<Vector3>,
+    /* index into the renderer's materials, filled in by Object */
+    pub m_material: usize,
+    /* index into the renderer's area lights when the surface is one, filled in by Object */
+    pub m_light: Option<usize>,
+}
+
+/* a shape paired with the material it is rendered with */
+pub struct Object {
+    pub m_shape: Box<dyn Hittable>,
+    pub m_material: usize,
+    /* the area light sampling this shape, for emissive ones */
+    pub m_light: Option<usize>,
+}
+
+pub trait Hittable: Send + Sync {
+    fn hit(&self, ray: &Ray, t_min: Real, t_max: Real) -> Option<HitRecord>;
+
+    /* world space box around the shape, None for unbounded shapes such as planes */
+    fn bounds(&self) -> Option<Aabb>;
+}
+
+/* Associative functions implementation block */
+impl HitRecord {
+    /* Constructor, outward_normal is expected to be unit length */
+    pub fn new(ray: &Ray, t: Real, outward_normal: Normal3<Real>, uv: Vector2<Real>) -> HitRecord {
+        /* grazing hits count as front facing */
+        let front_face = outward_normal.dot(&ray.m_direction) <= 0.0;
+        let normal = if front_face {
+            outward_normal
+        } else {
+            -outward_normal
+        };
+        return HitRecord {
+            m_point: ray.at(t),
+            m_normal: normal,
+            m_t: t,
+            m_front_face: front_face,
+            m_uv: uv,
+            m_color: None,
+            m_material: 0,
+            m_light: None,
+        };
+    }
+}
+
+/* Associative functions implementation block */
+impl Object {
+    /* Constructor */
+    pub fn new(shape: Box<dyn Hittable>, material: usize) -> Object {
+        return Object {
+            m_shape: shape,
+            m_material: material,
+            m_light: None,
+        };
+    }
+}
+
+/* lets hierarchies hold shapes of different types */
+impl Hittable for Box<dyn Hittable> {
+    fn hit(&self, ray: &Ray, t_min: Real, t_max: Real) -> Option<HitRecord> {
+        return self.as_ref().hit(ray, t_min, t_max);
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        return self.as_ref().bounds();
+    }
+}
+
+impl Hittable for Object {
+    fn hit(&self, ray: &Ray, t_min: Real, t_max: Real) -> Option<HitRecord> {
+        let mut rec = self.m_shape.hit(ray, t_min, t_max)?;
+        rec.m_material = self.m_material;
+        rec.m_light = self.m_light;
+        return Some(rec);
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        return self.m_shape.bounds();
+    }
+}
@@ -0,0 +1,109 @@
+use crate::aabb::Aabb;
+use crate::hittable::{HitRecord, Hittable};
+use crate::matrix3::Matrix3;
+use crate::matrix4::Matrix4;
+use crate::ray::Ray;
+use crate::scalar::Real;
+
+/* a shape placed in the world by an arbitrary affine transform */
+pub struct Instance {
+    m_shape: Box<dyn Hittable>,
+    m_transform: Matrix4<Real>,
+    m_inverse: Matrix4<Real>,
+    m_normal: Matrix3<Real>,
+}
+
+/* Associative functions implementation block */
+impl Instance {
+    /* Constructor, None when the transform is singular */
+    pub fn new(shape: Box<dyn Hittable>, transform: Matrix4<Real>) -> Option<Instance> {
+        let inverse = transform.inverse()?;
+        let normal = transform.normal_matrix()?;
+        return Some(Instance {
+            m_shape: shape,
+            m_transform: transform,
+            m_inverse: inverse,
+            m_normal: normal,
+        });
+    }
+}
+
+impl Hittable for Instance {
+    fn hit(&self, ray: &Ray, t_min: Real, t_max: Real) -> Option<HitRecord> {
+        /* the object space direction is left unnormalized so t means the same in both spaces */
+        let local = Ray::new(
+            self.m_inverse.transform_point(&ray.m_origin),
+            self.m_inverse.transform_direction(&ray.m_direction),
+        );
+        let mut rec = self.m_shape.hit(&local, t_min, t_max)?;
+
+        /* the inverse transpose keeps the sign of n.d, so the normal still faces against the ray */
+        rec.m_point = self.m_transform.transform_point(&rec.m_point);
+        rec.m_normal = (self.m_normal * rec.m_normal).unit();
+        return Some(rec);
+    }
+
+    /* box around the transformed corners of the shape's box */
+    fn bounds(&self) -> Option<Aabb> {
+        let local = self.m_shape.bounds()?;
+        return Some(local.corners().iter().fold(Aabb::empty(), |b, p| {
+            b.union_point(&self.m_transform.transform_point(p))
+        }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::direction3::Direction3;
+    use crate::normal3::Normal3;
+    use crate::point3::Point3;
+    use crate::sphere::Sphere;
+
+    #[test]
+    fn non_uniform_scale() {
+        /* unit sphere stretched into an ellipsoid with radii 2, 1, 1 */
+        let sphere = Box::new(Sphere::new(Point3::origin(), 1.0));
+        let instance = Instance::new(
+            sphere,
+            Matrix4::translate(0.0, 0.0, -5.0) * Matrix4::scale(2.0, 1.0, 1.0),
+        )
+        .unwrap();
+
+        let ray = Ray::new(
+            Point3::new((10.0, 0.0, -5.0)),
+            Direction3::new((-1.0, 0.0, 0.0)),
+        );
+        let rec = instance.hit(&ray, 0.001, Real::MAX).unwrap();
+        assert!((rec.m_t - 8.0).abs() < 1e-5);
+        assert!(rec.m_point.approx_eq(&Point3::new((2.0, 0.0, -5.0)), 1e-5));
+        assert!(rec.m_normal.approx_eq(&Normal3::new((1.0, 0.0, 0.0)), 1e-5));
+
+        /* off axis the normal is not the scaled object space normal, on (x/2)^2 + y^2 = 1 it is (x/4, y) */
+        let x = Real::sqrt(2.0);
+        let y = Real::sqrt(0.5);
+        let ray = Ray::new(
+            Point3::new((x, 5.0, -5.0)),
+            Direction3::new((0.0, -1.0, 0.0)),
+        );
+        let rec = instance.hit(&ray, 0.001, Real::MAX).unwrap();
+        assert!((rec.m_t - (5.0 - y)).abs() < 1e-5);
+        assert!(
+            rec.m_normal
+                .approx_eq(&Normal3::new((x / 4.0, y, 0.0)).unit(), 1e-5)
+        );
+        assert!(rec.m_front_face);
+
+        let ray = Ray::new(
+            Point3::new((0.0, 1.5, 0.0)),
+            Direction3::new((0.0, 0.0, -1.0)),
+        );
+        assert!(instance.hit(&ray, 0.001, Real::MAX).is_none());
+    }
+
+    #[test]
+    fn singular() {
+        let sphere = Box::new(Sphere::new(Point3::origin(), 1.0));
+        assert!(Instance::new(sphere, Matrix4::scale(1.0, 0.0, 1.0)).is_none());
+    }
+}
@@ -0,0 +1,104 @@
+use std::sync::Arc;
+
+use rand::rngs::StdRng;
+
+use crate::direction3::Direction3;
+use crate::hittable::HitRecord;
+use crate::material::{self, Lobe, Material, Scatter};
+use crate::ray::Ray;
+use crate::sampling;
+use crate::scalar::{Float, Real};
+use crate::texture::Texture;
+use crate::vector3::Vector3;
+
+/* ideal diffuse surface, reflects the same radiance in every direction */
+#[derive(Debug)]
+pub struct Lambertian {
+    pub m_albedo: Vector3,
+    /* multiplies the albedo by the vertex color of meshes that have them */
+    pub m_vertex_colors: bool,
+    /* multiplies the albedo by the texture at the hit's uv */
+    pub m_texture: Option<Arc<Texture>>,
+}
+
+/* Associative methods implementation block */
+impl Lambertian {
+    /* diffuse reflectance at the hit */
+    pub fn albedo(&self, rec: &HitRecord) -> Vector3 {
+        return material::albedo(self.m_albedo, self.m_vertex_colors, &self.m_texture, rec);
+    }
+}
+
+/* Associative functions implementation block */
+impl Lambertian {
+    /* Constructor */
+    pub fn new(albedo: Vector3) -> Lambertian {
+        return Lambertian {
+            m_albedo: albedo,
+            m_vertex_colors: false,
+            m_texture: None,
+        };
+    }
+}
+
+impl Material for Lambertian {
+    /* cosine weighted, the cosine and the density cancel out leaving the albedo */
+    fn scatter(&self, _ray: &Ray, rec: &HitRecord, rng: &mut StdRng) -> Option<Scatter> {
+        let direction = sampling::cosine_hemisphere(&rec.m_normal, rng);
+        return Some(Scatter {
+            m_ray: Ray::new(rec.m_point, direction),
+            m_attenuation: self.albedo(rec),
+            m_lobe: Lobe::Diffuse,
+        });
+    }
+
+    fn eval(&self, _ray: &Ray, rec: &HitRecord, direction: &Direction3<Real>) -> Vector3 {
+        if rec.m_normal.dot(direction) <= 0.0 {
+            return Vector3::zero();
+        }
+        return self.albedo(rec) / f32::PI;
+    }
+
+    fn pdf(&self, _ray: &Ray, rec: &HitRecord, direction: &Direction3<Real>) -> Real {
+        return rec.m_normal.dot(direction).max(0.0) / Real::PI;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::normal3::Normal3;
+    use crate::point3::Point3;
+    use crate::vector2::Vector2;
+
+    #[test]
+    fn scatter_and_eval() {
+        let ray = Ray::new(
+            Point3::new((0.0, 1.0, 0.0)),
+            Direction3::new((0.0, -1.0, 0.0)),
+        );
+        let mut rec = HitRecord::new(&ray, 1.0, Normal3::new((0.0, 1.0, 0.0)), Vector2::zero());
+        rec.m_color = Some(Vector3::new((0.5, 1.0, 1.0)));
+        let mut material = Lambertian::new(Vector3::new((0.8, 0.6, 0.4)));
+        assert_eq!(material.albedo(&rec), Vector3::new((0.8, 0.6, 0.4)));
+        material.m_vertex_colors = true;
+        assert_eq!(material.albedo(&rec), Vector3::new((0.4, 0.6, 0.4)));
+
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..100 {
+            let scatter = material.scatter(&ray, &rec, &mut rng).unwrap();
+            assert!(scatter.m_ray.m_direction.get_y() >= 0.0 && scatter.m_lobe == Lobe::Diffuse);
+            assert_eq!(scatter.m_attenuation, material.albedo(&rec));
+        }
+        let up = Direction3::new((0.0, 1.0, 0.0));
+        assert_eq!(
+            material.eval(&ray, &rec, &up),
+            material.albedo(&rec) / f32::PI
+        );
+        assert_eq!(material.eval(&ray, &rec, &-up), Vector3::zero());
+        assert_eq!(material.pdf(&ray, &rec, &up), 1.0 / Real::PI);
+        assert_eq!(material.pdf(&ray, &rec, &-up), 0.0);
+    }
+}
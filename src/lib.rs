@@ -0,0 +1,64 @@
+#![allow(clippy::needless_return)]
+
+/* math */
+pub mod bounds2i;
+pub mod direction3;
+pub mod matrix3;
+pub mod matrix4;
+pub mod normal3;
+pub mod point2i;
+pub mod point3;
+pub mod quaternion;
+pub mod scalar;
+pub mod simd;
+pub mod transform;
+pub mod vector2;
+pub mod vector3;
+pub mod vector4;
+
+/* geometry */
+pub mod aabb;
+pub mod aabox;
+pub mod bvh;
+pub mod disk;
+pub mod hittable;
+pub mod instance;
+pub mod mesh;
+pub mod packet;
+pub mod plane;
+pub mod ray;
+pub mod sphere;
+pub mod triangle;
+
+/* scene description */
+pub mod area_light;
+pub mod dielectric;
+pub mod emissive;
+pub mod error;
+pub mod gltf;
+pub mod lambertian;
+pub mod light;
+pub mod loader;
+pub mod material;
+pub mod metal;
+pub mod microfacet;
+pub mod obj;
+pub mod ply;
+pub mod scene;
+pub mod stl;
+pub mod texture;
+
+/* rendering */
+pub mod camera;
+pub mod film;
+pub mod renderer;
+pub mod sampling;
+pub mod tile;
+
+/* shared by the binaries */
+pub mod cli;
+pub mod logger;
+
+/* helpers of the unit tests */
+#[cfg(test)]
+mod testing;
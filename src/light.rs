@@ -0,0 +1,312 @@
+use std::fmt::Debug;
+
+use rand::rngs::StdRng;
+
+use crate::direction3::Direction3;
+use crate::hittable::{HitRecord, Hittable};
+use crate::point3::Point3;
+use crate::ray::Ray;
+use crate::scalar::{Float, Real};
+use crate::vector3::Vector3;
+
+/* a direction toward a light and the light arriving along it */
+#[derive(Debug)]
+pub struct LightSample {
+    /* unit direction from the lit point toward the light */
+    pub m_direction: Direction3<Real>,
+    /* to the sampled point of the light, infinite for directional lights */
+    pub m_distance: Real,
+    /* radiance arriving along m_direction in W/(sr m^2), or for delta lights the irradiance
+    in W/m^2 on a surface facing the light */
+    pub m_radiance: Vector3,
+    /* solid angle density m_direction was sampled with, 1 for delta lights */
+    pub m_pdf: Real,
+}
+
+/* emitter the renderer samples for direct lighting, see area_light for the emissive surfaces.
+Point and spot lights give their radiant intensity in W/sr, directional lights their
+irradiance in W/m^2 and area lights their radiance in W/(sr m^2) */
+pub trait Light: Send + Sync + Debug {
+    /* direction toward the light from point, None when no light reaches it from there */
+    fn sample(&self, point: &Point3<Real>, rng: &mut StdRng) -> Option<LightSample>;
+
+    /* solid angle density sample gives the direction from origin to the point rec found on the
+    light, zero for delta lights which no ray can hit */
+    fn pdf(&self, origin: &Point3<Real>, rec: &HitRecord) -> Real;
+
+    /* emitted watts weighted by luminance, the area lights are picked proportionally to it */
+    fn power(&self) -> Real;
+
+    /* infinitely small or far lights, only reached by sampling them */
+    fn is_delta(&self) -> bool {
+        return false;
+    }
+}
+
+/* Associative methods implementation block */
+impl LightSample {
+    /* true when nothing of scene lies between origin and the sampled point of the light */
+    pub fn visible<H: Hittable + ?Sized>(&self, scene: &H, origin: &Point3<Real>) -> bool {
+        let t_max = match self.m_distance.is_finite() {
+            /* stops short of the light's own surface */
+            true => self.m_distance * (1.0 - 1e-4),
+            false => Real::MAX,
+        };
+        let shadow_ray = Ray::new(*origin, self.m_direction);
+        return scene.hit(&shadow_ray, 0.001, t_max).is_none();
+    }
+}
+
+/* isotropic point light, falls off with the squared distance */
+#[derive(Debug)]
+pub struct PointLight {
+    pub m_position: Point3<Real>,
+    pub m_color: Vector3,
+    /* W/sr, times m_color */
+    pub m_intensity: f32,
+}
+
+/* Associative functions implementation block */
+impl PointLight {
+    /* Constructor */
+    pub fn new(position: Point3<Real>, color: Vector3, intensity: f32) -> PointLight {
+        return PointLight {
+            m_position: position,
+            m_color: color,
+            m_intensity: intensity,
+        };
+    }
+}
+
+impl Light for PointLight {
+    fn sample(&self, point: &Point3<Real>, _rng: &mut StdRng) -> Option<LightSample> {
+        let to_light = self.m_position - *point;
+        let distance = to_light.magnitude();
+        if distance <= 0.0 {
+            return None;
+        }
+        return Some(LightSample {
+            m_direction: to_light / distance,
+            m_distance: distance,
+            m_radiance: self.m_color * (self.m_intensity / (distance * distance) as f32),
+            m_pdf: 1.0,
+        });
+    }
+
+    fn pdf(&self, _origin: &Point3<Real>, _rec: &HitRecord) -> Real {
+        return 0.0;
+    }
+
+    fn power(&self) -> Real {
+        return 4.0 * Real::PI * (self.m_intensity * self.m_color.luminance()) as Real;
+    }
+
+    fn is_delta(&self) -> bool {
+        return true;
+    }
+}
+
+/* parallel light from infinitely far away, such as the sun */
+#[derive(Debug)]
+pub struct DirectionalLight {
+    /* unit direction the light travels in */
+    pub m_direction: Direction3<Real>,
+    pub m_color: Vector3,
+    /* W/m^2 on a surface facing the light, times m_color */
+    pub m_irradiance: f32,
+}
+
+/* Associative functions implementation block */
+impl DirectionalLight {
+    /* Constructor, direction is normalized */
+    pub fn new(direction: Direction3<Real>, color: Vector3, irradiance: f32) -> DirectionalLight {
+        return DirectionalLight {
+            m_direction: direction.unit(),
+            m_color: color,
+            m_irradiance: irradiance,
+        };
+    }
+}
+
+impl Light for DirectionalLight {
+    fn sample(&self, _point: &Point3<Real>, _rng: &mut StdRng) -> Option<LightSample> {
+        return Some(LightSample {
+            m_direction: -self.m_direction,
+            m_distance: Real::INFINITY,
+            m_radiance: self.m_color * self.m_irradiance,
+            m_pdf: 1.0,
+        });
+    }
+
+    fn pdf(&self, _origin: &Point3<Real>, _rec: &HitRecord) -> Real {
+        return 0.0;
+    }
+
+    /* unbounded without the size of the scene, delta lights are never picked by power anyway */
+    fn power(&self) -> Real {
+        return 0.0;
+    }
+
+    fn is_delta(&self) -> bool {
+        return true;
+    }
+}
+
+/* point light shining inside a cone, fading out between the inner and outer angles */
+#[derive(Debug)]
+pub struct SpotLight {
+    pub m_position: Point3<Real>,
+    /* unit axis of the cone */
+    pub m_direction: Direction3<Real>,
+    pub m_color: Vector3,
+    /* W/sr on the axis, times m_color */
+    pub m_intensity: f32,
+    /* cosines of the angles from the axis where the falloff starts and ends */
+    pub m_cos_inner: Real,
+    pub m_cos_outer: Real,
+}
+
+/* Associative methods implementation block */
+impl SpotLight {
+    /* fraction of the intensity sent at a cosine of cos with the axis, the square of the linear
+    ramp between the cone angles as in glTF */
+    pub fn falloff(&self, cos: Real) -> Real {
+        if cos >= self.m_cos_inner {
+            return 1.0;
+        }
+        let t = ((cos - self.m_cos_outer) / (self.m_cos_inner - self.m_cos_outer)).clamp(0.0, 1.0);
+        return t * t;
+    }
+}
+
+/* Associative functions implementation block */
+impl SpotLight {
+    /* Constructor, the angles are half angles of the cone in degrees, inner clamped to outer */
+    pub fn new(
+        position: Point3<Real>,
+        direction: Direction3<Real>,
+        color: Vector3,
+        intensity: f32,
+        inner_angle: Real,
+        outer_angle: Real,
+    ) -> SpotLight {
+        let outer = outer_angle.clamp(0.0, 180.0);
+        let inner = inner_angle.clamp(0.0, outer);
+        return SpotLight {
+            m_position: position,
+            m_direction: direction.unit(),
+            m_color: color,
+            m_intensity: intensity,
+            m_cos_inner: inner.to_radians().cos(),
+            m_cos_outer: outer.to_radians().cos(),
+        };
+    }
+}
+
+impl Light for SpotLight {
+    fn sample(&self, point: &Point3<Real>, _rng: &mut StdRng) -> Option<LightSample> {
+        let to_light = self.m_position - *point;
+        let distance = to_light.magnitude();
+        if distance <= 0.0 {
+            return None;
+        }
+        let direction = to_light / distance;
+        let falloff = self.falloff(-direction.dot(&self.m_direction));
+        if falloff <= 0.0 {
+            return None;
+        }
+        return Some(LightSample {
+            m_direction: direction,
+            m_distance: distance,
+            m_radiance: self.m_color
+                * (self.m_intensity * (falloff / (distance * distance)) as f32),
+            m_pdf: 1.0,
+        });
+    }
+
+    fn pdf(&self, _origin: &Point3<Real>, _rec: &HitRecord) -> Real {
+        return 0.0;
+    }
+
+    /* the solid angle of the outer cone at full intensity, an upper bound */
+    fn power(&self) -> Real {
+        let solid_angle = 2.0 * Real::PI * (1.0 - self.m_cos_outer);
+        return solid_angle * (self.m_intensity * self.m_color.luminance()) as Real;
+    }
+
+    fn is_delta(&self) -> bool {
+        return true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::sphere::Sphere;
+
+    #[test]
+    fn punctual_lights() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let white = Vector3::new((1.0, 1.0, 1.0));
+        let point = PointLight::new(Point3::new((0.0, 2.0, 0.0)), white, 8.0);
+        let sample = point.sample(&Point3::origin(), &mut rng).unwrap();
+        assert_eq!(sample.m_direction, Direction3::new((0.0, 1.0, 0.0)));
+        assert_eq!((sample.m_distance, sample.m_pdf), (2.0, 1.0));
+        /* inverse square law */
+        assert_eq!(sample.m_radiance, white * 2.0);
+        assert!(point.is_delta());
+
+        let sun = DirectionalLight::new(Direction3::new((0.0, -2.0, 0.0)), white, 3.0);
+        let sample = sun
+            .sample(&Point3::new((5.0, -7.0, 1.0)), &mut rng)
+            .unwrap();
+        assert_eq!(sample.m_direction, Direction3::new((0.0, 1.0, 0.0)));
+        assert_eq!(sample.m_radiance, white * 3.0);
+        assert!(sample.m_distance.is_infinite());
+
+        /* a spot pointing down with full intensity up to 30 degrees, none past 60 */
+        let spot = SpotLight::new(
+            Point3::new((0.0, 1.0, 0.0)),
+            Direction3::new((0.0, -1.0, 0.0)),
+            white,
+            4.0,
+            30.0,
+            60.0,
+        );
+        let mut at = |x: Real| spot.sample(&Point3::new((x, 0.0, 0.0)), &mut rng);
+        assert_eq!(at(0.0).unwrap().m_radiance, white * 4.0);
+        assert!(at(2.0).is_none());
+        /* 45 degrees off the axis, along the ramp between the cosines of 30 and 60 degrees */
+        let t = (Real::sqrt(0.5) - 0.5) / (Real::sqrt(0.75) - 0.5);
+        let radiance = at(1.0).unwrap().m_radiance.get_r() as Real;
+        assert!((radiance - 4.0 * t * t / 2.0).abs() < 1e-5);
+        assert_eq!(spot.falloff(1.0), 1.0);
+        assert_eq!(spot.falloff(0.0), 0.0);
+    }
+
+    #[test]
+    fn visibility() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let white = Vector3::new((1.0, 1.0, 1.0));
+        let blocker = Sphere::new(Point3::new((0.0, 2.0, 0.0)), 0.5);
+        let above = PointLight::new(Point3::new((0.0, 4.0, 0.0)), white, 1.0);
+        let below = PointLight::new(Point3::new((0.0, 1.0, 0.0)), white, 1.0);
+        let sun = DirectionalLight::new(Direction3::new((0.0, -1.0, 0.0)), white, 1.0);
+        let origin = Point3::origin();
+        let mut visible = |light: &dyn Light| {
+            let sample = light.sample(&origin, &mut rng).unwrap();
+            return sample.visible(&blocker, &origin);
+        };
+        assert!(!visible(&above));
+        assert!(visible(&below));
+        assert!(!visible(&sun));
+        let side = Point3::new((3.0, 0.0, 0.0));
+        assert!(
+            sun.sample(&side, &mut rng)
+                .unwrap()
+                .visible(&blocker, &side)
+        );
+    }
+}
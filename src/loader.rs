@@ -0,0 +1,114 @@
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::error::Error;
+
+pub fn read_file(path: &str) -> Result<String, Error> {
+    return fs::read_to_string(path).map_err(|e| Error::io(path, e));
+}
+
+pub fn read_bytes(path: &str) -> Result<Vec<u8>, Error> {
+    return fs::read(path).map_err(|e| Error::io(path, e));
+}
+
+/* path as seen from the directory of the file base, absolute paths are kept */
+pub fn relative_to(base: &str, path: &str) -> String {
+    let directory = Path::new(base).parent().unwrap_or(Path::new(""));
+    return String::from(directory.join(path).to_string_lossy());
+}
+
+/* reads the {"values": [...]} array of path, which must hold exactly expected numbers */
+pub fn read_values(path: &str, expected: usize) -> Result<Vec<f32>, Error> {
+    let json: Value = serde_json::from_str(&read_file(path)?).map_err(|e| Error::json(path, e))?;
+
+    let vals = json
+        .get("values")
+        .ok_or_else(|| Error::Missing {
+            m_path: String::from(path),
+            m_pointer: String::from("/values"),
+        })?
+        .as_array()
+        .ok_or_else(|| Error::NotArray {
+            m_path: String::from(path),
+            m_pointer: String::from("/values"),
+        })?;
+
+    if vals.len() != expected {
+        return Err(Error::Length {
+            m_path: String::from(path),
+            m_pointer: String::from("/values"),
+            m_expected: expected,
+            m_found: vals.len(),
+        });
+    }
+
+    let mut arr = Vec::with_capacity(expected);
+    for (i, val) in vals.iter().enumerate() {
+        let val = val.as_f64().ok_or_else(|| Error::NotNumeric {
+            m_path: String::from(path),
+            m_pointer: format!("/values/{}", i),
+        })?;
+        arr.push(val as f32);
+    }
+    return Ok(arr);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matrix4::Matrix4;
+    use crate::testing::temp_file;
+    use crate::vector4::Vector4;
+
+    #[test]
+    fn values() {
+        assert!(Vector4::<f32>::try_from("res/scene/vector.json").is_ok());
+        assert!(Matrix4::<f32>::try_from("res/scene/matrix.json").is_ok());
+
+        let path = temp_file("rt_rs_missing.json", r#"{"value": [1, 2, 3, 4]}"#);
+        match Vector4::<f32>::json(&path) {
+            Err(Error::Missing { m_pointer, .. }) => assert_eq!(m_pointer, "/values"),
+            other => panic!("unexpected {:?}", other),
+        }
+
+        let path = temp_file("rt_rs_length.json", r#"{"values": [1, 2, 3]}"#);
+        match Vector4::<f32>::json(&path) {
+            Err(Error::Length {
+                m_expected,
+                m_found,
+                ..
+            }) => assert_eq!((m_expected, m_found), (4, 3)),
+            other => panic!("unexpected {:?}", other),
+        }
+
+        let path = temp_file("rt_rs_array.json", r#"{"values": 4}"#);
+        match Vector4::<f32>::json(&path) {
+            Err(Error::NotArray { m_pointer, .. }) => assert_eq!(m_pointer, "/values"),
+            other => panic!("unexpected {:?}", other),
+        }
+
+        let path = temp_file("rt_rs_numeric.json", r#"{"values": [1, 2, "3", 4]}"#);
+        match Vector4::<f32>::try_from(path.as_str()) {
+            Err(Error::NotNumeric { m_pointer, .. }) => assert_eq!(m_pointer, "/values/2"),
+            other => panic!("unexpected {:?}", other),
+        }
+
+        /* a vector file is too short for a matrix */
+        assert!(matches!(
+            Matrix4::<f32>::try_from("res/scene/vector.json"),
+            Err(Error::Length { .. })
+        ));
+    }
+
+    #[test]
+    fn relative_paths() {
+        assert_eq!(
+            relative_to("res/scene/mesh.json", "../mesh/cube.obj"),
+            "res/scene/../mesh/cube.obj"
+        );
+        assert_eq!(relative_to("cube.obj", "cube.mtl"), "cube.mtl");
+        assert_eq!(relative_to("res/scene/a.json", "/tmp/b.obj"), "/tmp/b.obj");
+    }
+}
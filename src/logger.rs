@@ -0,0 +1,36 @@
+use log::{LevelFilter, Log, Metadata, Record};
+
+/* prints every enabled record to stderr */
+struct StderrLogger;
+
+static LOGGER: StderrLogger = StderrLogger;
+
+impl Log for StderrLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        return metadata.level() <= log::max_level();
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!(
+                "[{}] {}: {}",
+                record.level(),
+                record.target(),
+                record.args()
+            );
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/* installs the stderr logger, the level can be changed again later with set_level */
+pub fn init(level: LevelFilter) {
+    /* another logger may already be installed by the caller, in which case only the level changes */
+    let _ = log::set_logger(&LOGGER);
+    set_level(level);
+}
+
+pub fn set_level(level: LevelFilter) {
+    log::set_max_level(level);
+}
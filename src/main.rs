@@ -1,31 +1,39 @@
-use std::time::{Duration, Instant};
+#![allow(clippy::needless_return)]
+
+use std::time::Instant;
 
 /*idea*/
 
-use image::ColorType::Rgba8;
-use sdl2::event::Event;
-use sdl2::keyboard::Keycode;
-use sdl2::pixels::PixelFormatEnum;
-use sdl2::rect::Rect;
+use rt_rs::cli::{Options, USAGE};
+use rt_rs::logger;
+use rt_rs::renderer::Renderer;
+use rt_rs::scene::Scene;
 
 use crate::test::*;
 
-mod matrix3;
-mod matrix4;
-mod renderer;
 mod test;
-mod vector3;
-mod vector4;
-
-/* toggles constructor messages */
-const DEBUG: bool = false;
 
 fn main() {
-    trace_rays();
+    let options = match Options::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            std::process::exit(2);
+        }
+    };
+    if options.m_help {
+        println!("{}", USAGE);
+        return;
+    }
+    logger::init(options.m_log_level);
+    if options.m_self_test {
+        self_test();
+    }
+
+    trace_rays(&options);
 }
 
-fn trace_rays() {
-    println!("DEBUG: {}", DEBUG);
+fn self_test() {
     test_vector();
     println!();
     test_matrix();
@@ -34,91 +42,37 @@ fn trace_rays() {
     println!();
     test_json_matrix();
     println!();
-    sdl2();
 }
 
-pub fn sdl2() {
-    // Initialize SDL2
-    let sdl_context = sdl2::init().unwrap();
-    let video_subsystem = sdl_context.video().unwrap();
-
-    let width = 512;
-    let height = 512;
-
-    // Create a window
-    let window = video_subsystem
-        .window("SDL2", width, height)
-        .position_centered()
-        .build()
-        .unwrap();
-
-    // Create a canvas for rendering
-    let mut canvas = window.into_canvas().build().unwrap();
-
-    // Create an image buffer (RGBA8 format)
-    let mut s_renderer = renderer::Renderer::new(width, height);
-    //let mut image_buffer: RgbaImage = s.render();
-    let mut raw_pixels: Vec<u8>;
-
-    // Main loop
-    let mut event_pump = sdl_context.event_pump().unwrap();
-    let mut last_frame_time = Instant::now();
-
-    //image_buffer = s.render();
-    raw_pixels = s_renderer.render().into_raw();
-
-    'running: loop {
-        let frame_start_time = Instant::now();
-        for event in event_pump.poll_iter() {
-            match event {
-                Event::Quit { .. }
-                | Event::KeyDown {
-                    keycode: Some(Keycode::Escape),
-                    ..
-                } => {
-                    /* save last image buffer to output.png before exiting */
-                    image::save_buffer("output.png", &*raw_pixels, width, height, Rgba8)
-                        .expect("COULDN'T SAVE IMAGE BUFFER BEFORE EXITING!");
-                    break 'running;
-                }
-                _ => { /* do nothing */ }
-            }
+/* headless render straight to the output file */
+fn trace_rays(options: &Options) {
+    let mut scene = match Scene::load(&options.m_scene) {
+        Ok(scene) => scene,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
         }
-
-        //image_buffer = s.render();
-        raw_pixels = s_renderer.render().into_raw();
-        let surface = sdl2::surface::Surface::from_data(
-            &mut raw_pixels,
-            width,
-            height,
-            width * PixelFormatEnum::RGBA32.byte_size_per_pixel() as u32,
-            PixelFormatEnum::RGBA32,
-        )
-            .unwrap();
-        let texture_creator = canvas.texture_creator();
-        let texture = texture_creator
-            .create_texture_from_surface(surface)
-            .unwrap();
-
-        // Clear the canvas
-        canvas.set_draw_color(sdl2::pixels::Color::RGB(0, 0, 0));
-        canvas.clear();
-
-        // Draw the texture to the canvas
-        canvas
-            .copy(&texture, None, Some(Rect::new(0, 0, width, height)))
-            .unwrap();
-
-        // Calculate frame time and FPS
-        let frame_time = frame_start_time.duration_since(last_frame_time);
-        last_frame_time = frame_start_time;
-        let fps = 1.0 / frame_time.as_secs_f64();
-        println!("FPS: {:?}, Frametime: {:?}", fps, frame_time);
-
-        // Update the window
-        canvas.present();
-
-        // Add a slight delay to avoid consuming too much CPU
-        std::thread::sleep(Duration::new(0, 1_000_000_000u32 / 240));
+    };
+    options.apply(&mut scene);
+
+    let mut s_renderer = Renderer::from_scene(&scene);
+    s_renderer.m_threads = options.m_threads;
+    s_renderer.m_tile_size = options.m_tile_size;
+    s_renderer.m_seed = options.m_seed;
+
+    let start = Instant::now();
+    let image = s_renderer.render();
+    println!(
+        "rendered {}x{} at {} spp in {:?}, last pass: {}",
+        s_renderer.m_film.width(),
+        s_renderer.m_film.height(),
+        s_renderer.m_film.m_sample_count,
+        start.elapsed(),
+        s_renderer.m_stats.summary()
+    );
+
+    if let Err(e) = image.save(&options.m_output) {
+        eprintln!("{}: {}", options.m_output, e);
+        std::process::exit(1);
     }
 }
@@ -0,0 +1,71 @@
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use rand::rngs::StdRng;
+
+use crate::direction3::Direction3;
+use crate::hittable::HitRecord;
+use crate::ray::Ray;
+use crate::scalar::Real;
+use crate::texture::Texture;
+use crate::vector3::Vector3;
+
+/* kind of reflection a scattered ray was sampled from */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lobe {
+    /* spread over the whole hemisphere */
+    Diffuse,
+    /* concentrated around the mirror direction */
+    Glossy,
+    /* a single mirror or refraction direction, which the lights cannot be sampled for */
+    Specular,
+}
+
+/* the ray a material continues an incoming one with */
+#[derive(Debug)]
+pub struct Scatter {
+    pub m_ray: Ray,
+    /* fraction of the light arriving along m_ray that leaves back along the incoming ray, the
+    brdf times the cosine over the density m_ray was sampled with */
+    pub m_attenuation: Vector3,
+    pub m_lobe: Lobe,
+}
+
+/* how a surface reflects, transmits and emits light, see lambertian, metal, dielectric,
+microfacet and emissive */
+pub trait Material: Send + Sync + Debug {
+    /* samples the direction light arrives from to leave back along ray, None when the surface
+    absorbs it */
+    fn scatter(&self, ray: &Ray, rec: &HitRecord, rng: &mut StdRng) -> Option<Scatter>;
+
+    /* brdf of light arriving from the unit direction and leaving back along ray, zero for
+    specular materials */
+    fn eval(&self, ray: &Ray, rec: &HitRecord, direction: &Direction3<Real>) -> Vector3;
+
+    /* solid angle density scatter samples the unit direction with, zero for specular
+    materials */
+    fn pdf(&self, ray: &Ray, rec: &HitRecord, direction: &Direction3<Real>) -> Real;
+
+    /* radiance the surface emits back along ray, in W/(sr m^2) */
+    fn emitted(&self, _ray: &Ray, _rec: &HitRecord) -> Vector3 {
+        return Vector3::zero();
+    }
+}
+
+/* color times the vertex color of meshes that have them when asked to, times the texture at
+the hit's uv */
+pub fn albedo(
+    color: Vector3,
+    vertex_colors: bool,
+    texture: &Option<Arc<Texture>>,
+    rec: &HitRecord,
+) -> Vector3 {
+    let color = match rec.m_color {
+        Some(vertex) if vertex_colors => color * vertex,
+        _ => color,
+    };
+    return match texture {
+        Some(texture) => color * texture.sample(&rec.m_uv),
+        None => color,
+    };
+}
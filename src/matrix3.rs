@@ -1,54 +1,110 @@
-#![allow(dead_code)]
+use std::ops::{
+    Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign,
+};
 
-use crate::DEBUG;
+use crate::normal3::Normal3;
+use crate::quaternion::Quaternion;
+use crate::scalar::Float;
 use crate::vector3::Vector3;
 
-#[derive(Debug)]
-pub struct Matrix3 {
-    pub m_data: [[f32; 3]; 3],
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Matrix3<T: Float = f32> {
+    pub m_data: [[T; 3]; 3],
 }
 
-pub trait Instantiator {
-    fn into(self) -> Matrix3;
+pub type Matrix3d = Matrix3<f64>;
+
+pub trait Instantiator<T: Float> {
+    fn into(self) -> Matrix3<T>;
 }
 
 /* Associative methods implementation block */
-impl Matrix3 {
-    pub fn det(&self) -> f32 {
-        return Matrix3::s_det3(&self);
+impl<T: Float> Matrix3<T> {
+    pub fn det(&self) -> T {
+        return Matrix3::s_det3(self);
+    }
+
+    pub fn transpose(&self) -> Matrix3<T> {
+        let mut m = Matrix3::zero();
+        for i in 0..3 {
+            for j in 0..3 {
+                m.m_data[i][j] = self.m_data[j][i];
+            }
+        }
+        return m;
+    }
+
+    /* adjugate over determinant, None when the matrix is singular */
+    pub fn inverse(&self) -> Option<Matrix3<T>> {
+        let mut cofactors = Matrix3::zero();
+        for i in 0..3 {
+            for j in 0..3 {
+                cofactors.m_data[i][j] = Matrix3::s_cofactor3(self, i, j);
+            }
+        }
+
+        let mut det = T::ZERO;
+        for j in 0..3 {
+            det += self.m_data[0][j] * cofactors.m_data[0][j];
+        }
+        if det == T::ZERO || !det.is_finite() {
+            return None;
+        }
+
+        return Some(cofactors.transpose() / det);
+    }
+
+    /* inverse transpose, maps normals so they stay perpendicular to transformed surfaces */
+    pub fn normal_matrix(&self) -> Option<Matrix3<T>> {
+        return self.inverse().map(|m| m.transpose());
+    }
+
+    /* same matrix in another precision */
+    pub fn cast<U: Float>(&self) -> Matrix3<U> {
+        return Matrix3 {
+            m_data: self.m_data.map(|row| row.map(|v| U::from_f64(v.to_f64()))),
+        };
+    }
+
+    /* true when every entry is within epsilon of m's */
+    pub fn approx_eq(&self, m: &Matrix3<T>, epsilon: T) -> bool {
+        return self
+            .m_data
+            .iter()
+            .flatten()
+            .zip(m.m_data.iter().flatten())
+            .all(|(a, b)| (*a - *b).abs() <= epsilon);
     }
 }
 
 /* Associative functions implementation block */
-impl Matrix3 {
+impl<T: Float> Matrix3<T> {
     /* Constructor */
-    pub fn new<A>(args: A) -> Matrix3
+    pub fn new<A>(args: A) -> Matrix3<T>
     where
-        A: Instantiator,
+        A: Instantiator<T>,
     {
         return args.into();
     }
 
     /* Static Constructors */
-    pub fn identity() -> Matrix3 {
-        if DEBUG {
-            println!("Matrix3::Instantiator: Invoked Identity overload!");
-        }
+    pub fn identity() -> Matrix3<T> {
         return Matrix3 {
-            m_data: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+            m_data: [
+                [T::ONE, T::ZERO, T::ZERO],
+                [T::ZERO, T::ONE, T::ZERO],
+                [T::ZERO, T::ZERO, T::ONE],
+            ],
         };
     }
 
-    pub fn zero() -> Matrix3 {
-        if DEBUG {
-            println!("Matrix3::Instantiator: Invoked Zero overload!");
-        }
+    pub fn zero() -> Matrix3<T> {
         return Matrix3 {
-            m_data: [[0.0, 0.0, 0.0], [0.0, 0.0, 0.0], [0.0, 0.0, 0.0]],
+            m_data: [[T::ZERO; 3]; 3],
         };
     }
 
-    fn s_det3(matrix: &Matrix3) -> f32 {
+    fn s_det3(matrix: &Matrix3<T>) -> T {
         return matrix.m_data[0][0] * matrix.m_data[1][1] * matrix.m_data[2][2]
             + matrix.m_data[0][1] * matrix.m_data[1][2] * matrix.m_data[2][0]
             + matrix.m_data[0][2] * matrix.m_data[2][1] * matrix.m_data[1][0]
@@ -56,46 +112,218 @@ impl Matrix3 {
             - matrix.m_data[1][0] * matrix.m_data[0][1] * matrix.m_data[2][2]
             - matrix.m_data[0][0] * matrix.m_data[1][2] * matrix.m_data[2][1];
     }
+
+    /* signed determinant of the 2x2 minor left after removing row and column */
+    fn s_cofactor3(matrix: &Matrix3<T>, row: usize, column: usize) -> T {
+        let r0 = if row == 0 { 1 } else { 0 };
+        let r1 = if row == 2 { 1 } else { 2 };
+        let c0 = if column == 0 { 1 } else { 0 };
+        let c1 = if column == 2 { 1 } else { 2 };
+        let minor = matrix.m_data[r0][c0] * matrix.m_data[r1][c1]
+            - matrix.m_data[r0][c1] * matrix.m_data[r1][c0];
+        return if (row + column).is_multiple_of(2) {
+            minor
+        } else {
+            -minor
+        };
+    }
 }
 
-impl Instantiator for () {
-    fn into(self) -> Matrix3 {
-        if DEBUG {
-            println!("Matrix3::Instantiator: Invoked () Identity overload!");
+/* Operator overloads */
+impl<T: Float> Add for Matrix3<T> {
+    type Output = Matrix3<T>;
+
+    fn add(self, m: Matrix3<T>) -> Matrix3<T> {
+        let mut r = self;
+        for i in 0..3 {
+            for j in 0..3 {
+                r.m_data[i][j] += m.m_data[i][j];
+            }
         }
+        return r;
+    }
+}
+
+impl<T: Float> Sub for Matrix3<T> {
+    type Output = Matrix3<T>;
+
+    fn sub(self, m: Matrix3<T>) -> Matrix3<T> {
+        let mut r = self;
+        for i in 0..3 {
+            for j in 0..3 {
+                r.m_data[i][j] -= m.m_data[i][j];
+            }
+        }
+        return r;
+    }
+}
+
+impl<T: Float> Mul<T> for Matrix3<T> {
+    type Output = Matrix3<T>;
+
+    fn mul(self, s: T) -> Matrix3<T> {
+        let mut r = self;
+        for row in r.m_data.iter_mut() {
+            for val in row.iter_mut() {
+                *val *= s;
+            }
+        }
+        return r;
+    }
+}
+
+impl<T: Float> Div<T> for Matrix3<T> {
+    type Output = Matrix3<T>;
+
+    fn div(self, s: T) -> Matrix3<T> {
+        let mut r = self;
+        for row in r.m_data.iter_mut() {
+            for val in row.iter_mut() {
+                *val /= s;
+            }
+        }
+        return r;
+    }
+}
+
+impl Mul<Matrix3<f32>> for f32 {
+    type Output = Matrix3<f32>;
+
+    fn mul(self, m: Matrix3<f32>) -> Matrix3<f32> {
+        return m * self;
+    }
+}
+
+impl Mul<Matrix3<f64>> for f64 {
+    type Output = Matrix3<f64>;
+
+    fn mul(self, m: Matrix3<f64>) -> Matrix3<f64> {
+        return m * self;
+    }
+}
+
+impl<T: Float> Neg for Matrix3<T> {
+    type Output = Matrix3<T>;
+
+    fn neg(self) -> Matrix3<T> {
+        return self * -T::ONE;
+    }
+}
+
+impl<T: Float> Mul for Matrix3<T> {
+    type Output = Matrix3<T>;
+
+    fn mul(self, m: Matrix3<T>) -> Matrix3<T> {
+        let mut r = Matrix3::zero();
+        for i in 0..3 {
+            for k in 0..3 {
+                for j in 0..3 {
+                    r.m_data[i][j] += self.m_data[i][k] * m.m_data[k][j];
+                }
+            }
+        }
+        return r;
+    }
+}
+
+impl<T: Float> Mul<Vector3<T>> for Matrix3<T> {
+    type Output = Vector3<T>;
+
+    fn mul(self, v: Vector3<T>) -> Vector3<T> {
+        let mut r = Vector3::zero();
+        for i in 0..3 {
+            for j in 0..3 {
+                r.m_data[i] += self.m_data[i][j] * v.m_data[j];
+            }
+        }
+        return r;
+    }
+}
+
+/* meant for normal matrices, see normal_matrix */
+impl<T: Float> Mul<Normal3<T>> for Matrix3<T> {
+    type Output = Normal3<T>;
+
+    fn mul(self, n: Normal3<T>) -> Normal3<T> {
+        return Normal3::new(&(self * n.vector()));
+    }
+}
+
+impl<T: Float> AddAssign for Matrix3<T> {
+    fn add_assign(&mut self, m: Matrix3<T>) {
+        *self = *self + m;
+    }
+}
+
+impl<T: Float> SubAssign for Matrix3<T> {
+    fn sub_assign(&mut self, m: Matrix3<T>) {
+        *self = *self - m;
+    }
+}
+
+impl<T: Float> MulAssign for Matrix3<T> {
+    fn mul_assign(&mut self, m: Matrix3<T>) {
+        *self = *self * m;
+    }
+}
+
+impl<T: Float> MulAssign<T> for Matrix3<T> {
+    fn mul_assign(&mut self, s: T) {
+        *self = *self * s;
+    }
+}
+
+impl<T: Float> DivAssign<T> for Matrix3<T> {
+    fn div_assign(&mut self, s: T) {
+        *self = *self / s;
+    }
+}
+
+/* m[(row, column)] */
+impl<T: Float> Index<(usize, usize)> for Matrix3<T> {
+    type Output = T;
+
+    fn index(&self, (row, column): (usize, usize)) -> &T {
+        return &self.m_data[row][column];
+    }
+}
+
+impl<T: Float> IndexMut<(usize, usize)> for Matrix3<T> {
+    fn index_mut(&mut self, (row, column): (usize, usize)) -> &mut T {
+        return &mut self.m_data[row][column];
+    }
+}
+
+impl<T: Float> Instantiator<T> for () {
+    fn into(self) -> Matrix3<T> {
         return Matrix3 {
-            m_data: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+            m_data: [
+                [T::ONE, T::ZERO, T::ZERO],
+                [T::ZERO, T::ONE, T::ZERO],
+                [T::ZERO, T::ZERO, T::ONE],
+            ],
         };
     }
 }
 
-impl Instantiator for i64 {
-    fn into(self) -> Matrix3 {
-        if DEBUG {
-            println!("Matrix3::Instantiator: Invoked (i64) Zero overload!");
-        }
+impl<T: Float> Instantiator<T> for i64 {
+    fn into(self) -> Matrix3<T> {
         return Matrix3 {
-            m_data: [[0.0, 0.0, 0.0], [0.0, 0.0, 0.0], [0.0, 0.0, 0.0]],
+            m_data: [[T::ZERO; 3]; 3],
         };
     }
 }
 
-impl Instantiator for &Matrix3 {
-    fn into(self) -> Matrix3 {
-        if DEBUG {
-            println!("Matrix3::Instantiator: Invoked (&Matrix3) overload!");
-        }
+impl<T: Float> Instantiator<T> for &Matrix3<T> {
+    fn into(self) -> Matrix3<T> {
         return Matrix3 {
             m_data: self.m_data,
         };
     }
 }
 
-impl Instantiator for (f32, f32, f32, f32, f32, f32, f32, f32, f32) {
-    fn into(self) -> Matrix3 {
-        if DEBUG {
-            println!("Matrix3::Instantiator: Invoked (f32, f32, f32, f32, f32, f32, f32, f32, f32) overload!");
-        }
+impl<T: Float> Instantiator<T> for (T, T, T, T, T, T, T, T, T) {
+    fn into(self) -> Matrix3<T> {
         return Matrix3 {
             m_data: [
                 [self.0, self.1, self.2],
@@ -106,11 +334,8 @@ impl Instantiator for (f32, f32, f32, f32, f32, f32, f32, f32, f32) {
     }
 }
 
-impl Instantiator for (&Vector3, &Vector3, &Vector3) {
-    fn into(self) -> Matrix3 {
-        if DEBUG {
-            println!("Matrix3::Instantiator: Invoked (&Vector3, &Vector3, &Vector3) overload!");
-        }
+impl<T: Float> Instantiator<T> for (&Vector3<T>, &Vector3<T>, &Vector3<T>) {
+    fn into(self) -> Matrix3<T> {
         return Matrix3 {
             m_data: [
                 [self.0.m_data[0], self.1.m_data[0], self.2.m_data[0]],
@@ -120,3 +345,46 @@ impl Instantiator for (&Vector3, &Vector3, &Vector3) {
         };
     }
 }
+
+impl<T: Float> Instantiator<T> for &Quaternion<T> {
+    fn into(self) -> Matrix3<T> {
+        return self.to_matrix3();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operators() {
+        let m = Matrix3::new((1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 10.0));
+        assert_eq!(Matrix3::identity() * m, m);
+        assert_eq!(
+            m * Vector3::new((1.0, 0.0, 0.0)),
+            Vector3::new((1.0, 4.0, 7.0))
+        );
+        assert_eq!(m - m, Matrix3::zero());
+        assert_eq!(m[(2, 2)], 10.0);
+        let mut n = m;
+        n += m;
+        assert!(n.approx_eq(&(m * 2.0), 0.0));
+    }
+
+    #[test]
+    fn inverse() {
+        let m = Matrix3::new((2.0, 0.0, 1.0, 1.0, 3.0, 0.0, 0.0, 1.0, 4.0));
+        let inverse = m.inverse().unwrap();
+        assert!((m * inverse).approx_eq(&Matrix3::identity(), 1e-6));
+        assert!((inverse * m).approx_eq(&Matrix3::identity(), 1e-6));
+        assert!(m.transpose().transpose() == m);
+        assert!(
+            m.normal_matrix()
+                .unwrap()
+                .approx_eq(&inverse.transpose(), 0.0)
+        );
+
+        let singular = Matrix3::new((1.0, 2.0, 3.0, 2.0, 4.0, 6.0, 0.0, 1.0, 1.0));
+        assert!(singular.inverse().is_none());
+    }
+}
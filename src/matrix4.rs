@@ -1,29 +1,35 @@
-#![allow(dead_code)]
-
-use std::f32::consts::PI;
-use std::fs::File;
-use std::io::Read;
+use std::ops::{
+    Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign,
+};
 
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
 
-use crate::DEBUG;
+use crate::direction3::Direction3;
+use crate::error::Error;
+use crate::loader;
 use crate::matrix3::Matrix3;
+use crate::normal3::Normal3;
+use crate::point3::Point3;
+use crate::quaternion::Quaternion;
+use crate::scalar::Float;
+use crate::transform::Transform;
 use crate::vector3::Vector3;
 use crate::vector4::Vector4;
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct Matrix4 {
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Matrix4<T: Float = f32> {
     #[serde(rename = "values")]
-    m_data: [[f32; 4]; 4],
+    m_data: [[T; 4]; 4],
 }
 
-pub trait Instantiator {
-    fn into(self) -> Matrix4;
+pub type Matrix4d = Matrix4<f64>;
+
+pub trait Instantiator<T: Float> {
+    fn into(self) -> Matrix4<T>;
 }
 
-pub trait Rotator {
-    fn into(self) -> Matrix4;
+pub trait Rotator<T: Float> {
+    fn into(self) -> Matrix4<T>;
 }
 
 pub trait Multiplier<T> {
@@ -31,122 +37,151 @@ pub trait Multiplier<T> {
 }
 
 /* Associative methods implementation block */
-impl Matrix4 {
-    pub fn det(&self) -> f32 {
-        return Matrix4::s_det4(&self);
+impl<T: Float> Matrix4<T> {
+    pub fn det(&self) -> T {
+        return Matrix4::s_det4(self);
     }
-}
 
-impl Multiplier<Vector4> for Matrix4 {
-    fn mult(&self, obj: &Vector4) -> Vector4 {
-        if DEBUG {
-            println!("Matrix4::Multiplier: Invoked (&Vector4) overload!");
-        }
-        return Vector4::new((
-            self.m_data[0][0] * obj.m_data[0]
-                + self.m_data[0][1] * obj.m_data[1]
-                + self.m_data[0][2] * obj.m_data[2]
-                + self.m_data[0][3] * obj.m_data[3],
-            self.m_data[1][0] * obj.m_data[0]
-                + self.m_data[1][1] * obj.m_data[1]
-                + self.m_data[1][2] * obj.m_data[2]
-                + self.m_data[1][3] * obj.m_data[3],
-            self.m_data[2][0] * obj.m_data[0]
-                + self.m_data[2][1] * obj.m_data[1]
-                + self.m_data[2][2] * obj.m_data[2]
-                + self.m_data[2][3] * obj.m_data[3],
-            self.m_data[3][0] * obj.m_data[0]
-                + self.m_data[3][1] * obj.m_data[1]
-                + self.m_data[3][2] * obj.m_data[2]
-                + self.m_data[3][3] * obj.m_data[3],
-        ));
-    }
-}
-
-impl Multiplier<Matrix4> for Matrix4 {
-    fn mult(&self, obj: &Matrix4) -> Matrix4 {
-        if DEBUG {
-            println!("Matrix4::Multiplier: Invoked (&Matrix4) overload!");
+    pub fn transpose(&self) -> Matrix4<T> {
+        let mut m = Matrix4::zero();
+        for i in 0..4 {
+            for j in 0..4 {
+                m.m_data[i][j] = self.m_data[j][i];
+            }
         }
-        let mut m = Self::new(0);
+        return m;
+    }
 
+    /* adjugate over determinant, None when the matrix is singular */
+    pub fn inverse(&self) -> Option<Matrix4<T>> {
+        let mut cofactors = Matrix4::zero();
         for i in 0..4 {
-            for k in 0..4 {
-                for j in 0..4 {
-                    m.m_data[i][j] += self.m_data[i][k] * obj.m_data[k][j];
-                }
+            for j in 0..4 {
+                cofactors.m_data[i][j] = Matrix4::s_cofactor4(self, i, j);
             }
         }
 
+        /* same expansion as s_det4, reusing the first row of cofactors */
+        let mut det = T::ZERO;
+        for j in 0..4 {
+            det += self.m_data[0][j] * cofactors.m_data[0][j];
+        }
+        if det == T::ZERO || !det.is_finite() {
+            return None;
+        }
+
+        return Some(cofactors.transpose() / det);
+    }
+
+    /* upper left 3x3 block, the transform without its translation */
+    pub fn linear(&self) -> Matrix3<T> {
+        let mut m = Matrix3::zero();
+        for i in 0..3 {
+            for j in 0..3 {
+                m.m_data[i][j] = self.m_data[i][j];
+            }
+        }
         return m;
     }
+
+    /* inverse transpose of the linear part, maps normals so they stay perpendicular to transformed surfaces */
+    pub fn normal_matrix(&self) -> Option<Matrix3<T>> {
+        return self.linear().normal_matrix();
+    }
+
+    /* translation, rotation, scale and shear, None for singular and projective matrices */
+    pub fn decompose(&self) -> Option<Transform<T>> {
+        return Transform::decompose(self);
+    }
+
+    /* applies the whole transform (w = 1), dividing by the resulting w for projections */
+    pub fn transform_point(&self, p: &Point3<T>) -> Point3<T> {
+        return Point3::new(&(*self * p.homogeneous()));
+    }
+
+    /* ignores the translation (w = 0) */
+    pub fn transform_direction(&self, d: &Direction3<T>) -> Direction3<T> {
+        return Direction3::new(&Vector3::new(&(*self * d.homogeneous())));
+    }
+
+    /* by the inverse transpose, None when the matrix is singular. Normals transformed many times
+    should go through a normal_matrix computed once instead */
+    pub fn transform_normal(&self, n: &Normal3<T>) -> Option<Normal3<T>> {
+        return Some(self.normal_matrix()? * *n);
+    }
+
+    /* same matrix in another precision */
+    pub fn cast<U: Float>(&self) -> Matrix4<U> {
+        return Matrix4 {
+            m_data: self.m_data.map(|row| row.map(|v| U::from_f64(v.to_f64()))),
+        };
+    }
+
+    /* true when every entry is within epsilon of m's */
+    pub fn approx_eq(&self, m: &Matrix4<T>, epsilon: T) -> bool {
+        return self
+            .m_data
+            .iter()
+            .flatten()
+            .zip(m.m_data.iter().flatten())
+            .all(|(a, b)| (*a - *b).abs() <= epsilon);
+    }
+}
+
+impl<T: Float> Multiplier<Vector4<T>> for Matrix4<T> {
+    fn mult(&self, obj: &Vector4<T>) -> Vector4<T> {
+        return Vector4 {
+            m_data: T::mat4_mul_vec4(&self.m_data, &obj.m_data),
+        };
+    }
+}
+
+impl<T: Float> Multiplier<Matrix4<T>> for Matrix4<T> {
+    fn mult(&self, obj: &Matrix4<T>) -> Matrix4<T> {
+        return Matrix4 {
+            m_data: T::mat4_mul(&self.m_data, &obj.m_data),
+        };
+    }
 }
 
 /* Associative functions implementation block */
-impl Matrix4 {
+impl<T: Float> Matrix4<T> {
     /* Constructor */
-    pub fn new<A>(args: A) -> Matrix4
+    pub fn new<A>(args: A) -> Matrix4<T>
     where
-        A: Instantiator,
+        A: Instantiator<T>,
     {
         return args.into();
     }
 
-    pub fn json(path: &str) -> Matrix4 {
-        if DEBUG {
-            println!("Matrix4::Instantiator: Invoked json overload!");
-        }
-        let mut file = File::open(path).unwrap();
-        let mut data = String::new();
-        file.read_to_string(&mut data).unwrap();
+    pub fn json(path: &str) -> Result<Matrix4<T>, Error> {
+        let arr = loader::read_values(path, 16)?;
 
-        let json: Value = serde_json::from_str(&data).expect("JSON was not well-formatted");
-
-        let vals = json.get("values");
-
-        assert_eq!(16, vals.unwrap().as_array().unwrap().len());
-
-        let vals: Option<&Vec<Value>> = vals.unwrap().as_array();
-        let mut arr = [0.0f32; 16];
-        for i in 0..16 {
-            arr[i] = vals.unwrap().get(i).unwrap().as_f64().unwrap() as f32;
+        let mut values = [T::ZERO; 16];
+        for (value, a) in values.iter_mut().zip(arr) {
+            *value = T::from_f32(a);
         }
-
-        return Matrix4::new((
-            arr[0], arr[1], arr[2], arr[3], arr[4], arr[5], arr[6], arr[7], arr[8], arr[9],
-            arr[10], arr[11], arr[12], arr[13], arr[14], arr[15],
-        ));
+        return Ok(Matrix4::new(values));
     }
 
     /* Static Constructors */
-    pub fn identity() -> Matrix4 {
-        if DEBUG {
-            println!("Matrix4::Instantiator: Invoked Identity overload!");
-        }
+    pub fn identity() -> Matrix4<T> {
         return Matrix4 {
             m_data: [
-                [1.0, 0.0, 0.0, 0.0],
-                [0.0, 1.0, 0.0, 0.0],
-                [0.0, 0.0, 1.0, 0.0],
-                [0.0, 0.0, 0.0, 1.0],
+                [T::ONE, T::ZERO, T::ZERO, T::ZERO],
+                [T::ZERO, T::ONE, T::ZERO, T::ZERO],
+                [T::ZERO, T::ZERO, T::ONE, T::ZERO],
+                [T::ZERO, T::ZERO, T::ZERO, T::ONE],
             ],
         };
     }
 
-    pub fn zero() -> Matrix4 {
-        if DEBUG {
-            println!("Matrix4::Instantiator: Invoked Zero overload!");
-        }
+    pub fn zero() -> Matrix4<T> {
         return Matrix4 {
-            m_data: [
-                [0.0, 0.0, 0.0, 0.0],
-                [0.0, 0.0, 0.0, 0.0],
-                [0.0, 0.0, 0.0, 0.0],
-                [0.0, 0.0, 0.0, 0.0],
-            ],
+            m_data: [[T::ZERO; 4]; 4],
         };
     }
-    pub fn translate(tx: f32, ty: f32, tz: f32) -> Matrix4 {
+    pub fn translate(tx: T, ty: T, tz: T) -> Matrix4<T> {
         let mut m = Self::identity();
         m.m_data[0][3] = tx;
         m.m_data[1][3] = ty;
@@ -154,7 +189,7 @@ impl Matrix4 {
         return m;
     }
 
-    pub fn scale(sx: f32, sy: f32, sz: f32) -> Matrix4 {
+    pub fn scale(sx: T, sy: T, sz: T) -> Matrix4<T> {
         let mut m = Self::identity();
         m.m_data[0][0] = sx;
         m.m_data[1][1] = sy;
@@ -162,9 +197,9 @@ impl Matrix4 {
         return m;
     }
 
-    pub fn rotate_x(angle: f32) -> Matrix4 {
+    pub fn rotate_x(angle: T) -> Matrix4<T> {
         let mut m = Self::identity();
-        let angle = angle * PI / 180.0;
+        let angle = angle.to_radians();
         m.m_data[1][1] = angle.cos();
         m.m_data[1][2] = -angle.sin();
         m.m_data[2][1] = angle.sin();
@@ -172,9 +207,9 @@ impl Matrix4 {
         return m;
     }
 
-    pub fn rotate_y(angle: f32) -> Matrix4 {
+    pub fn rotate_y(angle: T) -> Matrix4<T> {
         let mut m = Self::identity();
-        let angle = angle * PI / 180.0;
+        let angle = angle.to_radians();
         m.m_data[0][0] = angle.cos();
         m.m_data[0][2] = angle.sin();
         m.m_data[2][0] = -angle.sin();
@@ -182,9 +217,9 @@ impl Matrix4 {
         return m;
     }
 
-    pub fn rotate_z(angle: f32) -> Matrix4 {
+    pub fn rotate_z(angle: T) -> Matrix4<T> {
         let mut m = Self::identity();
-        let angle = angle * PI / 180.0;
+        let angle = angle.to_radians();
         m.m_data[0][0] = angle.cos();
         m.m_data[0][1] = -angle.sin();
         m.m_data[1][0] = angle.sin();
@@ -192,40 +227,85 @@ impl Matrix4 {
         return m;
     }
 
-    pub fn rotate<A>(args: A) -> Matrix4
+    pub fn rotate<A>(args: A) -> Matrix4<T>
     where
-        A: Rotator,
+        A: Rotator<T>,
     {
         return args.into();
     }
 
-    fn s_rotate(x: f32, y: f32, z: f32, angle: f32) -> Matrix4 {
-        let angle = angle * PI / 180.0;
-        let c: f32 = angle.cos();
-        let s: f32 = angle.sin();
-        let t: f32 = 1.0 - c;
+    /* world to camera transform, the camera sits at eye looking down -z towards target */
+    pub fn look_at(eye: &Point3<T>, target: &Point3<T>, up: &Direction3<T>) -> Matrix4<T> {
+        let f = (*target - *eye).unit();
+        let s = f.cross(up).unit();
+        let u = s.cross(&f);
+
+        let mut m = Self::identity();
+        for j in 0..3 {
+            m.m_data[0][j] = s.m_data[j];
+            m.m_data[1][j] = u.m_data[j];
+            m.m_data[2][j] = -f.m_data[j];
+        }
+        let eye = *eye - Point3::origin();
+        m.m_data[0][3] = -s.dot(&eye);
+        m.m_data[1][3] = -u.dot(&eye);
+        m.m_data[2][3] = f.dot(&eye);
+        return m;
+    }
+
+    /* camera to clip space, fov is the vertical field of view in degrees, depth maps to [-1, 1] */
+    pub fn perspective(fov: T, aspect: T, near: T, far: T) -> Matrix4<T> {
+        let t = T::ONE / (fov.to_radians() / T::from_f64(2.0)).tan();
+
+        let mut m = Self::zero();
+        m.m_data[0][0] = t / aspect;
+        m.m_data[1][1] = t;
+        m.m_data[2][2] = (far + near) / (near - far);
+        m.m_data[2][3] = T::from_f64(2.0) * far * near / (near - far);
+        m.m_data[3][2] = -T::ONE;
+        return m;
+    }
 
-        let magnitude: f32 = (x.powf(2.0) + y.powf(2.0) + z.powf(2.0)).sqrt();
+    /* camera to clip space, maps the box between the six planes to [-1, 1] on every axis */
+    pub fn orthographic(left: T, right: T, bottom: T, top: T, near: T, far: T) -> Matrix4<T> {
+        let two = T::from_f64(2.0);
+        let mut m = Self::identity();
+        m.m_data[0][0] = two / (right - left);
+        m.m_data[1][1] = two / (top - bottom);
+        m.m_data[2][2] = -two / (far - near);
+        m.m_data[0][3] = -(right + left) / (right - left);
+        m.m_data[1][3] = -(top + bottom) / (top - bottom);
+        m.m_data[2][3] = -(far + near) / (far - near);
+        return m;
+    }
+
+    fn s_rotate(x: T, y: T, z: T, angle: T) -> Matrix4<T> {
+        let angle = angle.to_radians();
+        let c: T = angle.cos();
+        let s: T = angle.sin();
+        let t: T = T::ONE - c;
+
+        let magnitude: T = (x * x + y * y + z * z).sqrt();
         let x = x / magnitude;
         let y = y / magnitude;
         let z = z / magnitude;
 
-        let tx: f32 = t * x;
-        let ty: f32 = t * y;
-        let tz: f32 = t * z;
+        let tx: T = t * x;
+        let ty: T = t * y;
+        let tz: T = t * z;
 
-        let txx: f32 = tx * x;
-        let txy: f32 = tx * y;
-        let txz: f32 = tx * z;
+        let txx: T = tx * x;
+        let txy: T = tx * y;
+        let txz: T = tx * z;
 
-        let tyy: f32 = ty * y;
-        let tyz: f32 = ty * z;
+        let tyy: T = ty * y;
+        let tyz: T = ty * z;
 
-        let tzz: f32 = tz * z;
+        let tzz: T = tz * z;
 
-        let sx: f32 = s * x;
-        let sy: f32 = s * y;
-        let sz: f32 = s * z;
+        let sx: T = s * x;
+        let sy: T = s * y;
+        let sz: T = s * z;
 
         let mut m = Self::identity();
 
@@ -244,160 +324,213 @@ impl Matrix4 {
         m.m_data[2][2] = tzz + c;
         //m.m_data[2][3] = 0.0;
 
-        m.m_data[3][0] = 0.0;
-        m.m_data[3][1] = 0.0;
-        m.m_data[3][2] = 0.0;
+        m.m_data[3][0] = T::ZERO;
+        m.m_data[3][1] = T::ZERO;
+        m.m_data[3][2] = T::ZERO;
         //m.m_data[3][3] = 1.0;
 
         return m;
     }
 
-    fn s_det4(matrix: &Matrix4) -> f32 {
-        let m0 = Matrix3::new((
-            &Vector3::new((
-                matrix.m_data[1][1],
-                matrix.m_data[1][2],
-                matrix.m_data[1][3],
-            )),
-            &Vector3::new((
-                matrix.m_data[2][1],
-                matrix.m_data[2][2],
-                matrix.m_data[2][3],
-            )),
-            &Vector3::new((
-                matrix.m_data[3][1],
-                matrix.m_data[3][2],
-                matrix.m_data[3][3],
-            )),
-        ));
-
-        let m1 = Matrix3::new((
-            &Vector3::new((
-                matrix.m_data[1][0],
-                matrix.m_data[1][2],
-                matrix.m_data[1][3],
-            )),
-            &Vector3::new((
-                matrix.m_data[2][0],
-                matrix.m_data[2][2],
-                matrix.m_data[2][3],
-            )),
-            &Vector3::new((
-                matrix.m_data[3][0],
-                matrix.m_data[3][2],
-                matrix.m_data[3][3],
-            )),
-        ));
-
-        let m2 = Matrix3::new((
-            &Vector3::new((
-                matrix.m_data[1][0],
-                matrix.m_data[1][1],
-                matrix.m_data[1][3],
-            )),
-            &Vector3::new((
-                matrix.m_data[2][0],
-                matrix.m_data[2][1],
-                matrix.m_data[2][3],
-            )),
-            &Vector3::new((
-                matrix.m_data[3][0],
-                matrix.m_data[3][1],
-                matrix.m_data[3][3],
-            )),
-        ));
-
-        let m3 = Matrix3::new((
-            &Vector3::new((
-                matrix.m_data[1][0],
-                matrix.m_data[1][1],
-                matrix.m_data[1][2],
-            )),
-            &Vector3::new((
-                matrix.m_data[2][0],
-                matrix.m_data[2][1],
-                matrix.m_data[2][2],
-            )),
-            &Vector3::new((
-                matrix.m_data[3][0],
-                matrix.m_data[3][1],
-                matrix.m_data[3][2],
-            )),
-        ));
-
-        return matrix.m_data[0][0] * Matrix3::det(&m0) - matrix.m_data[0][1] * Matrix3::det(&m1)
-            + matrix.m_data[0][2] * Matrix3::det(&m2)
-            - matrix.m_data[0][3] * Matrix3::det(&m3);
-    }
-}
-
-impl Instantiator for () {
-    fn into(self) -> Matrix4 {
-        if DEBUG {
-            println!("Matrix4::Instantiator: Invoked () Identity overload!");
+    fn s_det4(matrix: &Matrix4<T>) -> T {
+        /* Laplace expansion along the first row */
+        let mut det = T::ZERO;
+        for j in 0..4 {
+            det += matrix.m_data[0][j] * Matrix4::s_cofactor4(matrix, 0, j);
         }
-        return Matrix4 {
-            m_data: [
-                [1.0, 0.0, 0.0, 0.0],
-                [0.0, 1.0, 0.0, 0.0],
-                [0.0, 0.0, 1.0, 0.0],
-                [0.0, 0.0, 0.0, 1.0],
-            ],
+        return det;
+    }
+
+    /* signed determinant of the 3x3 minor left after removing row and column */
+    fn s_cofactor4(matrix: &Matrix4<T>, row: usize, column: usize) -> T {
+        let mut minor = Matrix3::zero();
+        for (i, r) in (0..4).filter(|r| *r != row).enumerate() {
+            for (j, c) in (0..4).filter(|c| *c != column).enumerate() {
+                minor.m_data[i][j] = matrix.m_data[r][c];
+            }
+        }
+        let det = minor.det();
+        return if (row + column).is_multiple_of(2) {
+            det
+        } else {
+            -det
         };
     }
 }
 
-impl Instantiator for i64 {
-    fn into(self) -> Matrix4 {
-        if DEBUG {
-            println!("Matrix4::Instantiator: Invoked (i64) Zero overload!");
+/* Operator overloads */
+impl<T: Float> Add for Matrix4<T> {
+    type Output = Matrix4<T>;
+
+    fn add(self, m: Matrix4<T>) -> Matrix4<T> {
+        let mut r = self;
+        for i in 0..4 {
+            for j in 0..4 {
+                r.m_data[i][j] += m.m_data[i][j];
+            }
+        }
+        return r;
+    }
+}
+
+impl<T: Float> Sub for Matrix4<T> {
+    type Output = Matrix4<T>;
+
+    fn sub(self, m: Matrix4<T>) -> Matrix4<T> {
+        let mut r = self;
+        for i in 0..4 {
+            for j in 0..4 {
+                r.m_data[i][j] -= m.m_data[i][j];
+            }
+        }
+        return r;
+    }
+}
+
+impl<T: Float> Mul<T> for Matrix4<T> {
+    type Output = Matrix4<T>;
+
+    fn mul(self, s: T) -> Matrix4<T> {
+        let mut r = self;
+        for row in r.m_data.iter_mut() {
+            for val in row.iter_mut() {
+                *val *= s;
+            }
+        }
+        return r;
+    }
+}
+
+impl<T: Float> Div<T> for Matrix4<T> {
+    type Output = Matrix4<T>;
+
+    fn div(self, s: T) -> Matrix4<T> {
+        let mut r = self;
+        for row in r.m_data.iter_mut() {
+            for val in row.iter_mut() {
+                *val /= s;
+            }
         }
+        return r;
+    }
+}
+
+impl Mul<Matrix4<f32>> for f32 {
+    type Output = Matrix4<f32>;
+
+    fn mul(self, m: Matrix4<f32>) -> Matrix4<f32> {
+        return m * self;
+    }
+}
+
+impl Mul<Matrix4<f64>> for f64 {
+    type Output = Matrix4<f64>;
+
+    fn mul(self, m: Matrix4<f64>) -> Matrix4<f64> {
+        return m * self;
+    }
+}
+
+impl<T: Float> Neg for Matrix4<T> {
+    type Output = Matrix4<T>;
+
+    fn neg(self) -> Matrix4<T> {
+        return self * -T::ONE;
+    }
+}
+
+impl<T: Float> Mul for Matrix4<T> {
+    type Output = Matrix4<T>;
+
+    fn mul(self, m: Matrix4<T>) -> Matrix4<T> {
+        return self.mult(&m);
+    }
+}
+
+impl<T: Float> Mul<Vector4<T>> for Matrix4<T> {
+    type Output = Vector4<T>;
+
+    fn mul(self, v: Vector4<T>) -> Vector4<T> {
+        return self.mult(&v);
+    }
+}
+
+impl<T: Float> AddAssign for Matrix4<T> {
+    fn add_assign(&mut self, m: Matrix4<T>) {
+        *self = *self + m;
+    }
+}
+
+impl<T: Float> SubAssign for Matrix4<T> {
+    fn sub_assign(&mut self, m: Matrix4<T>) {
+        *self = *self - m;
+    }
+}
+
+impl<T: Float> MulAssign for Matrix4<T> {
+    fn mul_assign(&mut self, m: Matrix4<T>) {
+        *self = *self * m;
+    }
+}
+
+impl<T: Float> MulAssign<T> for Matrix4<T> {
+    fn mul_assign(&mut self, s: T) {
+        *self = *self * s;
+    }
+}
+
+impl<T: Float> DivAssign<T> for Matrix4<T> {
+    fn div_assign(&mut self, s: T) {
+        *self = *self / s;
+    }
+}
+
+/* m[(row, column)] */
+impl<T: Float> Index<(usize, usize)> for Matrix4<T> {
+    type Output = T;
+
+    fn index(&self, (row, column): (usize, usize)) -> &T {
+        return &self.m_data[row][column];
+    }
+}
+
+impl<T: Float> IndexMut<(usize, usize)> for Matrix4<T> {
+    fn index_mut(&mut self, (row, column): (usize, usize)) -> &mut T {
+        return &mut self.m_data[row][column];
+    }
+}
+
+impl<T: Float> Instantiator<T> for () {
+    fn into(self) -> Matrix4<T> {
         return Matrix4 {
             m_data: [
-                [0.0, 0.0, 0.0, 0.0],
-                [0.0, 0.0, 0.0, 0.0],
-                [0.0, 0.0, 0.0, 0.0],
-                [0.0, 0.0, 0.0, 0.0],
+                [T::ONE, T::ZERO, T::ZERO, T::ZERO],
+                [T::ZERO, T::ONE, T::ZERO, T::ZERO],
+                [T::ZERO, T::ZERO, T::ONE, T::ZERO],
+                [T::ZERO, T::ZERO, T::ZERO, T::ONE],
             ],
         };
     }
 }
 
-impl Instantiator for &Matrix4 {
-    fn into(self) -> Matrix4 {
-        if DEBUG {
-            println!("Matrix4::Instantiator: Invoked (&Matrix4) overload!");
-        }
+impl<T: Float> Instantiator<T> for i64 {
+    fn into(self) -> Matrix4<T> {
+        return Matrix4 {
+            m_data: [[T::ZERO; 4]; 4],
+        };
+    }
+}
+
+impl<T: Float> Instantiator<T> for &Matrix4<T> {
+    fn into(self) -> Matrix4<T> {
         return Matrix4 {
             m_data: self.m_data,
         };
     }
 }
 
-impl Instantiator
-    for (
-        f32,
-        f32,
-        f32,
-        f32,
-        f32,
-        f32,
-        f32,
-        f32,
-        f32,
-        f32,
-        f32,
-        f32,
-        f32,
-        f32,
-        f32,
-        f32,
-    )
-{
-    fn into(self) -> Matrix4 {
-        if DEBUG {
-            println!("Matrix4::Instantiator: Invoked (f32, f32, f32, f32, f32, f32, f32, f32, f32, f32, f32, f32, f32, f32, f32, f32) overload!");
-        }
+impl<T: Float> Instantiator<T> for (T, T, T, T, T, T, T, T, T, T, T, T, T, T, T, T) {
+    fn into(self) -> Matrix4<T> {
         return Matrix4 {
             m_data: [
                 [self.0, self.1, self.2, self.3],
@@ -409,13 +542,21 @@ impl Instantiator
     }
 }
 
-impl Instantiator for (&Vector4, &Vector4, &Vector4, &Vector4) {
-    fn into(self) -> Matrix4 {
-        if DEBUG {
-            println!(
-                "Matrix4::Instantiator: Invoked (&Vector4, &Vector4, &Vector4, &Vector4) overload!"
-            );
-        }
+impl<T: Float> Instantiator<T> for [T; 16] {
+    fn into(self) -> Matrix4<T> {
+        return Matrix4 {
+            m_data: [
+                [self[0], self[1], self[2], self[3]],
+                [self[4], self[5], self[6], self[7]],
+                [self[8], self[9], self[10], self[11]],
+                [self[12], self[13], self[14], self[15]],
+            ],
+        };
+    }
+}
+
+impl<T: Float> Instantiator<T> for (&Vector4<T>, &Vector4<T>, &Vector4<T>, &Vector4<T>) {
+    fn into(self) -> Matrix4<T> {
         return Matrix4 {
             m_data: [
                 [
@@ -447,38 +588,181 @@ impl Instantiator for (&Vector4, &Vector4, &Vector4, &Vector4) {
     }
 }
 
-impl Instantiator for &str {
-    fn into(self) -> Matrix4 {
-        if DEBUG {
-            println!("Matrix4::Instantiator: Invoked (&str) json overload!");
-        }
-        return Matrix4::json(self);
+impl<T: Float> Instantiator<T> for &str {
+    fn into(self) -> Matrix4<T> {
+        return Matrix4::json(self).unwrap_or_else(|e| panic!("{}", e));
     }
 }
 
-impl Rotator for (f32, f32, f32, f32) {
-    fn into(self) -> Matrix4 {
-        if DEBUG {
-            println!("Matrix4::Rotator: Invoked (f32, f32, f32, f32) overload!");
-        }
+impl<T: Float> TryFrom<&str> for Matrix4<T> {
+    type Error = Error;
+
+    fn try_from(path: &str) -> Result<Matrix4<T>, Error> {
+        return Matrix4::json(path);
+    }
+}
+
+impl<T: Float> Rotator<T> for (T, T, T, T) {
+    fn into(self) -> Matrix4<T> {
         return Matrix4::s_rotate(self.0, self.1, self.2, self.3);
     }
 }
 
-impl Rotator for (&Vector3, f32) {
-    fn into(self) -> Matrix4 {
-        if DEBUG {
-            println!("Matrix4::Rotator: Invoked (&Vector3, f32) overload!");
-        }
+impl<T: Float> Rotator<T> for (&Vector3<T>, T) {
+    fn into(self) -> Matrix4<T> {
         return Matrix4::s_rotate(self.0.get_x(), self.0.get_y(), self.0.get_z(), self.1);
     }
 }
 
-impl Rotator for (&Vector4, f32) {
-    fn into(self) -> Matrix4 {
-        if DEBUG {
-            println!("Matrix4::Rotator: Invoked (&Vector4, f32) overload!");
-        }
+impl<T: Float> Rotator<T> for (&Vector4<T>, T) {
+    fn into(self) -> Matrix4<T> {
         return Matrix4::s_rotate(self.0.get_x(), self.0.get_y(), self.0.get_z(), self.1);
     }
 }
+
+impl<T: Float> Instantiator<T> for &Quaternion<T> {
+    fn into(self) -> Matrix4<T> {
+        return self.to_matrix4();
+    }
+}
+
+impl<T: Float> Instantiator<T> for &Transform<T> {
+    fn into(self) -> Matrix4<T> {
+        return self.to_matrix4();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matrix_vector_product() {
+        let m = Matrix4::translate(1.0, 2.0, 3.0) * Matrix4::scale(2.0, 2.0, 2.0);
+        let p = m * Vector4::new((1.0, 1.0, 1.0, 1.0));
+        assert_eq!(p, Vector4::new((3.0, 4.0, 5.0, 1.0)));
+        let d = m * Vector4::new((1.0, 1.0, 1.0, 0.0));
+        assert_eq!(d, Vector4::new((2.0, 2.0, 2.0, 0.0)));
+    }
+
+    #[test]
+    fn operators() {
+        let i = Matrix4::identity();
+        assert_eq!(i + i, i * 2.0);
+        assert_eq!(i - i, Matrix4::zero());
+        assert_eq!(-i, i * -1.0);
+        assert_eq!((i * 4.0) / 2.0, 2.0 * i);
+        assert_eq!(i[(3, 3)], 1.0);
+        assert_eq!(i[(0, 3)], 0.0);
+
+        let mut m = Matrix4::rotate_z(90.0);
+        m *= Matrix4::rotate_z(-90.0);
+        assert!(m.approx_eq(&i, 1e-6));
+        m[(0, 3)] = 5.0;
+        assert_eq!(m[(0, 3)], 5.0);
+    }
+
+    #[test]
+    fn camera_matrices() {
+        let eye = Point3::new((1.0, 2.0, 3.0));
+        let target = Point3::new((1.0, 2.0, -7.0));
+        let view = Matrix4::look_at(&eye, &target, &Direction3::new((0.0, 1.0, 0.0)));
+        assert!(
+            view.transform_point(&eye)
+                .approx_eq(&Point3::origin(), 1e-6)
+        );
+        assert!(
+            view.transform_point(&target)
+                .approx_eq(&Point3::new((0.0, 0.0, -10.0)), 1e-5)
+        );
+
+        /* the near and far planes land on -1 and 1 */
+        let projection = Matrix4::perspective(90.0, 2.0, 1.0, 10.0);
+        let near = projection.transform_point(&Point3::new((2.0, 1.0, -1.0)));
+        assert!(near.approx_eq(&Point3::new((1.0, 1.0, -1.0)), 1e-5));
+        let far = projection * Vector4::new((0.0, 0.0, -10.0, 1.0));
+        assert!((far.get_z() / far.get_w() - 1.0).abs() < 1e-5);
+
+        let projection = Matrix4::orthographic(-2.0, 2.0, -1.0, 1.0, 0.0, 10.0);
+        assert!(
+            projection
+                .transform_point(&Point3::new((2.0, -1.0, -10.0)))
+                .approx_eq(&Point3::new((1.0, -1.0, 1.0)), 1e-6)
+        );
+    }
+
+    #[test]
+    fn inverse_of_static_constructors() {
+        let transforms = [
+            Matrix4::identity(),
+            Matrix4::translate(1.0, -2.0, 3.5),
+            Matrix4::scale(2.0, 0.5, -3.0),
+            Matrix4::rotate_x(30.0),
+            Matrix4::rotate_y(-45.0),
+            Matrix4::rotate_z(120.0),
+            Matrix4::rotate((1.0, 2.0, 3.0, 60.0)),
+            Matrix4::look_at(
+                &Point3::new((1.0, 2.0, 3.0)),
+                &Point3::origin(),
+                &Direction3::new((0.0, 1.0, 0.0)),
+            ),
+            Matrix4::perspective(60.0, 1.5, 0.1, 100.0),
+            Matrix4::orthographic(-2.0, 2.0, -1.0, 1.0, 0.1, 100.0),
+            Matrix4::translate(1.0, 2.0, 3.0)
+                * Matrix4::rotate_y(75.0)
+                * Matrix4::scale(0.25, 4.0, 1.0),
+        ];
+        for m in transforms {
+            let inverse = m.inverse().unwrap();
+            assert!(
+                (m * inverse).approx_eq(&Matrix4::identity(), 1e-5),
+                "{:?}",
+                m
+            );
+            assert!(
+                (inverse * m).approx_eq(&Matrix4::identity(), 1e-5),
+                "{:?}",
+                m
+            );
+            assert!((m.det() * inverse.det() - 1.0).abs() < 1e-5);
+        }
+        assert!(Matrix4::<f32>::zero().inverse().is_none());
+        assert!(Matrix4::scale(1.0, 0.0, 1.0).inverse().is_none());
+    }
+
+    #[test]
+    fn transpose_and_normal_matrix() {
+        let m = Matrix4::new([
+            1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0, 11.0, 12.0, 13.0, 14.0, 15.0, 16.0,
+        ]);
+        assert_eq!(m.transpose()[(0, 3)], 13.0);
+        assert_eq!(m.transpose().transpose(), m);
+
+        /* squashing a 45 degree slope keeps its normal perpendicular to the squashed slope */
+        let m = Matrix4::translate(5.0, 0.0, 0.0) * Matrix4::scale(1.0, 0.5, 1.0);
+        let tangent = m.transform_direction(&Direction3::new((1.0, 1.0, 0.0)));
+        let normal = m.transform_normal(&Normal3::new((1.0, -1.0, 0.0))).unwrap();
+        assert!(normal.dot(&tangent).abs() < 1e-6);
+        /* the direction is not translated, the point is */
+        assert_eq!(tangent, Direction3::new((1.0, 0.5, 0.0)));
+        assert_eq!(
+            m.transform_point(&Point3::new((1.0, 1.0, 1.0))),
+            Point3::new((6.0, 0.5, 1.0))
+        );
+    }
+
+    #[test]
+    fn double_precision() {
+        /* far from the origin f32 cannot even represent the offset of the point */
+        let m: Matrix4<f64> = Matrix4::translate(1.0e7, -3.0e6, 2.0e7) * Matrix4::rotate_y(30.0);
+        let inverse = m.inverse().unwrap();
+        let p = Point3::new((1.0e7 + 0.125, -3.0e6, 2.0e7));
+        let local = inverse.transform_point(&p);
+        assert!((local.distance(&Point3::origin()) - 0.125).abs() < 1e-9);
+        assert!(m.transform_point(&local).approx_eq(&p, 1e-8));
+
+        let single: Matrix4 = m.cast();
+        assert!(single.cast::<f64>().approx_eq(&m, 1.0));
+        assert_ne!(single.cast::<f64>(), m);
+    }
+}
@@ -0,0 +1,410 @@
+use std::sync::Arc;
+
+use crate::aabb::Aabb;
+use crate::bvh::Bvh;
+use crate::direction3::Direction3;
+use crate::error::Error;
+use crate::gltf;
+use crate::hittable::{HitRecord, Hittable};
+use crate::lambertian::Lambertian;
+use crate::material::Material;
+use crate::matrix4::Matrix4;
+use crate::microfacet::Microfacet;
+use crate::normal3::Normal3;
+use crate::obj;
+use crate::ply;
+use crate::point3::Point3;
+use crate::ray::Ray;
+use crate::scalar::Real;
+use crate::stl;
+use crate::texture::Texture;
+use crate::triangle::Triangle;
+use crate::vector2::Vector2;
+use crate::vector3::Vector3;
+
+/* indexed triangle mesh, the attribute arrays are either empty or hold one entry per position */
+#[derive(Debug, Default)]
+pub struct Mesh {
+    pub m_positions: Vec<Point3<Real>>,
+    pub m_normals: Vec<Normal3<Real>>,
+    pub m_uvs: Vec<Vector2<Real>>,
+    /* linear rgb, see Material::m_vertex_colors */
+    pub m_colors: Vec<Vector3>,
+    /* counter-clockwise, as Triangle */
+    pub m_triangles: Vec<[u32; 3]>,
+}
+
+/* one triangle of a shared mesh, the primitive of the mesh's hierarchy */
+#[derive(Debug)]
+pub struct MeshTriangle {
+    m_mesh: Arc<Mesh>,
+    m_index: usize,
+}
+
+/* material as described by a model file, turned into a renderer material when the scene is built */
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelMaterial {
+    pub m_name: String,
+    pub m_color: Vector3,
+    pub m_vertex_colors: bool,
+    pub m_texture: Option<Arc<Texture>>,
+    /* metallic and roughness factors of formats with physically based materials, a lambertian
+    material without them */
+    pub m_metallic_roughness: Option<(f32, f32)>,
+}
+
+/* the triangles of a model that share a material */
+#[derive(Debug)]
+pub struct ModelPart {
+    pub m_mesh: Arc<Mesh>,
+    /* index into Model::m_materials, None for the material of the scene object */
+    pub m_material: Option<usize>,
+    /* places the mesh in the model, identity for formats without a node hierarchy */
+    pub m_transform: Matrix4<Real>,
+}
+
+/* camera of a model file, looking down the -z axis of m_transform with +y up */
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelCamera {
+    pub m_transform: Matrix4<Real>,
+    pub m_projection: ModelProjection,
+    /* width over height, None to follow the image */
+    pub m_aspect: Option<Real>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ModelProjection {
+    /* vertical field of view in degrees */
+    Perspective(Real),
+    /* vertical extent of the view in world units */
+    Orthographic(Real),
+}
+
+/* point, spot or directional light of a model file. The intensity is radiometric like the one
+of scene lights, W/sr for point and spot lights and W/m^2 for directional ones */
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelLight {
+    pub m_kind: ModelLightKind,
+    pub m_position: Point3<Real>,
+    /* unit direction the light shines along, unused by point lights */
+    pub m_direction: Direction3<Real>,
+    pub m_color: Vector3,
+    pub m_intensity: f32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ModelLightKind {
+    Point,
+    /* inner and outer half angles of the cone in degrees */
+    Spot(Real, Real),
+    Directional,
+}
+
+/* geometry loaded from a model file, with the cameras and lights of the formats that have them */
+#[derive(Debug, Default)]
+pub struct Model {
+    pub m_parts: Vec<ModelPart>,
+    pub m_materials: Vec<ModelMaterial>,
+    pub m_cameras: Vec<ModelCamera>,
+    pub m_lights: Vec<ModelLight>,
+}
+
+/* Associative methods implementation block */
+impl Mesh {
+    pub fn triangle(&self, index: usize) -> Triangle {
+        let [a, b, c] = self.m_triangles[index].map(|i| self.m_positions[i as usize]);
+        return Triangle::new(a, b, c);
+    }
+
+    /* positions, triangle indices and attribute lengths that agree with each other */
+    pub fn is_valid(&self) -> bool {
+        let n = self.m_positions.len();
+        return (self.m_normals.is_empty() || self.m_normals.len() == n)
+            && (self.m_uvs.is_empty() || self.m_uvs.len() == n)
+            && (self.m_colors.is_empty() || self.m_colors.len() == n)
+            && self
+                .m_triangles
+                .iter()
+                .all(|t| t.iter().all(|&i| (i as usize) < n));
+    }
+}
+
+/* Associative functions implementation block */
+impl Mesh {
+    /* Constructor */
+    pub fn new(
+        positions: Vec<Point3<Real>>,
+        normals: Vec<Normal3<Real>>,
+        uvs: Vec<Vector2<Real>>,
+        triangles: Vec<[u32; 3]>,
+    ) -> Mesh {
+        return Mesh {
+            m_positions: positions,
+            m_normals: normals,
+            m_uvs: uvs,
+            m_colors: Vec::new(),
+            m_triangles: triangles,
+        };
+    }
+
+    /* one normal per position, the average of the faces around it weighted by their area.
+    Positions no face uses keep a zero normal */
+    pub fn smooth_normals(
+        positions: &[Point3<Real>],
+        triangles: &[[u32; 3]],
+    ) -> Vec<Normal3<Real>> {
+        let mut sums = vec![Direction3::zero(); positions.len()];
+        for t in triangles {
+            let [a, b, c] = t.map(|i| positions[i as usize]);
+            /* twice the area along the face normal */
+            let n = (b - a).cross(&(c - a));
+            for &i in t {
+                sums[i as usize] += n;
+            }
+        }
+        return sums
+            .iter()
+            .map(|n| {
+                if n.magnitude() > 0.0 {
+                    Normal3::new(&n.unit())
+                } else {
+                    Normal3::new(n)
+                }
+            })
+            .collect();
+    }
+
+    /* hierarchy over every triangle of the mesh */
+    pub fn bvh(mesh: &Arc<Mesh>) -> Bvh<MeshTriangle> {
+        return Bvh::new(
+            (0..mesh.m_triangles.len())
+                .map(|i| MeshTriangle::new(mesh.clone(), i))
+                .collect(),
+        );
+    }
+}
+
+/* Associative functions implementation block */
+impl MeshTriangle {
+    /* Constructor */
+    pub fn new(mesh: Arc<Mesh>, index: usize) -> MeshTriangle {
+        return MeshTriangle {
+            m_mesh: mesh,
+            m_index: index,
+        };
+    }
+}
+
+impl Hittable for MeshTriangle {
+    /* the flat triangle's hit with its barycentrics interpolating the vertex attributes */
+    fn hit(&self, ray: &Ray, t_min: Real, t_max: Real) -> Option<HitRecord> {
+        let mut rec = self.m_mesh.triangle(self.m_index).hit(ray, t_min, t_max)?;
+        let [i0, i1, i2] = self.m_mesh.m_triangles[self.m_index].map(|i| i as usize);
+        let (u, v) = (rec.m_uv.get_u(), rec.m_uv.get_v());
+        let w = 1.0 - u - v;
+
+        let normals = &self.m_mesh.m_normals;
+        if !normals.is_empty() {
+            let n = normals[i0] * w + normals[i1] * u + normals[i2] * v;
+            /* the winding decides the front face, the vertex normals only the shading */
+            if n.magnitude() > 0.0 {
+                rec.m_normal = n.unit().face_forward(&Direction3::new(&rec.m_normal));
+            }
+        }
+        let uvs = &self.m_mesh.m_uvs;
+        if !uvs.is_empty() {
+            rec.m_uv = uvs[i0] * w + uvs[i1] * u + uvs[i2] * v;
+        }
+        let colors = &self.m_mesh.m_colors;
+        if !colors.is_empty() {
+            let [w, u, v] = [w, u, v].map(|b| b as f32);
+            rec.m_color = Some(colors[i0] * w + colors[i1] * u + colors[i2] * v);
+        }
+        return Some(rec);
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        return self.m_mesh.triangle(self.m_index).bounds();
+    }
+}
+
+/* Associative methods implementation block */
+impl ModelMaterial {
+    /* a microfacet material when the file gives metallic and roughness, else a lambertian one
+    of the color */
+    pub fn build(&self) -> Box<dyn Material> {
+        if let Some((metallic, roughness)) = self.m_metallic_roughness {
+            return Box::new(Microfacet {
+                m_base_color: self.m_color,
+                m_metallic: metallic as Real,
+                m_roughness: roughness as Real,
+                m_vertex_colors: self.m_vertex_colors,
+                m_texture: self.m_texture.clone(),
+            });
+        }
+        return Box::new(Lambertian {
+            m_albedo: self.m_color,
+            m_vertex_colors: self.m_vertex_colors,
+            m_texture: self.m_texture.clone(),
+        });
+    }
+}
+
+/* Associative functions implementation block */
+impl ModelMaterial {
+    /* Constructor, a plain color */
+    pub fn new(name: String, color: Vector3) -> ModelMaterial {
+        return ModelMaterial {
+            m_name: name,
+            m_color: color,
+            m_vertex_colors: false,
+            m_texture: None,
+            m_metallic_roughness: None,
+        };
+    }
+}
+
+/* Associative functions implementation block */
+impl ModelPart {
+    /* Constructor, a part that is not moved within its model */
+    pub fn new(mesh: Mesh, material: Option<usize>) -> ModelPart {
+        return ModelPart {
+            m_mesh: Arc::new(mesh),
+            m_material: material,
+            m_transform: Matrix4::identity(),
+        };
+    }
+}
+
+/* Associative methods implementation block */
+impl Model {
+    pub fn triangle_count(&self) -> usize {
+        return self
+            .m_parts
+            .iter()
+            .map(|p| p.m_mesh.m_triangles.len())
+            .sum();
+    }
+
+    /* box around the positions of every part as placed in the model, empty without any */
+    pub fn bounds(&self) -> Aabb {
+        let mut bounds = Aabb::empty();
+        for part in &self.m_parts {
+            for p in &part.m_mesh.m_positions {
+                bounds = bounds.union_point(&part.m_transform.transform_point(p));
+            }
+        }
+        return bounds;
+    }
+}
+
+/* Associative functions implementation block */
+impl Model {
+    /* reads a model file, the format is taken from the extension */
+    pub fn load(path: &str) -> Result<Model, Error> {
+        let extension = std::path::Path::new(path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase());
+        let model = match extension.as_deref() {
+            Some("obj") => obj::load(path)?,
+            Some("ply") => ply::load(path)?,
+            Some("stl") => stl::load(path)?,
+            Some("gltf") | Some("glb") => gltf::load(path)?,
+            _ => {
+                return Err(Error::Unsupported {
+                    m_path: String::from(path),
+                });
+            }
+        };
+        log::info!(
+            "{}: {} triangles, {} parts",
+            path,
+            model.triangle_count(),
+            model.m_parts.len()
+        );
+        return Ok(model);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /* unit square in the z = 0 plane, split along its diagonal */
+    fn square() -> Mesh {
+        return Mesh::new(
+            vec![
+                Point3::new((0.0, 0.0, 0.0)),
+                Point3::new((1.0, 0.0, 0.0)),
+                Point3::new((1.0, 1.0, 0.0)),
+                Point3::new((0.0, 1.0, 0.0)),
+            ],
+            Vec::new(),
+            vec![
+                Vector2::new((0.0, 0.0)),
+                Vector2::new((1.0, 0.0)),
+                Vector2::new((1.0, 1.0)),
+                Vector2::new((0.0, 1.0)),
+            ],
+            vec![[0, 1, 2], [0, 2, 3]],
+        );
+    }
+
+    #[test]
+    fn interpolation() {
+        let mut mesh = square();
+        /* normals tilted towards +x on the right edge */
+        mesh.m_normals = vec![
+            Normal3::new((0.0, 0.0, 1.0)),
+            Normal3::new((1.0, 0.0, 1.0)).unit(),
+            Normal3::new((1.0, 0.0, 1.0)).unit(),
+            Normal3::new((0.0, 0.0, 1.0)),
+        ];
+        assert!(mesh.is_valid());
+        let bvh = Mesh::bvh(&Arc::new(mesh));
+
+        let ray = Ray::new(
+            Point3::new((0.25, 0.75, 1.0)),
+            Direction3::new((0.0, 0.0, -1.0)),
+        );
+        let rec = bvh.hit(&ray, 0.001, Real::MAX).unwrap();
+        assert!((rec.m_t - 1.0).abs() < 1e-9);
+        assert!(rec.m_front_face);
+        assert!(rec.m_uv.approx_eq(&Vector2::new((0.25, 0.75)), 1e-9));
+        assert!(rec.m_normal.get_x() > 0.0 && rec.m_normal.get_z() > 0.0);
+        assert!((rec.m_normal.magnitude() - 1.0).abs() < 1e-9);
+
+        /* from behind, the shading normal flips with the geometric one */
+        let ray = Ray::new(
+            Point3::new((0.75, 0.25, -1.0)),
+            Direction3::new((0.0, 0.0, 1.0)),
+        );
+        let rec = bvh.hit(&ray, 0.001, Real::MAX).unwrap();
+        assert!(!rec.m_front_face);
+        assert!(rec.m_normal.get_z() < 0.0);
+    }
+
+    #[test]
+    fn smooth_normals() {
+        let mesh = square();
+        let normals = Mesh::smooth_normals(&mesh.m_positions, &mesh.m_triangles);
+        assert!(normals.iter().all(|n| *n == Normal3::new((0.0, 0.0, 1.0))));
+
+        /* a roof, the ridge gets the average of both sides, the unused position stays zero */
+        let positions = vec![
+            Point3::new((-1.0, 0.0, 0.0)),
+            Point3::new((0.0, 1.0, 0.0)),
+            Point3::new((0.0, 1.0, -1.0)),
+            Point3::new((-1.0, 0.0, -1.0)),
+            Point3::new((1.0, 0.0, 0.0)),
+            Point3::new((1.0, 0.0, -1.0)),
+            Point3::new((5.0, 5.0, 5.0)),
+        ];
+        let triangles = [[0, 1, 2], [0, 2, 3], [4, 5, 2], [4, 2, 1]];
+        let normals = Mesh::smooth_normals(&positions, &triangles);
+        assert!(normals[1].approx_eq(&Normal3::new((0.0, 1.0, 0.0)), 1e-9));
+        assert!(normals[0].approx_eq(&Normal3::new((-1.0, 1.0, 0.0)).unit(), 1e-9));
+        assert_eq!(normals[6], Normal3::new((0.0, 0.0, 0.0)));
+    }
+}
@@ -0,0 +1,135 @@
+use rand::rngs::StdRng;
+
+use crate::direction3::Direction3;
+use crate::hittable::HitRecord;
+use crate::material::{Lobe, Material, Scatter};
+use crate::ray::Ray;
+use crate::sampling;
+use crate::scalar::{Float, Real};
+use crate::vector3::Vector3;
+
+/* mirror whose reflections get blurred by m_fuzz */
+#[derive(Debug)]
+pub struct Metal {
+    pub m_albedo: Vector3,
+    /* radius of the ball the mirror direction is jittered in, 0 for a perfect mirror */
+    pub m_fuzz: Real,
+}
+
+/* Associative functions implementation block */
+impl Metal {
+    /* Constructor, fuzz is clamped to [0, 1] */
+    pub fn new(albedo: Vector3, fuzz: Real) -> Metal {
+        return Metal {
+            m_albedo: albedo,
+            m_fuzz: fuzz.clamp(0.0, 1.0),
+        };
+    }
+}
+
+impl Material for Metal {
+    /* absorbs the rays the fuzz would send below the surface */
+    fn scatter(&self, ray: &Ray, rec: &HitRecord, rng: &mut StdRng) -> Option<Scatter> {
+        let mut direction = ray.m_direction.unit().reflect(&rec.m_normal);
+        if self.m_fuzz > 0.0 {
+            direction = (direction + sampling::unit_ball(rng) * self.m_fuzz).unit();
+        }
+        if rec.m_normal.dot(&direction) <= 0.0 {
+            return None;
+        }
+        return Some(Scatter {
+            m_ray: Ray::new(rec.m_point, direction),
+            m_attenuation: self.m_albedo,
+            m_lobe: if self.m_fuzz > 0.0 {
+                Lobe::Glossy
+            } else {
+                Lobe::Specular
+            },
+        });
+    }
+
+    /* the reflections are scaled by the albedo whatever their direction, so the brdf is the
+    albedo times the density over the cosine */
+    fn eval(&self, ray: &Ray, rec: &HitRecord, direction: &Direction3<Real>) -> Vector3 {
+        let cos_theta = rec.m_normal.dot(direction);
+        if cos_theta <= 0.0 {
+            return Vector3::zero();
+        }
+        return self.m_albedo * (self.pdf(ray, rec, direction) / cos_theta) as f32;
+    }
+
+    /* the jittered points fill a ball of radius m_fuzz around the mirror direction, the density
+    is the volume of the ball's chord along the direction over the volume of the ball, zero for
+    a perfect mirror */
+    fn pdf(&self, ray: &Ray, rec: &HitRecord, direction: &Direction3<Real>) -> Real {
+        if self.m_fuzz <= 0.0 {
+            return 0.0;
+        }
+        let mirror = ray.m_direction.unit().reflect(&rec.m_normal);
+        let b = direction.dot(&mirror);
+        let discriminant = b * b - (1.0 - self.m_fuzz * self.m_fuzz);
+        if b <= 0.0 || discriminant < 0.0 {
+            return 0.0;
+        }
+        let far = b + discriminant.sqrt();
+        let near = (b - discriminant.sqrt()).max(0.0);
+        return (far.powi(3) - near.powi(3)) / (4.0 * Real::PI * self.m_fuzz.powi(3));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::normal3::Normal3;
+    use crate::point3::Point3;
+    use crate::vector2::Vector2;
+
+    #[test]
+    fn reflections() {
+        let ray = Ray::new(
+            Point3::new((-1.0, 1.0, 0.0)),
+            Direction3::new((1.0, -1.0, 0.0)),
+        );
+        let rec = HitRecord::new(&ray, 1.0, Normal3::new((0.0, 1.0, 0.0)), Vector2::zero());
+        let mut rng = StdRng::seed_from_u64(3);
+
+        let mirror = Metal::new(Vector3::new((0.9, 0.8, 0.7)), 0.0);
+        let scatter = mirror.scatter(&ray, &rec, &mut rng).unwrap();
+        let expected = Direction3::new((1.0, 1.0, 0.0)).unit();
+        assert!(scatter.m_ray.m_direction.approx_eq(&expected, 1e-12));
+        assert_eq!(scatter.m_attenuation, Vector3::new((0.9, 0.8, 0.7)));
+        assert_eq!(scatter.m_lobe, Lobe::Specular);
+
+        /* fuzzy reflections stay within the fuzz of the mirror direction, or are absorbed */
+        let fuzzy = Metal::new(Vector3::new((1.0, 1.0, 1.0)), 2.0);
+        assert_eq!(fuzzy.m_fuzz, 1.0);
+        let fuzzy = Metal::new(Vector3::new((1.0, 1.0, 1.0)), 0.3);
+        for _ in 0..100 {
+            if let Some(scatter) = fuzzy.scatter(&ray, &rec, &mut rng) {
+                let d = scatter.m_ray.m_direction;
+                assert!(d.get_y() > 0.0 && d.dot(&expected) > 0.9);
+                assert_eq!(scatter.m_lobe, Lobe::Glossy);
+            }
+        }
+        assert_eq!(
+            mirror.eval(&ray, &rec, &expected),
+            Vector3::new((0.0, 0.0, 0.0))
+        );
+
+        /* the fuzzy brdf peaks around the mirror direction and vanishes outside the fuzz */
+        assert!(fuzzy.eval(&ray, &rec, &expected).get_r() > 1.0);
+        let away = Direction3::new((-1.0, 1.0, 0.0)).unit();
+        assert_eq!(fuzzy.eval(&ray, &rec, &away), Vector3::zero());
+
+        /* the density integrates to one over the sphere, checked with uniform samples */
+        let samples = 200_000;
+        let mut sum = 0.0;
+        for _ in 0..samples {
+            let d = sampling::unit_sphere(&mut rng);
+            sum += fuzzy.pdf(&ray, &rec, &d) * 4.0 * Real::PI;
+        }
+        assert!((sum / samples as Real - 1.0).abs() < 0.05);
+    }
+}
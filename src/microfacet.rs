@@ -0,0 +1,339 @@
+use std::sync::Arc;
+
+use rand::Rng;
+use rand::rngs::StdRng;
+
+use crate::direction3::Direction3;
+use crate::hittable::HitRecord;
+use crate::material::{self, Lobe, Material, Scatter};
+use crate::ray::Ray;
+use crate::sampling::{self, Frame};
+use crate::scalar::{Float, Real};
+use crate::texture::Texture;
+use crate::vector3::Vector3;
+
+/* smallest GGX alpha, below it the distribution is too sharp for floating point */
+const MIN_ALPHA: Real = 1e-3;
+
+/* reflectance at normal incidence of dielectrics, that of an index of refraction of 1.5 */
+const DIELECTRIC_F0: f32 = 0.04;
+
+/* metallic-roughness surface of glTF: a GGX (Trowbridge-Reitz) microfacet specular layer with
+Smith masking-shadowing and Schlick's Fresnel, over a diffuse base for dielectrics. Metals have
+no diffuse base and tint their specular reflection with the base color. Light bouncing more
+than once between microfacets is lost, so rough surfaces are a little darker than they should */
+#[derive(Debug)]
+pub struct Microfacet {
+    pub m_base_color: Vector3,
+    /* 0 for dielectrics, 1 for metals, blending both in between */
+    pub m_metallic: Real,
+    /* perceptual roughness from 0 to 1, the GGX alpha is its square */
+    pub m_roughness: Real,
+    /* multiplies the base color by the vertex color of meshes that have them */
+    pub m_vertex_colors: bool,
+    /* multiplies the base color by the texture at the hit's uv */
+    pub m_texture: Option<Arc<Texture>>,
+}
+
+/* Associative methods implementation block */
+impl Microfacet {
+    pub fn alpha(&self) -> Real {
+        return (self.m_roughness * self.m_roughness).max(MIN_ALPHA);
+    }
+
+    /* GGX density of microfacet normals whose cosine with the normal is cos_h */
+    pub fn distribution(&self, cos_h: Real) -> Real {
+        if cos_h <= 0.0 {
+            return 0.0;
+        }
+        let a2 = self.alpha() * self.alpha();
+        let d = cos_h * cos_h * (a2 - 1.0) + 1.0;
+        return a2 / (Real::PI * d * d);
+    }
+
+    /* Smith masking of the microfacets seen at a cosine of cos with the normal */
+    pub fn masking(&self, cos: Real) -> Real {
+        return 2.0 * cos / (cos + self.lambda_root(cos));
+    }
+
+    /* height correlated Smith masking-shadowing of the outgoing and incoming directions */
+    pub fn shadowing(&self, cos_o: Real, cos_i: Real) -> Real {
+        return 2.0 * cos_o * cos_i
+            / (cos_o * self.lambda_root(cos_i) + cos_i * self.lambda_root(cos_o));
+    }
+
+    fn lambda_root(&self, cos: Real) -> Real {
+        let a2 = self.alpha() * self.alpha();
+        return (a2 + (1.0 - a2) * cos * cos).sqrt();
+    }
+
+    /* diffuse color and reflectance at normal incidence at the hit */
+    fn lobes(&self, rec: &HitRecord) -> (Vector3, Vector3) {
+        let base = material::albedo(
+            self.m_base_color,
+            self.m_vertex_colors,
+            &self.m_texture,
+            rec,
+        );
+        let metallic = self.m_metallic.clamp(0.0, 1.0) as f32;
+        let dielectric = Vector3::new((DIELECTRIC_F0, DIELECTRIC_F0, DIELECTRIC_F0));
+        return (
+            base * (1.0 - metallic),
+            dielectric * (1.0 - metallic) + base * metallic,
+        );
+    }
+
+    /* chance of sampling the specular lobe rather than the diffuse one, from their rough share
+    of the reflected light */
+    fn specular_probability(diffuse: Vector3, f0: Vector3, cos_o: Real) -> Real {
+        let specular = fresnel(f0, cos_o).luminance();
+        let diffuse = diffuse.luminance() * (1.0 - specular);
+        if specular + diffuse <= 0.0 {
+            return 1.0;
+        }
+        return (specular / (specular + diffuse)) as Real;
+    }
+
+    /* normal of the microfacets visible from wo, a unit direction in the local frame, sampled
+    proportionally to their projected area (Heitz 2018) */
+    fn sample_visible_normal(&self, wo: &Direction3<Real>, u1: Real, u2: Real) -> Direction3<Real> {
+        let alpha = self.alpha();
+        let vh = Direction3::new((alpha * wo.get_x(), alpha * wo.get_y(), wo.get_z())).unit();
+        let length_sq = vh.get_x() * vh.get_x() + vh.get_y() * vh.get_y();
+        let t1 = if length_sq > 0.0 {
+            Direction3::new((-vh.get_y(), vh.get_x(), 0.0)) / length_sq.sqrt()
+        } else {
+            Direction3::new((1.0, 0.0, 0.0))
+        };
+        let t2 = vh.cross(&t1);
+
+        let r = u1.sqrt();
+        let phi = 2.0 * Real::PI * u2;
+        let p1 = r * phi.cos();
+        let s = 0.5 * (1.0 + vh.get_z());
+        let p2 = (1.0 - s) * (1.0 - p1 * p1).sqrt() + s * r * phi.sin();
+        let nh = t1 * p1 + t2 * p2 + vh * (1.0 - p1 * p1 - p2 * p2).max(0.0).sqrt();
+        return Direction3::new((alpha * nh.get_x(), alpha * nh.get_y(), nh.get_z().max(0.0)))
+            .unit();
+    }
+
+    /* brdf and density of the sampling for the unit directions wo and wi, both pointing away
+    from the surface */
+    fn evaluate(
+        &self,
+        rec: &HitRecord,
+        wo: &Direction3<Real>,
+        wi: &Direction3<Real>,
+    ) -> (Vector3, Real) {
+        let cos_o = rec.m_normal.dot(wo);
+        let cos_i = rec.m_normal.dot(wi);
+        if cos_o <= 0.0 || cos_i <= 0.0 {
+            return (Vector3::zero(), 0.0);
+        }
+        let h = (*wo + *wi).unit();
+        let cos_h = rec.m_normal.dot(&h);
+        let (diffuse, f0) = self.lobes(rec);
+        let f = fresnel(f0, wo.dot(&h).max(0.0));
+        let d = self.distribution(cos_h);
+
+        let specular = f * (d * self.shadowing(cos_o, cos_i) / (4.0 * cos_o * cos_i)) as f32;
+        /* the base only gets the light the dielectric layer lets through on the way in and out,
+        which keeps rough dielectrics from reflecting more than they receive. Schlick's Fresnel
+        being linear in f0, the blend of dielectric and metal is the specular term of the blended
+        f0 and the diffuse term of the dielectric one */
+        let dielectric = Vector3::new((DIELECTRIC_F0, DIELECTRIC_F0, DIELECTRIC_F0));
+        let one = Vector3::new((1.0, 1.0, 1.0));
+        let transmitted = (one - fresnel(dielectric, cos_o)) * (one - fresnel(dielectric, cos_i));
+        let brdf = diffuse * transmitted / f32::PI + specular;
+
+        let p = Self::specular_probability(diffuse, f0, cos_o);
+        let specular_pdf = self.masking(cos_o) * d / (4.0 * cos_o);
+        let diffuse_pdf = cos_i / Real::PI;
+        return (brdf, p * specular_pdf + (1.0 - p) * diffuse_pdf);
+    }
+}
+
+/* Associative functions implementation block */
+impl Microfacet {
+    /* Constructor */
+    pub fn new(base_color: Vector3, metallic: Real, roughness: Real) -> Microfacet {
+        return Microfacet {
+            m_base_color: base_color,
+            m_metallic: metallic,
+            m_roughness: roughness,
+            m_vertex_colors: false,
+            m_texture: None,
+        };
+    }
+}
+
+impl Material for Microfacet {
+    /* visible normals of the specular lobe or the cosine for the diffuse one, picked by their
+    share of the light. The weight uses the density of both so it is the same either way */
+    fn scatter(&self, ray: &Ray, rec: &HitRecord, rng: &mut StdRng) -> Option<Scatter> {
+        let wo = -ray.m_direction.unit();
+        let cos_o = rec.m_normal.dot(&wo);
+        if cos_o <= 0.0 {
+            return None;
+        }
+        let (diffuse, f0) = self.lobes(rec);
+        let (wi, lobe) = if rng.gen::<Real>() < Self::specular_probability(diffuse, f0, cos_o) {
+            let frame = Frame::new(&rec.m_normal);
+            let h = self.sample_visible_normal(&frame.to_local(&wo), rng.gen(), rng.gen());
+            let h = frame.to_world(&h);
+            (h * (2.0 * wo.dot(&h)) - wo, Lobe::Glossy)
+        } else {
+            (
+                sampling::cosine_hemisphere(&rec.m_normal, rng),
+                Lobe::Diffuse,
+            )
+        };
+
+        let (brdf, pdf) = self.evaluate(rec, &wo, &wi);
+        if pdf <= 0.0 {
+            return None;
+        }
+        let cos_i = rec.m_normal.dot(&wi);
+        return Some(Scatter {
+            m_ray: Ray::new(rec.m_point, wi),
+            m_attenuation: brdf * (cos_i / pdf) as f32,
+            m_lobe: lobe,
+        });
+    }
+
+    fn eval(&self, ray: &Ray, rec: &HitRecord, direction: &Direction3<Real>) -> Vector3 {
+        return self.evaluate(rec, &-ray.m_direction.unit(), direction).0;
+    }
+
+    fn pdf(&self, ray: &Ray, rec: &HitRecord, direction: &Direction3<Real>) -> Real {
+        return self.evaluate(rec, &-ray.m_direction.unit(), direction).1;
+    }
+}
+
+/* Schlick's approximation of the reflectance at a cosine of cos with the microfacet normal */
+fn fresnel(f0: Vector3, cos: Real) -> Vector3 {
+    let weight = (1.0 - cos).clamp(0.0, 1.0).powi(5) as f32;
+    return f0 + (Vector3::new((1.0, 1.0, 1.0)) - f0) * weight;
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::normal3::Normal3;
+    use crate::point3::Point3;
+    use crate::vector2::Vector2;
+
+    /* ray arriving at the origin of the z = 0 plane at a cosine of cos_o with its normal */
+    fn hit(cos_o: Real) -> (Ray, HitRecord) {
+        let sin_o = (1.0 - cos_o * cos_o).sqrt();
+        let ray = Ray::new(
+            Point3::new((sin_o, 0.0, cos_o)),
+            Direction3::new((-sin_o, 0.0, -cos_o)),
+        );
+        let rec = HitRecord::new(&ray, 1.0, Normal3::new((0.0, 0.0, 1.0)), Vector2::zero());
+        return (ray, rec);
+    }
+
+    /* fraction of the light of a white environment the surface reflects, the white furnace */
+    fn furnace(material: &Microfacet, cos_o: Real, rng: &mut StdRng) -> f32 {
+        let (ray, rec) = hit(cos_o);
+        let count = 40000;
+        let mut sum = 0.0;
+        for _ in 0..count {
+            if let Some(scatter) = material.scatter(&ray, &rec, rng) {
+                assert!(scatter.m_ray.m_direction.get_z() > 0.0);
+                sum += scatter.m_attenuation.get_r() as f64;
+            }
+        }
+        return (sum / count as f64) as f32;
+    }
+
+    #[test]
+    fn white_furnace() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let white = Vector3::new((1.0, 1.0, 1.0));
+        for cos_o in [0.2, 0.6, 1.0] {
+            /* a smooth white metal reflects everything */
+            let mirror = Microfacet::new(white, 1.0, 0.05);
+            let albedo = furnace(&mirror, cos_o, &mut rng);
+            assert!((albedo - 1.0).abs() < 0.01, "{albedo}");
+
+            /* rough surfaces lose the light scattered more than once between microfacets, more
+            so the rougher they are, but never create any */
+            for metallic in [0.0, 0.5, 1.0] {
+                let mut previous = 1.01;
+                for roughness in [0.3, 0.7, 1.0] {
+                    let material = Microfacet::new(white, metallic, roughness);
+                    let albedo = furnace(&material, cos_o, &mut rng);
+                    assert!(albedo < previous, "{cos_o} {metallic} {roughness} {albedo}");
+                    previous = albedo;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn sampling_matches_eval_and_pdf() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let material = Microfacet::new(Vector3::new((0.9, 0.6, 0.3)), 0.5, 0.5);
+        let (ray, rec) = hit(0.7);
+        let count = 100000;
+        let (mut uniform, mut density, mut absorbed) = (Vector3::zero(), 0.0, 0.0);
+        for _ in 0..count {
+            /* the same integral sampled uniformly over the sphere */
+            let wi = sampling::unit_sphere(&mut rng);
+            let cos_i = wi.get_z().max(0.0);
+            let weight = (4.0 * Real::PI * cos_i / count as Real) as f32;
+            uniform += material.eval(&ray, &rec, &wi) * weight;
+            density += material.pdf(&ray, &rec, &wi) * 4.0 * Real::PI / count as Real;
+        }
+
+        let mut sampled = Vector3::zero();
+        for _ in 0..count {
+            /* visible normals reflecting below the surface are absorbed, the density misses them */
+            let Some(scatter) = material.scatter(&ray, &rec, &mut rng) else {
+                absorbed += 1.0 / count as Real;
+                continue;
+            };
+            let wi = scatter.m_ray.m_direction;
+            let pdf = material.pdf(&ray, &rec, &wi);
+            let cos_i = wi.get_z();
+            let expected = material.eval(&ray, &rec, &wi) * (cos_i / pdf) as f32;
+            assert!(scatter.m_attenuation.approx_eq(&expected, 1e-4));
+            sampled += scatter.m_attenuation / count as f32;
+        }
+        assert!(sampled.approx_eq(&uniform, 0.02), "{sampled:?} {uniform:?}");
+        assert!(absorbed > 0.0);
+        assert!(
+            (density + absorbed - 1.0).abs() < 0.02,
+            "{density} {absorbed}"
+        );
+    }
+
+    #[test]
+    fn terms() {
+        let material = Microfacet::new(Vector3::new((1.0, 1.0, 1.0)), 0.0, 0.5);
+        assert_eq!(material.alpha(), 0.25);
+        assert_eq!(
+            Microfacet::new(Vector3::zero(), 0.0, 0.0).alpha(),
+            MIN_ALPHA
+        );
+        /* no masking looking straight down, and D integrates to 1 over projected area */
+        assert!((material.masking(1.0) - 1.0).abs() < 1e-12);
+        assert!(material.shadowing(0.2, 0.3) < material.masking(0.2));
+        let steps = 100000;
+        let projected: Real = (0..steps)
+            .map(|i| {
+                let cos_h = (i as Real + 0.5) / steps as Real;
+                material.distribution(cos_h) * cos_h * 2.0 * Real::PI / steps as Real
+            })
+            .sum();
+        assert!((projected - 1.0).abs() < 1e-3);
+
+        let f0 = Vector3::new((0.04, 0.04, 0.04));
+        assert_eq!(fresnel(f0, 1.0), f0);
+        assert_eq!(fresnel(f0, 0.0), Vector3::new((1.0, 1.0, 1.0)));
+    }
+}
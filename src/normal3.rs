@@ -0,0 +1,163 @@
+use std::ops::{Add, Index, Mul, Neg};
+
+use crate::direction3::Direction3;
+use crate::scalar::Float;
+use crate::vector3::Vector3;
+
+/* surface normal. Unlike a Direction3 it stays orthogonal to the surface under non-uniform scales,
+so it is transformed by the inverse transpose, see Matrix4::normal_matrix */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Normal3<T: Float = f32> {
+    pub m_data: [T; 3],
+}
+
+pub type Normal3d = Normal3<f64>;
+
+pub trait Instantiator<T: Float> {
+    fn into(self) -> Normal3<T>;
+}
+
+/* Associative methods implementation block */
+impl<T: Float> Normal3<T> {
+    /* Accessors */
+    pub fn get_x(&self) -> T {
+        return self.m_data[0];
+    }
+
+    pub fn get_y(&self) -> T {
+        return self.m_data[1];
+    }
+
+    pub fn get_z(&self) -> T {
+        return self.m_data[2];
+    }
+
+    pub fn magnitude(&self) -> T {
+        return self.vector().magnitude();
+    }
+
+    /* cosine of the angle to d when both are unit length */
+    pub fn dot(&self, d: &Direction3<T>) -> T {
+        return self.vector().dot(&d.vector());
+    }
+
+    pub fn normalize(&mut self) {
+        *self = self.unit();
+    }
+
+    /* non-mutating counterpart of normalize() */
+    pub fn unit(&self) -> Normal3<T> {
+        return Normal3::new(&self.vector().unit());
+    }
+
+    /* the normal flipped into the hemisphere of d */
+    pub fn face_forward(&self, d: &Direction3<T>) -> Normal3<T> {
+        if self.dot(d) < T::ZERO {
+            return -*self;
+        }
+        return *self;
+    }
+
+    /* the components as an untyped vector */
+    pub fn vector(&self) -> Vector3<T> {
+        return Vector3::new((self.m_data[0], self.m_data[1], self.m_data[2]));
+    }
+
+    /* same normal in another precision */
+    pub fn cast<U: Float>(&self) -> Normal3<U> {
+        return Normal3 {
+            m_data: self.m_data.map(|v| U::from_f64(v.to_f64())),
+        };
+    }
+
+    /* true when every component is within epsilon of n's */
+    pub fn approx_eq(&self, n: &Normal3<T>, epsilon: T) -> bool {
+        return self.vector().approx_eq(&n.vector(), epsilon);
+    }
+}
+
+/* Associative functions implementation block */
+impl<T: Float> Normal3<T> {
+    /* Constructor */
+    pub fn new<A>(args: A) -> Normal3<T>
+    where
+        A: Instantiator<T>,
+    {
+        return args.into();
+    }
+}
+
+/* Operator overloads */
+/* weighted sums interpolate vertex normals */
+impl<T: Float> Add for Normal3<T> {
+    type Output = Normal3<T>;
+
+    fn add(self, n: Normal3<T>) -> Normal3<T> {
+        return Normal3::new(&(self.vector() + n.vector()));
+    }
+}
+
+impl<T: Float> Mul<T> for Normal3<T> {
+    type Output = Normal3<T>;
+
+    fn mul(self, s: T) -> Normal3<T> {
+        return Normal3::new(&(self.vector() * s));
+    }
+}
+
+impl<T: Float> Neg for Normal3<T> {
+    type Output = Normal3<T>;
+
+    fn neg(self) -> Normal3<T> {
+        return Normal3::new(&(-self.vector()));
+    }
+}
+
+impl<T: Float> Index<usize> for Normal3<T> {
+    type Output = T;
+
+    fn index(&self, i: usize) -> &T {
+        return &self.m_data[i];
+    }
+}
+
+impl<T: Float> Instantiator<T> for (T, T, T) {
+    fn into(self) -> Normal3<T> {
+        return Normal3 {
+            m_data: [self.0, self.1, self.2],
+        };
+    }
+}
+
+impl<T: Float> Instantiator<T> for &Vector3<T> {
+    fn into(self) -> Normal3<T> {
+        return Normal3 {
+            m_data: { self.m_data },
+        };
+    }
+}
+
+/* e.g. the cross product of two edges of a surface */
+impl<T: Float> Instantiator<T> for &Direction3<T> {
+    fn into(self) -> Normal3<T> {
+        return Normal3 {
+            m_data: { self.m_data },
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn face_forward() {
+        let n = Normal3::new((0.0, 2.0, 0.0)).unit();
+        assert_eq!(n, Normal3::new((0.0, 1.0, 0.0)));
+        let down = Direction3::new((0.0, -1.0, 0.0));
+        assert_eq!(n.face_forward(&down), -n);
+        assert_eq!(n.face_forward(&-down), n);
+        assert_eq!(n.dot(&down), -1.0);
+        assert_eq!(n * 0.5 + n * 0.5, n);
+    }
+}
@@ -0,0 +1,388 @@
+use std::collections::HashMap;
+
+use crate::error::Error;
+use crate::loader;
+use crate::mesh::{Mesh, Model, ModelMaterial, ModelPart};
+use crate::normal3::Normal3;
+use crate::point3::Point3;
+use crate::scalar::Real;
+use crate::vector2::Vector2;
+use crate::vector3::Vector3;
+
+/* one vertex of a face, indices into the v, vt and vn statements read so far */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Corner {
+    m_position: usize,
+    m_uv: Option<usize>,
+    m_normal: Option<usize>,
+}
+
+/* reads a Wavefront OBJ file and the MTL libraries it names, relative to its directory */
+pub fn load(path: &str) -> Result<Model, Error> {
+    return parse(&loader::read_file(path)?, path);
+}
+
+/* source is the content of the OBJ file at path, which names it in errors and locates its
+libraries. Polygons are split into fans around their first vertex, faces without normals get the
+smooth normals of their positions, and faces are grouped into one part per material */
+pub fn parse(source: &str, path: &str) -> Result<Model, Error> {
+    let mut positions: Vec<Point3<Real>> = Vec::new();
+    let mut uvs: Vec<Vector2<Real>> = Vec::new();
+    let mut normals: Vec<Normal3<Real>> = Vec::new();
+    let mut materials: Vec<ModelMaterial> = Vec::new();
+    /* triangles per material, in order of first use */
+    let mut groups: Vec<(Option<usize>, Vec<[Corner; 3]>)> = Vec::new();
+    let mut material: Option<usize> = None;
+
+    for (i, line) in source.lines().enumerate() {
+        let number = i + 1;
+        let error = |message: String| Error::parse(path, number, message);
+        let mut tokens = line.split('#').next().unwrap_or("").split_whitespace();
+        let keyword = match tokens.next() {
+            Some(keyword) => keyword,
+            None => continue,
+        };
+        let args: Vec<&str> = tokens.collect();
+
+        match keyword {
+            /* an optional w, or the r g b of the vertex color extension, are ignored */
+            "v" => {
+                let v = numbers(keyword, &args, 3, 7).map_err(error)?;
+                positions.push(Point3::new((v[0], v[1], v[2])));
+            }
+            "vt" => {
+                let v = numbers(keyword, &args, 1, 3).map_err(error)?;
+                uvs.push(Vector2::new((v[0], v.get(1).copied().unwrap_or(0.0))));
+            }
+            "vn" => {
+                let v = numbers(keyword, &args, 3, 3).map_err(error)?;
+                normals.push(Normal3::new((v[0], v[1], v[2])));
+            }
+            "f" => {
+                if args.len() < 3 {
+                    return Err(error(format!(
+                        "face with {} vertices, at least 3 expected",
+                        args.len()
+                    )));
+                }
+                let counts = [positions.len(), uvs.len(), normals.len()];
+                let corners = args
+                    .iter()
+                    .map(|token| corner(token, counts))
+                    .collect::<Result<Vec<Corner>, String>>()
+                    .map_err(error)?;
+
+                let group = match groups.iter().position(|(m, _)| *m == material) {
+                    Some(group) => group,
+                    None => {
+                        groups.push((material, Vec::new()));
+                        groups.len() - 1
+                    }
+                };
+                for k in 1..corners.len() - 1 {
+                    groups[group]
+                        .1
+                        .push([corners[0], corners[k], corners[k + 1]]);
+                }
+            }
+            "usemtl" => {
+                let name = args.join(" ");
+                /* the last definition wins when libraries repeat a name. Exporters often name
+                materials their library lacks, those faces keep the object's material */
+                material = materials.iter().rposition(|m| m.m_name == name);
+                if material.is_none() {
+                    log::warn!("{}:{}: undefined material \"{}\"", path, number, name);
+                }
+            }
+            "mtllib" => {
+                if args.is_empty() {
+                    return Err(error(String::from("mtllib without a file name")));
+                }
+                for library in &args {
+                    materials.extend(mtl(&loader::relative_to(path, library))?);
+                }
+            }
+            /* object and group names, smoothing groups, lines and points */
+            "o" | "g" | "s" | "l" | "p" => {}
+            _ => log::debug!("{}:{}: ignoring {}", path, number, keyword),
+        }
+    }
+
+    let normals: Vec<Normal3<Real>> = normals
+        .iter()
+        .map(|n| if n.magnitude() > 0.0 { n.unit() } else { *n })
+        .collect();
+    let missing_normals = groups
+        .iter()
+        .flat_map(|(_, faces)| faces.iter().flatten())
+        .any(|c| c.m_normal.is_none());
+    /* over every face of the file so parts sharing an edge stay smooth across it */
+    let smooth = if missing_normals {
+        let triangles: Vec<[u32; 3]> = groups
+            .iter()
+            .flat_map(|(_, faces)| faces.iter())
+            .map(|face| face.map(|c| c.m_position as u32))
+            .collect();
+        Mesh::smooth_normals(&positions, &triangles)
+    } else {
+        Vec::new()
+    };
+
+    let mut model = Model {
+        m_parts: Vec::with_capacity(groups.len()),
+        m_materials: materials,
+        ..Model::default()
+    };
+    for (material, faces) in groups {
+        let has_uvs = faces.iter().flatten().any(|c| c.m_uv.is_some());
+        let mut mesh = Mesh::default();
+        /* corners with the same indices become one vertex */
+        let mut vertices: HashMap<Corner, u32> = HashMap::new();
+        for face in &faces {
+            let triangle = face.map(|c| {
+                return *vertices.entry(c).or_insert_with(|| {
+                    mesh.m_positions.push(positions[c.m_position]);
+                    mesh.m_normals.push(match c.m_normal {
+                        Some(n) => normals[n],
+                        None => smooth[c.m_position],
+                    });
+                    if has_uvs {
+                        mesh.m_uvs.push(c.m_uv.map_or(Vector2::zero(), |t| uvs[t]));
+                    }
+                    (mesh.m_positions.len() - 1) as u32
+                });
+            });
+            mesh.m_triangles.push(triangle);
+        }
+        model.m_parts.push(ModelPart::new(mesh, material));
+    }
+    if model.m_parts.is_empty() {
+        log::warn!("{}: no faces", path);
+    }
+    return Ok(model);
+}
+
+/* reads the materials of an MTL library, only their diffuse color for now */
+fn mtl(path: &str) -> Result<Vec<ModelMaterial>, Error> {
+    let source = loader::read_file(path)?;
+    let mut materials: Vec<ModelMaterial> = Vec::new();
+    for (i, line) in source.lines().enumerate() {
+        let number = i + 1;
+        let error = |message: String| Error::parse(path, number, message);
+        let mut tokens = line.split('#').next().unwrap_or("").split_whitespace();
+        let keyword = match tokens.next() {
+            Some(keyword) => keyword,
+            None => continue,
+        };
+        let args: Vec<&str> = tokens.collect();
+
+        match keyword {
+            "newmtl" => {
+                if args.is_empty() {
+                    return Err(error(String::from("newmtl without a name")));
+                }
+                materials.push(ModelMaterial::new(
+                    args.join(" "),
+                    Vector3::new((0.8, 0.8, 0.8)),
+                ));
+            }
+            /* a single value is a grey */
+            "Kd" => {
+                let v = numbers(keyword, &args, 1, 3).map_err(error)?;
+                if v.len() == 2 {
+                    return Err(error(String::from("Kd expects 1 or 3 numbers, found 2")));
+                }
+                let current = materials
+                    .last_mut()
+                    .ok_or_else(|| error(String::from("Kd before any newmtl")))?;
+                let rgb = [v[0], v[v.len() / 2], v[v.len() - 1]];
+                current.m_color = Vector3::new((rgb[0] as f32, rgb[1] as f32, rgb[2] as f32));
+            }
+            _ => log::debug!("{}:{}: ignoring {}", path, number, keyword),
+        }
+    }
+    return Ok(materials);
+}
+
+/* between min and max numbers */
+fn numbers(keyword: &str, args: &[&str], min: usize, max: usize) -> Result<Vec<Real>, String> {
+    if args.len() < min || args.len() > max {
+        let expected = if min == max {
+            format!("{}", min)
+        } else {
+            format!("{} to {}", min, max)
+        };
+        return Err(format!(
+            "{} expects {} numbers, found {}",
+            keyword,
+            expected,
+            args.len()
+        ));
+    }
+    return args
+        .iter()
+        .map(|a| {
+            a.parse::<Real>()
+                .map_err(|_| format!("\"{}\" is not a number", a))
+        })
+        .collect();
+}
+
+/* v, v/vt, v//vn or v/vt/vn, counts are the number of v, vt and vn statements so far */
+fn corner(token: &str, counts: [usize; 3]) -> Result<Corner, String> {
+    let fields: Vec<&str> = token.split('/').collect();
+    if fields.len() > 3 || fields[0].is_empty() {
+        return Err(format!("malformed face vertex \"{}\"", token));
+    }
+    let kinds = ["position", "texture coordinate", "normal"];
+    let optional = |k: usize| match fields.get(k) {
+        None | Some(&"") => Ok(None),
+        Some(field) => index(field, counts[k], kinds[k]).map(Some),
+    };
+    return Ok(Corner {
+        m_position: index(fields[0], counts[0], kinds[0])?,
+        m_uv: optional(1)?,
+        m_normal: optional(2)?,
+    });
+}
+
+/* 1-based index, or negative counting back from the last one defined */
+fn index(field: &str, count: usize, kind: &str) -> Result<usize, String> {
+    let i = field
+        .parse::<i64>()
+        .map_err(|_| format!("\"{}\" is not an index", field))?;
+    let resolved = if i > 0 { i - 1 } else { count as i64 + i };
+    if i == 0 || resolved < 0 || resolved >= count as i64 {
+        return Err(format!(
+            "{} index {} out of range, {} defined so far",
+            kind, i, count
+        ));
+    }
+    return Ok(resolved as usize);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{parse_error, temp_file};
+
+    #[test]
+    fn fixture() {
+        let model = load("res/mesh/cube.obj").unwrap();
+        assert_eq!(model.m_materials.len(), 2);
+        assert_eq!(model.m_parts.len(), 2);
+        assert_eq!(model.triangle_count(), 12);
+        for part in &model.m_parts {
+            let mesh = &part.m_mesh;
+            assert!(mesh.is_valid());
+            assert_eq!(mesh.m_uvs.len(), mesh.m_positions.len());
+            /* flat faces, each corner keeps its face's normal */
+            for t in &mesh.m_triangles {
+                assert!(
+                    t.iter()
+                        .all(|&i| mesh.m_normals[i as usize] == mesh.m_normals[t[0] as usize])
+                );
+            }
+        }
+        let red = &model.m_materials[model.m_parts[1].m_material.unwrap()];
+        assert_eq!(red.m_name, "red");
+        assert_eq!(red.m_color, Vector3::new((0.8, 0.1, 0.1)));
+
+        let sphere = load("res/mesh/sphere.obj").unwrap();
+        assert_eq!(sphere.m_parts.len(), 1);
+        assert_eq!(sphere.m_parts[0].m_material, None);
+    }
+
+    #[test]
+    fn fans_and_negative_indices() {
+        /* a pentagon with absolute and relative indices, and a quad after more vertices */
+        let source = "v 0 0 0\nv 1 0 0\nv 1.5 1 0\nv 0.5 2 0\nv -0.5 1 0\n\
+                      f 1 2 3 4 5\nf -5 -4 -3 -2 -1\n\
+                      v 0 0 1\nv 1 0 1\nv 1 1 1\nv 0 1 1\nf -4 -3 -2 -1\n";
+        let model = parse(source, "fans.obj").unwrap();
+        let mesh = &model.m_parts[0].m_mesh;
+        assert_eq!(mesh.m_triangles.len(), 3 + 3 + 2);
+        assert_eq!(&mesh.m_triangles[0..3], &[[0, 1, 2], [0, 2, 3], [0, 3, 4]]);
+        assert_eq!(mesh.m_triangles[0..3], mesh.m_triangles[3..6]);
+        assert_eq!(mesh.m_positions.len(), 9);
+        assert_eq!(mesh.m_positions[5], Point3::new((0.0, 0.0, 1.0)));
+        assert!(mesh.m_uvs.is_empty());
+    }
+
+    #[test]
+    fn smooth_normals() {
+        /* without normals, the faces get the smoothed ones, see Mesh::smooth_normals */
+        let model = parse("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n", "flat.obj").unwrap();
+        let mesh = &model.m_parts[0].m_mesh;
+        assert_eq!(mesh.m_normals, vec![Normal3::new((0.0, 0.0, 1.0)); 3]);
+
+        /* given normals are kept, and normalized */
+        let model = parse(
+            "v 0 0 0\nv 1 0 0\nv 0 1 0\nvn 0 0 2\nf 1//1 2//1 3//1\n",
+            "n.obj",
+        );
+        let mesh = &model.unwrap().m_parts[0].m_mesh;
+        assert_eq!(mesh.m_normals, vec![Normal3::new((0.0, 0.0, 1.0)); 3]);
+    }
+
+    #[test]
+    fn undefined_material() {
+        /* faces after an unknown usemtl keep the object's material */
+        let source = "v 0 0 0\nv 1 0 0\nv 0 1 0\nusemtl nope\nf 1 2 3\n";
+        let model = parse(source, "nope.obj").unwrap();
+        assert_eq!(model.m_parts.len(), 1);
+        assert_eq!(model.m_parts[0].m_material, None);
+    }
+
+    #[test]
+    fn errors() {
+        let line = |source: &str| parse_error(parse(source, "bad.obj"));
+        let triangle = "v 0 0 0\nv 1 0 0\nv 0 1 0\n";
+
+        assert_eq!(line("v 0 0 0\nv 1 0\n").0, 2);
+        assert_eq!(line("v 0 x 0\n").1, "\"x\" is not a number");
+        assert_eq!(line(&format!("{}f 1 2\n", triangle)).0, 4);
+        assert_eq!(
+            line(&format!("{}f 1 2 4\n", triangle)).1,
+            "position index 4 out of range, 3 defined so far"
+        );
+        assert!(
+            line(&format!("{}f 1 2 -4\n", triangle))
+                .1
+                .contains("out of range")
+        );
+        assert!(
+            line(&format!("{}f 0 1 2\n", triangle))
+                .1
+                .contains("out of range")
+        );
+        assert!(
+            line(&format!("{}f 1/1 2/1 3/1\n", triangle))
+                .1
+                .contains("texture coordinate")
+        );
+        assert!(
+            line(&format!("{}f 1/a 2 3\n", triangle))
+                .1
+                .contains("not an index")
+        );
+        assert!(
+            line(&format!("{}f 1/1/1/1 2 3\n", triangle))
+                .1
+                .contains("malformed")
+        );
+
+        let obj = temp_file("rt_rs_missing_mtl.obj", "mtllib rt_rs_nowhere.mtl\n");
+        assert!(matches!(load(&obj), Err(Error::Io { .. })));
+
+        temp_file("rt_rs_bad.mtl", "newmtl a\nKd 1 0.5\n");
+        let obj = temp_file("rt_rs_bad_mtl.obj", "mtllib rt_rs_bad.mtl\n");
+        match load(&obj) {
+            Err(Error::Parse { m_path, m_line, .. }) => {
+                assert!(m_path.ends_with("rt_rs_bad.mtl"));
+                assert_eq!(m_line, 2);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+}
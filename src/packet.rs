@@ -0,0 +1,158 @@
+use crate::direction3::Direction3;
+use crate::hittable::HitRecord;
+use crate::point3::Point3;
+use crate::ray::Ray;
+use crate::scalar::Real;
+
+/* N rays stored as a structure of arrays, lane i of every component belongs to ray i. The packet
+kernels loop over the lanes without branching so the compiler turns them into SIMD code */
+#[derive(Debug, Clone, Copy)]
+pub struct RayPacket<const N: usize> {
+    /* x, y and z lanes */
+    pub m_origin: [[Real; N]; 3],
+    pub m_direction: [[Real; N]; 3],
+}
+
+pub type RayPacket4 = RayPacket<4>;
+pub type RayPacket8 = RayPacket<8>;
+
+/* shapes that intersect a whole packet at once */
+pub trait PacketHittable {
+    /* the records Hittable::hit gives for each lane, t_max is per lane to keep the closest hit so far */
+    fn hit_packet<const N: usize>(
+        &self,
+        packet: &RayPacket<N>,
+        t_min: Real,
+        t_max: &[Real; N],
+    ) -> [Option<HitRecord>; N];
+
+    /* whether each lane hits anything, cheaper than hit_packet for shadow rays as no record is built */
+    fn occluded_packet<const N: usize>(
+        &self,
+        packet: &RayPacket<N>,
+        t_min: Real,
+        t_max: &[Real; N],
+    ) -> [bool; N];
+}
+
+/* Associative methods implementation block */
+impl<const N: usize> RayPacket<N> {
+    /* lane i as a single ray */
+    pub fn ray(&self, i: usize) -> Ray {
+        return Ray::new(
+            Point3::new((
+                self.m_origin[0][i],
+                self.m_origin[1][i],
+                self.m_origin[2][i],
+            )),
+            Direction3::new((
+                self.m_direction[0][i],
+                self.m_direction[1][i],
+                self.m_direction[2][i],
+            )),
+        );
+    }
+}
+
+/* Associative functions implementation block */
+impl<const N: usize> RayPacket<N> {
+    /* Constructor */
+    pub fn new(rays: &[Ray; N]) -> RayPacket<N> {
+        return RayPacket {
+            m_origin: [0, 1, 2].map(|axis| rays.each_ref().map(|r| r.m_origin[axis])),
+            m_direction: [0, 1, 2].map(|axis| rays.each_ref().map(|r| r.m_direction[axis])),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use super::*;
+    use crate::hittable::Hittable;
+    use crate::sphere::Sphere;
+    use crate::triangle::Triangle;
+
+    /* rays from around the origin towards the shapes below, roughly half of them hitting */
+    fn packet<const N: usize>(rng: &mut StdRng) -> RayPacket<N> {
+        let mut value = |range: Real| rng.gen_range(-range..range);
+        let rays = [(); N].map(|_| {
+            Ray::new(
+                Point3::new((value(0.5), value(0.5), value(0.5))),
+                Direction3::new((value(1.0), value(1.0), -4.0)).unit(),
+            )
+        });
+        return RayPacket::new(&rays);
+    }
+
+    fn matches_scalar<S: Hittable + PacketHittable, const N: usize>(shape: &S) {
+        let mut rng = StdRng::seed_from_u64(15);
+        let mut hits = 0;
+        for _ in 0..200 {
+            let packet = packet::<N>(&mut rng);
+            let t_max = [(); N].map(|_| rng.gen_range(3.0..10.0));
+            let records = shape.hit_packet(&packet, 0.001, &t_max);
+            let occluded = shape.occluded_packet(&packet, 0.001, &t_max);
+            for (i, rec) in records.iter().enumerate() {
+                let expected = shape.hit(&packet.ray(i), 0.001, t_max[i]);
+                match (rec, expected) {
+                    (Some(a), Some(b)) => {
+                        assert_eq!(a.m_t, b.m_t);
+                        assert_eq!(a.m_normal, b.m_normal);
+                        assert_eq!(a.m_front_face, b.m_front_face);
+                        assert_eq!(a.m_uv, b.m_uv);
+                        assert!(occluded[i]);
+                        hits += 1;
+                    }
+                    (None, None) => assert!(!occluded[i]),
+                    (a, b) => panic!("lane {} of {:?}: {:?} and {:?}", i, packet, a, b),
+                }
+            }
+        }
+        assert!(hits > 0);
+    }
+
+    #[test]
+    fn sphere() {
+        let sphere = Sphere::new(Point3::new((0.0, 0.0, -5.0)), 1.0);
+        matches_scalar::<_, 4>(&sphere);
+        matches_scalar::<_, 8>(&sphere);
+
+        /* from inside, only the far root is in range */
+        let sphere = Sphere::new(Point3::origin(), 2.0);
+        matches_scalar::<_, 4>(&sphere);
+    }
+
+    #[test]
+    fn triangle() {
+        let triangle = Triangle::new(
+            Point3::new((-1.0, -1.0, -5.0)),
+            Point3::new((1.0, -1.0, -5.0)),
+            Point3::new((0.0, 1.0, -4.0)),
+        );
+        matches_scalar::<_, 4>(&triangle);
+        matches_scalar::<_, 8>(&triangle);
+    }
+
+    #[test]
+    fn lanes() {
+        let rays = [
+            Ray::new(Point3::origin(), Direction3::new((1.0, 0.0, 0.0))),
+            Ray::new(
+                Point3::new((1.0, 2.0, 3.0)),
+                Direction3::new((0.0, 1.0, 0.0)),
+            ),
+            Ray::new(Point3::origin(), Direction3::new((0.0, 0.0, 1.0))),
+            Ray::new(
+                Point3::new((4.0, 5.0, 6.0)),
+                Direction3::new((0.0, 0.0, -1.0)),
+            ),
+        ];
+        let packet = RayPacket4::new(&rays);
+        assert_eq!(packet.m_origin[2], [0.0, 3.0, 0.0, 6.0]);
+        assert_eq!(packet.m_direction[1], [0.0, 1.0, 0.0, 0.0]);
+        assert_eq!(packet.ray(3).m_origin, rays[3].m_origin);
+    }
+}
@@ -0,0 +1,111 @@
+use crate::aabb::Aabb;
+use crate::direction3::Direction3;
+use crate::hittable::{Hittable, HitRecord};
+use crate::normal3::Normal3;
+use crate::point3::Point3;
+use crate::ray::Ray;
+use crate::scalar::Real;
+use crate::vector2::Vector2;
+
+/* infinite plane going through m_point */
+#[derive(Debug)]
+pub struct Plane {
+    pub m_point: Point3<Real>,
+    pub m_normal: Normal3<Real>,
+}
+
+/* Associative functions implementation block */
+impl Plane {
+    /* Constructor */
+    pub fn new(point: Point3<Real>, normal: Normal3<Real>) -> Plane {
+        return Plane {
+            m_point: point,
+            m_normal: normal.unit(),
+        };
+    }
+}
+
+impl Hittable for Plane {
+    fn hit(&self, ray: &Ray, t_min: Real, t_max: Real) -> Option<HitRecord> {
+        let denom = self.m_normal.dot(&ray.m_direction);
+        /* parallel rays never hit, even when they lie within the plane */
+        if denom.abs() < 1e-8 {
+            return None;
+        }
+
+        let t = self.m_normal.dot(&(self.m_point - ray.m_origin)) / denom;
+        if t < t_min || t > t_max {
+            return None;
+        }
+
+        /* planar coordinates of the hit point, measured from m_point */
+        let normal = Direction3::new(&self.m_normal);
+        let tangent = normal.perpendicular();
+        let bitangent = normal.cross(&tangent);
+        let local = ray.at(t) - self.m_point;
+
+        return Some(HitRecord::new(
+            ray,
+            t,
+            self.m_normal,
+            Vector2::new((local.dot(&tangent), local.dot(&bitangent))),
+        ));
+    }
+
+    /* unbounded, kept out of the BVH */
+    fn bounds(&self) -> Option<Aabb> {
+        return None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ground() -> Plane {
+        return Plane::new(Point3::new((0.0, -1.0, 0.0)), Normal3::new((0.0, 1.0, 0.0)));
+    }
+
+    #[test]
+    fn hit() {
+        let ray = Ray::new(Point3::origin(), Direction3::new((0.0, -1.0, 0.0)));
+        let rec = ground().hit(&ray, 0.001, Real::MAX).unwrap();
+        assert!((rec.m_t - 1.0).abs() < 1e-5);
+        assert!(rec.m_front_face);
+        assert!((rec.m_point.get_y() + 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn hit_from_below() {
+        let ray = Ray::new(
+            Point3::new((0.0, -2.0, 0.0)),
+            Direction3::new((0.0, 1.0, 0.0)),
+        );
+        let rec = ground().hit(&ray, 0.001, Real::MAX).unwrap();
+        assert!(!rec.m_front_face);
+        assert!((rec.m_normal.get_y() + 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn miss() {
+        let ray = Ray::new(Point3::origin(), Direction3::new((0.0, 1.0, 0.0)));
+        assert!(ground().hit(&ray, 0.001, Real::MAX).is_none());
+    }
+
+    #[test]
+    fn grazing() {
+        /* parallel to the plane, above it and within it */
+        let ray = Ray::new(Point3::origin(), Direction3::new((1.0, 0.0, 0.0)));
+        assert!(ground().hit(&ray, 0.001, Real::MAX).is_none());
+        let ray = Ray::new(
+            Point3::new((0.0, -1.0, 0.0)),
+            Direction3::new((1.0, 0.0, 0.0)),
+        );
+        assert!(ground().hit(&ray, 0.001, Real::MAX).is_none());
+
+        /* very shallow angle still hits far away */
+        let ray = Ray::new(Point3::origin(), Direction3::new((1.0, -0.01, 0.0)).unit());
+        let rec = ground().hit(&ray, 0.001, Real::MAX).unwrap();
+        assert!((rec.m_point.get_x() - 100.0).abs() < 1e-2);
+    }
+}
@@ -0,0 +1,521 @@
+use crate::error::Error;
+use crate::loader;
+use crate::mesh::{Mesh, Model, ModelPart};
+use crate::normal3::Normal3;
+use crate::point3::Point3;
+use crate::scalar::Real;
+use crate::vector2::Vector2;
+use crate::vector3::Vector3;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Encoding {
+    Ascii,
+    LittleEndian,
+    BigEndian,
+}
+
+/* property types */
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Scalar {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+#[derive(Debug)]
+struct Property {
+    m_name: String,
+    m_scalar: Scalar,
+    /* type of the entry count of list properties */
+    m_count: Option<Scalar>,
+}
+
+#[derive(Debug)]
+struct Element {
+    m_name: String,
+    m_count: usize,
+    m_properties: Vec<Property>,
+}
+
+/* the body after the header, read one value at a time */
+struct Reader<'a> {
+    m_encoding: Encoding,
+    m_bytes: &'a [u8],
+    m_offset: usize,
+    /* ascii only, the rest of the current row and the line it is on */
+    m_tokens: std::str::SplitWhitespace<'a>,
+    m_lines: std::iter::Enumerate<std::str::Lines<'a>>,
+    m_header_lines: usize,
+    m_line: usize,
+}
+
+/* reads an ascii, binary little or big endian PLY file. Vertices need x, y and z, and may have
+nx ny nz normals, u v (or s t) texture coordinates and red green blue colors. Faces are lists of
+vertex_indices split into fans, other elements are skipped */
+pub fn load(path: &str) -> Result<Model, Error> {
+    return parse(&loader::read_bytes(path)?, path);
+}
+
+/* bytes is the content of the file at path, which names it in errors */
+pub fn parse(bytes: &[u8], path: &str) -> Result<Model, Error> {
+    let (encoding, elements, body, header_lines) = header(bytes, path)?;
+    let text = match encoding {
+        Encoding::Ascii => std::str::from_utf8(&bytes[body..])
+            .map_err(|e| Error::parse(path, header_lines + 1, e.to_string()))?,
+        _ => "",
+    };
+    let mut reader = Reader {
+        m_encoding: encoding,
+        m_bytes: bytes,
+        m_offset: body,
+        m_tokens: "".split_whitespace(),
+        m_lines: text.lines().enumerate(),
+        m_header_lines: header_lines,
+        m_line: header_lines,
+    };
+
+    let mut mesh = Mesh::default();
+    let vertex_count = elements
+        .iter()
+        .find(|e| e.m_name == "vertex")
+        .map_or(0, |e| e.m_count);
+    let mut values = Vec::new();
+    let mut list = Vec::new();
+    for element in &elements {
+        let position = |name: &str| element.m_properties.iter().position(|p| p.m_name == name);
+        let any = |names: &[&str]| names.iter().find_map(|name| position(name));
+        let face_list = element.m_properties.iter().find(|p| {
+            p.m_count.is_some() && (p.m_name == "vertex_indices" || p.m_name == "vertex_index")
+        });
+
+        match element.m_name.as_str() {
+            "vertex" => {
+                let xyz = [position("x"), position("y"), position("z")];
+                let [Some(x), Some(y), Some(z)] = xyz else {
+                    return Err(reader.error(path, "vertex without x, y and z"));
+                };
+                let normal = [position("nx"), position("ny"), position("nz")];
+                let uv = [any(&["u", "s", "texture_u"]), any(&["v", "t", "texture_v"])];
+                let color = [
+                    any(&["red", "diffuse_red"]),
+                    any(&["green", "diffuse_green"]),
+                    any(&["blue", "diffuse_blue"]),
+                ];
+                /* integer colors span their type's range */
+                let range =
+                    color.map(|c| c.map_or(1.0, |c| element.m_properties[c].m_scalar.range()));
+
+                for _ in 0..element.m_count {
+                    reader
+                        .row(element, None, &mut values, &mut list)
+                        .map_err(|m| reader.error(path, &m))?;
+                    mesh.m_positions
+                        .push(Point3::new((values[x], values[y], values[z])));
+                    if let [Some(nx), Some(ny), Some(nz)] = normal {
+                        let n = Normal3::new((values[nx], values[ny], values[nz]));
+                        mesh.m_normals
+                            .push(if n.magnitude() > 0.0 { n.unit() } else { n });
+                    }
+                    if let [Some(u), Some(v)] = uv {
+                        mesh.m_uvs.push(Vector2::new((values[u], values[v])));
+                    }
+                    if let [Some(r), Some(g), Some(b)] = color {
+                        mesh.m_colors.push(Vector3::new((
+                            (values[r] / range[0]) as f32,
+                            (values[g] / range[1]) as f32,
+                            (values[b] / range[2]) as f32,
+                        )));
+                    }
+                }
+            }
+            "face" => {
+                let Some(indices) = face_list else {
+                    return Err(reader.error(path, "face without a vertex_indices list"));
+                };
+                for i in 0..element.m_count {
+                    reader
+                        .row(element, Some(&indices.m_name), &mut values, &mut list)
+                        .map_err(|m| reader.error(path, &m))?;
+                    if list.len() < 3 {
+                        let message = format!(
+                            "face {} has {} vertices, at least 3 expected",
+                            i,
+                            list.len()
+                        );
+                        return Err(reader.error(path, &message));
+                    }
+                    /* ascii values are read as reals, only whole numbers in range are vertex indices */
+                    if let Some(index) = list.iter().find(|&&v| {
+                        !v.is_finite() || v.fract() != 0.0 || v < 0.0 || v >= vertex_count as Real
+                    }) {
+                        let message =
+                            format!("face {} refers to vertex {} of {}", i, index, vertex_count);
+                        return Err(reader.error(path, &message));
+                    }
+                    for k in 1..list.len() - 1 {
+                        mesh.m_triangles
+                            .push([list[0], list[k], list[k + 1]].map(|v| v as u32));
+                    }
+                }
+            }
+            _ => {
+                for _ in 0..element.m_count {
+                    reader
+                        .row(element, None, &mut values, &mut list)
+                        .map_err(|m| reader.error(path, &m))?;
+                }
+            }
+        }
+    }
+
+    if mesh.m_normals.is_empty() {
+        mesh.m_normals = Mesh::smooth_normals(&mesh.m_positions, &mesh.m_triangles);
+    }
+    let mut model = Model::default();
+    if mesh.m_triangles.is_empty() {
+        log::warn!("{}: no faces", path);
+    } else {
+        model.m_parts.push(ModelPart::new(mesh, None));
+    }
+    return Ok(model);
+}
+
+/* the encoding, the elements, where the body starts and the number of header lines */
+fn header(bytes: &[u8], path: &str) -> Result<(Encoding, Vec<Element>, usize, usize), Error> {
+    let mut encoding = None;
+    let mut elements: Vec<Element> = Vec::new();
+    let mut offset = 0;
+    let mut number = 0;
+    loop {
+        let end = match bytes[offset..].iter().position(|&b| b == b'\n') {
+            Some(end) => offset + end,
+            None => {
+                return Err(Error::parse(
+                    path,
+                    number + 1,
+                    String::from("missing end_header"),
+                ));
+            }
+        };
+        let line = String::from_utf8_lossy(&bytes[offset..end]);
+        offset = end + 1;
+        number += 1;
+        let error = |message: String| Error::parse(path, number, message);
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+
+        if number == 1 {
+            if tokens != ["ply"] {
+                return Err(error(String::from("not a PLY file")));
+            }
+            continue;
+        }
+        match tokens.as_slice() {
+            ["end_header"] => break,
+            ["comment", ..] | ["obj_info", ..] | [] => {}
+            ["format", format, "1.0"] => {
+                encoding = Some(match *format {
+                    "ascii" => Encoding::Ascii,
+                    "binary_little_endian" => Encoding::LittleEndian,
+                    "binary_big_endian" => Encoding::BigEndian,
+                    _ => return Err(error(format!("unknown format {}", format))),
+                });
+            }
+            ["element", name, count] => elements.push(Element {
+                m_name: String::from(*name),
+                m_count: count
+                    .parse()
+                    .map_err(|_| error(format!("\"{}\" is not a count", count)))?,
+                m_properties: Vec::new(),
+            }),
+            ["property", rest @ ..] => {
+                let element = elements
+                    .last_mut()
+                    .ok_or_else(|| error(String::from("property before any element")))?;
+                let scalar = |name: &str| {
+                    Scalar::parse(name).ok_or_else(|| error(format!("unknown type {}", name)))
+                };
+                element.m_properties.push(match rest {
+                    ["list", count, item, name] => Property {
+                        m_name: String::from(*name),
+                        m_scalar: scalar(item)?,
+                        m_count: Some(scalar(count)?),
+                    },
+                    [item, name] => Property {
+                        m_name: String::from(*name),
+                        m_scalar: scalar(item)?,
+                        m_count: None,
+                    },
+                    _ => return Err(error(String::from("malformed property"))),
+                });
+            }
+            _ => return Err(error(format!("unexpected \"{}\"", line.trim()))),
+        }
+    }
+
+    let encoding =
+        encoding.ok_or_else(|| Error::parse(path, number, String::from("missing format")))?;
+    return Ok((encoding, elements, offset, number));
+}
+
+/* Associative methods implementation block */
+impl Scalar {
+    fn size(&self) -> usize {
+        return match self {
+            Scalar::I8 | Scalar::U8 => 1,
+            Scalar::I16 | Scalar::U16 => 2,
+            Scalar::I32 | Scalar::U32 | Scalar::F32 => 4,
+            Scalar::F64 => 8,
+        };
+    }
+
+    /* largest value of the unsigned types, 1 for the others */
+    fn range(&self) -> Real {
+        return match self {
+            Scalar::U8 => u8::MAX as Real,
+            Scalar::U16 => u16::MAX as Real,
+            Scalar::U32 => u32::MAX as Real,
+            _ => 1.0,
+        };
+    }
+}
+
+/* Associative functions implementation block */
+impl Scalar {
+    /* both the original names and the sized ones */
+    fn parse(name: &str) -> Option<Scalar> {
+        return match name {
+            "char" | "int8" => Some(Scalar::I8),
+            "uchar" | "uint8" => Some(Scalar::U8),
+            "short" | "int16" => Some(Scalar::I16),
+            "ushort" | "uint16" => Some(Scalar::U16),
+            "int" | "int32" => Some(Scalar::I32),
+            "uint" | "uint32" => Some(Scalar::U32),
+            "float" | "float32" => Some(Scalar::F32),
+            "double" | "float64" => Some(Scalar::F64),
+            _ => None,
+        };
+    }
+}
+
+/* Associative methods implementation block */
+impl Reader<'_> {
+    /* one row of element, a value per property into values with the entries of the list called
+    list into entries. Other lists are read and dropped */
+    fn row(
+        &mut self,
+        element: &Element,
+        list: Option<&str>,
+        values: &mut Vec<Real>,
+        entries: &mut Vec<Real>,
+    ) -> Result<(), String> {
+        if self.m_encoding == Encoding::Ascii {
+            /* blank lines do not count as rows */
+            let (i, line) = self
+                .m_lines
+                .find(|(_, line)| !line.trim().is_empty())
+                .ok_or_else(|| format!("missing {} rows", element.m_name))?;
+            self.m_line = self.m_header_lines + 1 + i;
+            self.m_tokens = line.split_whitespace();
+        }
+        values.clear();
+        entries.clear();
+        for property in &element.m_properties {
+            let Some(count) = property.m_count else {
+                values.push(self.value(property.m_scalar)?);
+                continue;
+            };
+            let count = self.value(count)?;
+            if count < 0.0 {
+                return Err(format!("negative count in {}", property.m_name));
+            }
+            let keep = list == Some(property.m_name.as_str());
+            for _ in 0..count as usize {
+                let value = self.value(property.m_scalar)?;
+                if keep {
+                    entries.push(value);
+                }
+            }
+            values.push(Real::NAN);
+        }
+        if self.m_encoding == Encoding::Ascii && self.m_tokens.next().is_some() {
+            return Err(format!("too many values for {}", element.m_name));
+        }
+        return Ok(());
+    }
+
+    fn value(&mut self, scalar: Scalar) -> Result<Real, String> {
+        if self.m_encoding == Encoding::Ascii {
+            let token = self
+                .m_tokens
+                .next()
+                .ok_or_else(|| String::from("missing values"))?;
+            return token
+                .parse::<Real>()
+                .map_err(|_| format!("\"{}\" is not a number", token));
+        }
+
+        let size = scalar.size();
+        let bytes = self
+            .m_bytes
+            .get(self.m_offset..self.m_offset + size)
+            .ok_or_else(|| format!("unexpected end of file at byte {}", self.m_offset))?;
+        self.m_offset += size;
+        /* as little endian */
+        let mut b = [0u8; 8];
+        b[..size].copy_from_slice(bytes);
+        if self.m_encoding == Encoding::BigEndian {
+            b[..size].reverse();
+        }
+        return Ok(match scalar {
+            Scalar::I8 => b[0] as i8 as Real,
+            Scalar::U8 => b[0] as Real,
+            Scalar::I16 => i16::from_le_bytes([b[0], b[1]]) as Real,
+            Scalar::U16 => u16::from_le_bytes([b[0], b[1]]) as Real,
+            Scalar::I32 => i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as Real,
+            Scalar::U32 => u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as Real,
+            Scalar::F32 => f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as Real,
+            Scalar::F64 => f64::from_le_bytes(b),
+        });
+    }
+
+    /* with the line for ascii files, the byte offset is in the message of binary ones */
+    fn error(&self, path: &str, message: &str) -> Error {
+        if self.m_encoding == Encoding::Ascii {
+            return Error::parse(path, self.m_line, String::from(message));
+        }
+        return Error::Malformed {
+            m_path: String::from(path),
+            m_message: String::from(message),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::testing::parse_error;
+
+    #[test]
+    fn encodings() {
+        let meshes: Vec<Arc<Mesh>> = ["ascii", "le", "be"]
+            .iter()
+            .map(|e| {
+                let model = load(&format!("res/mesh/pyramid_{}.ply", e)).unwrap();
+                return model.m_parts[0].m_mesh.clone();
+            })
+            .collect();
+        let mesh = &meshes[0];
+        assert!(mesh.is_valid());
+        /* the quad base is split in two */
+        assert_eq!(mesh.m_triangles.len(), 6);
+        assert_eq!(&mesh.m_triangles[0..2], &[[0, 1, 2], [0, 2, 3]]);
+        assert_eq!(mesh.m_positions[4], Point3::new((0.0, 1.5, 0.0)));
+        assert_eq!(mesh.m_colors[3], Vector3::new((1.0, 1.0, 0.0)));
+        /* no normals in the file, the apex one points up */
+        assert!(mesh.m_normals[4].get_y() > 0.99);
+        for other in &meshes[1..] {
+            assert_eq!(mesh.m_positions, other.m_positions);
+            assert_eq!(mesh.m_colors, other.m_colors);
+            assert_eq!(mesh.m_triangles, other.m_triangles);
+        }
+    }
+
+    #[test]
+    fn attributes() {
+        let source = "ply\nformat ascii 1.0\nelement vertex 3\n\
+                      property double x\nproperty double y\nproperty double z\n\
+                      property float nx\nproperty float ny\nproperty float nz\n\
+                      property float s\nproperty float t\n\
+                      property float red\nproperty float green\nproperty float blue\n\
+                      element face 1\nproperty list uchar uint vertex_index\nend_header\n\
+                      0 0 0 0 0 2 0 0 0.5 0.5 0.5\n\n1 0 0 0 0 1 1 0 1 0 0\n\
+                      0 1 0 0 0 1 0 1 0 0 1\n3 0 1 2\n";
+        let model = parse(source.as_bytes(), "attributes.ply").unwrap();
+        let mesh = &model.m_parts[0].m_mesh;
+        assert_eq!(mesh.m_normals[0], Normal3::new((0.0, 0.0, 1.0)));
+        assert_eq!(mesh.m_uvs[1], Vector2::new((1.0, 0.0)));
+        assert_eq!(mesh.m_colors[0], Vector3::new((0.5, 0.5, 0.5)));
+    }
+
+    #[test]
+    fn errors() {
+        let header = "ply\nformat ascii 1.0\nelement vertex 3\nproperty float x\n\
+                      property float y\nproperty float z\n\
+                      element face 1\nproperty list uchar int vertex_indices\nend_header\n";
+        let vertices = "0 0 0\n1 0 0\n0 1 0\n";
+        let line = |source: &str| parse_error(parse(source.as_bytes(), "bad.ply"));
+
+        assert_eq!(line("plyx\n").1, "not a PLY file");
+        assert_eq!(line("ply\nformat ascii 1.0\n").1, "missing end_header");
+        assert_eq!(line("ply\nend_header\n").1, "missing format");
+        assert_eq!(
+            line("ply\nformat ascii 1.0\nproperty float x\nend_header\n"),
+            (3, String::from("property before any element"))
+        );
+        assert_eq!(
+            line(&header.replace("float y", "float16 y")).1,
+            "unknown type float16"
+        );
+        assert_eq!(
+            line(&format!("{}0 0 0\n1 0\n", header)),
+            (11, String::from("missing values"))
+        );
+        assert_eq!(
+            line(&format!("{}{}3 0 1 3\n", header, vertices)),
+            (13, String::from("face 0 refers to vertex 3 of 3"))
+        );
+        assert_eq!(
+            line(&format!("{}{}2 0 1\n", header, vertices)).1,
+            "face 0 has 2 vertices, at least 3 expected"
+        );
+        assert_eq!(
+            line(&format!("{}{}3 0 1 2 7\n", header, vertices)).1,
+            "too many values for face"
+        );
+        assert_eq!(
+            line(&format!("{}{}3 0 1.5 2\n", header, vertices)).1,
+            "face 0 refers to vertex 1.5 of 3"
+        );
+        assert_eq!(
+            line(&format!("{}{}3 0 nan 2\n", header, vertices)).1,
+            "face 0 refers to vertex NaN of 3"
+        );
+
+        /* a zero normal stays zero instead of turning into NaN */
+        let normals = header.replace(
+            "float z\n",
+            "float z\nproperty float nx\nproperty float ny\nproperty float nz\n",
+        );
+        let model = parse(
+            format!(
+                "{}0 0 0 0 0 0\n1 0 0 0 0 1\n0 1 0 0 0 2\n3 0 1 2\n",
+                normals
+            )
+            .as_bytes(),
+            "normals.ply",
+        )
+        .unwrap();
+        assert_eq!(
+            model.m_parts[0].m_mesh.m_normals,
+            [(0.0, 0.0, 0.0), (0.0, 0.0, 1.0), (0.0, 0.0, 1.0)].map(Normal3::new)
+        );
+
+        /* binary files end early */
+        let binary = header.replace("ascii", "binary_little_endian");
+        let mut bytes = binary.into_bytes();
+        bytes.extend([0u8; 30]);
+        match parse(&bytes, "short.ply") {
+            Err(Error::Malformed { m_message, .. }) => {
+                assert!(m_message.starts_with("unexpected end of file"))
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use std::ops::{Add, Index, IndexMut, Neg, Sub};
+
+use crate::scalar::Float;
+use crate::vector2::Vector2;
+
+/* pixel position or resolution, signed so differences and crop windows can go negative */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Point2i {
+    pub m_data: [i32; 2],
+}
+
+pub trait Instantiator {
+    fn into(self) -> Point2i;
+}
+
+/* Associative methods implementation block */
+impl Point2i {
+    /* Accessors */
+    pub fn get_x(&self) -> i32 {
+        return self.m_data[0];
+    }
+
+    pub fn get_y(&self) -> i32 {
+        return self.m_data[1];
+    }
+
+    /* Mutators */
+    pub fn set_x(&mut self, x: i32) {
+        self.m_data[0] = x;
+    }
+
+    pub fn set_y(&mut self, y: i32) {
+        self.m_data[1] = y;
+    }
+
+    /* component-wise minimum and maximum */
+    pub fn min(&self, p: &Point2i) -> Point2i {
+        return Point2i {
+            m_data: [
+                self.m_data[0].min(p.m_data[0]),
+                self.m_data[1].min(p.m_data[1]),
+            ],
+        };
+    }
+
+    pub fn max(&self, p: &Point2i) -> Point2i {
+        return Point2i {
+            m_data: [
+                self.m_data[0].max(p.m_data[0]),
+                self.m_data[1].max(p.m_data[1]),
+            ],
+        };
+    }
+}
+
+/* Associative functions implementation block */
+impl Point2i {
+    /* Constructor */
+    pub fn new<A>(args: A) -> Point2i
+    where
+        A: Instantiator,
+    {
+        return args.into();
+    }
+
+    /* Static Constructors */
+    pub fn zero() -> Point2i {
+        return Point2i::new(());
+    }
+}
+
+/* Operator overloads */
+impl Add for Point2i {
+    type Output = Point2i;
+
+    fn add(self, p: Point2i) -> Point2i {
+        return Point2i {
+            m_data: [self.m_data[0] + p.m_data[0], self.m_data[1] + p.m_data[1]],
+        };
+    }
+}
+
+impl Sub for Point2i {
+    type Output = Point2i;
+
+    fn sub(self, p: Point2i) -> Point2i {
+        return Point2i {
+            m_data: [self.m_data[0] - p.m_data[0], self.m_data[1] - p.m_data[1]],
+        };
+    }
+}
+
+impl Neg for Point2i {
+    type Output = Point2i;
+
+    fn neg(self) -> Point2i {
+        return Point2i {
+            m_data: [-self.m_data[0], -self.m_data[1]],
+        };
+    }
+}
+
+impl Index<usize> for Point2i {
+    type Output = i32;
+
+    fn index(&self, i: usize) -> &i32 {
+        return &self.m_data[i];
+    }
+}
+
+impl IndexMut<usize> for Point2i {
+    fn index_mut(&mut self, i: usize) -> &mut i32 {
+        return &mut self.m_data[i];
+    }
+}
+
+impl Instantiator for () {
+    fn into(self) -> Point2i {
+        return Point2i { m_data: [0; 2] };
+    }
+}
+
+impl Instantiator for (i32, i32) {
+    fn into(self) -> Point2i {
+        return Point2i {
+            m_data: [self.0, self.1],
+        };
+    }
+}
+
+/* image sizes as given by the scene and the image crate */
+impl Instantiator for (u32, u32) {
+    fn into(self) -> Point2i {
+        return Point2i {
+            m_data: [self.0 as i32, self.1 as i32],
+        };
+    }
+}
+
+/* the pixel containing the position */
+impl<T: Float> Instantiator for &Vector2<T> {
+    fn into(self) -> Point2i {
+        return Point2i {
+            m_data: self.m_data.map(|v| v.to_f64().floor() as i32),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operators() {
+        let a = Point2i::new((3, 4));
+        let b = Point2i::new((5u32, 1u32));
+        assert_eq!(a + b, Point2i::new((8, 5)));
+        assert_eq!(a - b, Point2i::new((-2, 3)));
+        assert_eq!(-a, Point2i::new((-3, -4)));
+        assert_eq!(a.min(&b), Point2i::new((3, 1)));
+        assert_eq!(a.max(&b), Point2i::new((5, 4)));
+        assert_eq!(a[1], 4);
+    }
+}
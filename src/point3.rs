@@ -0,0 +1,215 @@
+use std::ops::{Add, AddAssign, Index, Sub, SubAssign};
+
+use crate::direction3::Direction3;
+use crate::scalar::Float;
+use crate::vector3::Vector3;
+use crate::vector4::Vector4;
+
+/* position in space, w = 1 in homogeneous coordinates so Matrix4 translates it. The difference of two
+points is a Direction3, adding two points does not compile */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point3<T: Float = f32> {
+    pub m_data: [T; 3],
+}
+
+pub type Point3d = Point3<f64>;
+
+pub trait Instantiator<T: Float> {
+    fn into(self) -> Point3<T>;
+}
+
+/* Associative methods implementation block */
+impl<T: Float> Point3<T> {
+    /* Accessors */
+    pub fn get_x(&self) -> T {
+        return self.m_data[0];
+    }
+
+    pub fn get_y(&self) -> T {
+        return self.m_data[1];
+    }
+
+    pub fn get_z(&self) -> T {
+        return self.m_data[2];
+    }
+
+    /* Mutators */
+    pub fn set_x(&mut self, x: T) {
+        self.m_data[0] = x;
+    }
+
+    pub fn set_y(&mut self, y: T) {
+        self.m_data[1] = y;
+    }
+
+    pub fn set_z(&mut self, z: T) {
+        self.m_data[2] = z;
+    }
+
+    pub fn distance(&self, p: &Point3<T>) -> T {
+        return (*self - *p).magnitude();
+    }
+
+    /* component-wise minimum and maximum, the corners of the box around both points */
+    pub fn min(&self, p: &Point3<T>) -> Point3<T> {
+        return Point3 {
+            m_data: [0, 1, 2].map(|i| self.m_data[i].min(p.m_data[i])),
+        };
+    }
+
+    pub fn max(&self, p: &Point3<T>) -> Point3<T> {
+        return Point3 {
+            m_data: [0, 1, 2].map(|i| self.m_data[i].max(p.m_data[i])),
+        };
+    }
+
+    /* the coordinates as an untyped vector */
+    pub fn vector(&self) -> Vector3<T> {
+        return Vector3::new((self.m_data[0], self.m_data[1], self.m_data[2]));
+    }
+
+    /* homogeneous coordinates, w = 1 */
+    pub fn homogeneous(&self) -> Vector4<T> {
+        return Vector4::new((self.m_data[0], self.m_data[1], self.m_data[2], T::ONE));
+    }
+
+    /* same point in another precision */
+    pub fn cast<U: Float>(&self) -> Point3<U> {
+        return Point3 {
+            m_data: self.m_data.map(|v| U::from_f64(v.to_f64())),
+        };
+    }
+
+    /* true when every coordinate is within epsilon of p's */
+    pub fn approx_eq(&self, p: &Point3<T>, epsilon: T) -> bool {
+        return self
+            .m_data
+            .iter()
+            .zip(p.m_data.iter())
+            .all(|(a, b)| (*a - *b).abs() <= epsilon);
+    }
+}
+
+/* Associative functions implementation block */
+impl<T: Float> Point3<T> {
+    /* Constructor */
+    pub fn new<A>(args: A) -> Point3<T>
+    where
+        A: Instantiator<T>,
+    {
+        return args.into();
+    }
+
+    /* Static Constructors */
+    pub fn origin() -> Point3<T> {
+        return Point3::new(());
+    }
+}
+
+/* Operator overloads */
+impl<T: Float> Sub for Point3<T> {
+    type Output = Direction3<T>;
+
+    fn sub(self, p: Point3<T>) -> Direction3<T> {
+        return Direction3 {
+            m_data: [0, 1, 2].map(|i| self.m_data[i] - p.m_data[i]),
+        };
+    }
+}
+
+impl<T: Float> Add<Direction3<T>> for Point3<T> {
+    type Output = Point3<T>;
+
+    fn add(self, d: Direction3<T>) -> Point3<T> {
+        return Point3 {
+            m_data: [0, 1, 2].map(|i| self.m_data[i] + d.m_data[i]),
+        };
+    }
+}
+
+impl<T: Float> Sub<Direction3<T>> for Point3<T> {
+    type Output = Point3<T>;
+
+    fn sub(self, d: Direction3<T>) -> Point3<T> {
+        return Point3 {
+            m_data: [0, 1, 2].map(|i| self.m_data[i] - d.m_data[i]),
+        };
+    }
+}
+
+impl<T: Float> AddAssign<Direction3<T>> for Point3<T> {
+    fn add_assign(&mut self, d: Direction3<T>) {
+        *self = *self + d;
+    }
+}
+
+impl<T: Float> SubAssign<Direction3<T>> for Point3<T> {
+    fn sub_assign(&mut self, d: Direction3<T>) {
+        *self = *self - d;
+    }
+}
+
+impl<T: Float> Index<usize> for Point3<T> {
+    type Output = T;
+
+    fn index(&self, i: usize) -> &T {
+        return &self.m_data[i];
+    }
+}
+
+impl<T: Float> Instantiator<T> for () {
+    fn into(self) -> Point3<T> {
+        return Point3 {
+            m_data: [T::ZERO; 3],
+        };
+    }
+}
+
+impl<T: Float> Instantiator<T> for (T, T, T) {
+    fn into(self) -> Point3<T> {
+        return Point3 {
+            m_data: [self.0, self.1, self.2],
+        };
+    }
+}
+
+impl<T: Float> Instantiator<T> for &Vector3<T> {
+    fn into(self) -> Point3<T> {
+        return Point3 {
+            m_data: { self.m_data },
+        };
+    }
+}
+
+/* homogeneous coordinates, divided by w */
+impl<T: Float> Instantiator<T> for &Vector4<T> {
+    fn into(self) -> Point3<T> {
+        let w = self.m_data[3];
+        return Point3 {
+            m_data: [self.m_data[0] / w, self.m_data[1] / w, self.m_data[2] / w],
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn affine_operators() {
+        let a = Point3::new((1.0, 2.0, 3.0));
+        let b = Point3::new((4.0, 6.0, 3.0));
+        let d = b - a;
+        assert_eq!(d, Direction3::new((3.0, 4.0, 0.0)));
+        assert_eq!(a + d, b);
+        assert_eq!(b - d, a);
+        assert_eq!(a.distance(&b), 5.0);
+        assert_eq!(a.min(&b), Point3::new((1.0, 2.0, 3.0)));
+        assert_eq!(a.max(&b), b);
+        assert_eq!(
+            Point3::new(&Vector4::new((2.0, 4.0, 6.0, 2.0))),
+            Point3::new((1.0, 2.0, 3.0))
+        );
+        assert_eq!(a.homogeneous(), Vector4::new((1.0, 2.0, 3.0, 1.0)));
+    }
+}
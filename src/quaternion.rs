@@ -0,0 +1,492 @@
+use std::ops::{Add, Mul, MulAssign, Neg};
+
+use crate::matrix3::Matrix3;
+use crate::matrix4::Matrix4;
+use crate::scalar::Float;
+use crate::vector3::Vector3;
+
+/* rotation stored as x, y, z (vector part) and w (scalar part), unit length when it represents a rotation */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quaternion<T: Float = f32> {
+    pub m_data: [T; 4],
+}
+
+pub trait Instantiator<T: Float> {
+    fn into(self) -> Quaternion<T>;
+}
+
+/* Associative methods implementation block */
+impl<T: Float> Quaternion<T> {
+    pub fn get_x(&self) -> T {
+        return self.m_data[0];
+    }
+
+    pub fn get_y(&self) -> T {
+        return self.m_data[1];
+    }
+
+    pub fn get_z(&self) -> T {
+        return self.m_data[2];
+    }
+
+    pub fn get_w(&self) -> T {
+        return self.m_data[3];
+    }
+
+    pub fn magnitude(&self) -> T {
+        return self.dot(self).sqrt();
+    }
+
+    pub fn dot(&self, q: &Quaternion<T>) -> T {
+        return self
+            .m_data
+            .iter()
+            .zip(q.m_data.iter())
+            .map(|(a, b)| *a * *b)
+            .sum();
+    }
+
+    pub fn normalize(&mut self) {
+        let magnitude = self.magnitude();
+        for val in self.m_data.iter_mut() {
+            *val /= magnitude;
+        }
+    }
+
+    pub fn unit(&self) -> Quaternion<T> {
+        let mut q = *self;
+        q.normalize();
+        return q;
+    }
+
+    /* negated vector part, the inverse of a unit quaternion */
+    pub fn conjugate(&self) -> Quaternion<T> {
+        return Quaternion::new((
+            -self.m_data[0],
+            -self.m_data[1],
+            -self.m_data[2],
+            self.m_data[3],
+        ));
+    }
+
+    pub fn inverse(&self) -> Quaternion<T> {
+        let q = self.conjugate();
+        let norm = self.dot(self);
+        return Quaternion::new((
+            q.m_data[0] / norm,
+            q.m_data[1] / norm,
+            q.m_data[2] / norm,
+            q.m_data[3] / norm,
+        ));
+    }
+
+    /* v' = q v q*, expects a unit quaternion */
+    pub fn rotate(&self, v: &Vector3<T>) -> Vector3<T> {
+        let u = Vector3::new((self.m_data[0], self.m_data[1], self.m_data[2]));
+        let t = u.cross(v) * T::from_f64(2.0);
+        return *v + t * self.m_data[3] + u.cross(&t);
+    }
+
+    /* constant angular velocity interpolation along the shortest arc, t in [0, 1] */
+    pub fn slerp(&self, q: &Quaternion<T>, t: T) -> Quaternion<T> {
+        let (q, cos_theta) = Quaternion::s_shortest(self, q);
+
+        /* nearly parallel, sin(theta) vanishes and nlerp is as good */
+        if cos_theta > T::from_f64(0.9995) {
+            return self.s_lerp(&q, t).unit();
+        }
+
+        let theta = cos_theta.acos();
+        let sin_theta = theta.sin();
+        let a = ((T::ONE - t) * theta).sin() / sin_theta;
+        let b = (t * theta).sin() / sin_theta;
+        return *self * a + q * b;
+    }
+
+    /* normalized linear interpolation, cheaper than slerp but speeds up around the middle */
+    pub fn nlerp(&self, q: &Quaternion<T>, t: T) -> Quaternion<T> {
+        let (q, _) = Quaternion::s_shortest(self, q);
+        return self.s_lerp(&q, t).unit();
+    }
+
+    pub fn to_matrix3(&self) -> Matrix3<T> {
+        let [x, y, z, w] = self.m_data;
+        let one = T::ONE;
+        let two = T::from_f64(2.0);
+        return Matrix3 {
+            m_data: [
+                [
+                    one - two * (y * y + z * z),
+                    two * (x * y - w * z),
+                    two * (x * z + w * y),
+                ],
+                [
+                    two * (x * y + w * z),
+                    one - two * (x * x + z * z),
+                    two * (y * z - w * x),
+                ],
+                [
+                    two * (x * z - w * y),
+                    two * (y * z + w * x),
+                    one - two * (x * x + y * y),
+                ],
+            ],
+        };
+    }
+
+    pub fn to_matrix4(&self) -> Matrix4<T> {
+        let m = self.to_matrix3();
+        let mut r = Matrix4::identity();
+        for i in 0..3 {
+            for j in 0..3 {
+                r[(i, j)] = m.m_data[i][j];
+            }
+        }
+        return r;
+    }
+
+    /* rotation axis and angle in degrees, the axis is +x for the identity */
+    pub fn to_axis_angle(&self) -> (Vector3<T>, T) {
+        let q = self.unit();
+        let sin_half = (T::ONE - q.m_data[3] * q.m_data[3]).max(T::ZERO).sqrt();
+        if sin_half < T::from_f64(1e-6) {
+            return (Vector3::new((T::ONE, T::ZERO, T::ZERO)), T::ZERO);
+        }
+        let axis = Vector3::new((q.m_data[0], q.m_data[1], q.m_data[2])) / sin_half;
+        let angle = T::from_f64(2.0) * q.m_data[3].clamp(-T::ONE, T::ONE).acos();
+        return (axis, angle * T::from_f64(180.0) / T::PI);
+    }
+
+    /* inverse of from_euler, angles in degrees, x is folded into z at +-90 degrees of y */
+    pub fn to_euler(&self) -> Vector3<T> {
+        let m = self.unit().to_matrix3().m_data;
+        let y = (-m[2][0]).clamp(-T::ONE, T::ONE).asin();
+        let (x, z) = if y.cos() > T::from_f64(1e-4) {
+            (m[2][1].atan2(m[2][2]), m[1][0].atan2(m[0][0]))
+        } else {
+            ((-m[1][2]).atan2(m[1][1]), T::ZERO)
+        };
+        return Vector3::new((x, y, z)) * (T::from_f64(180.0) / T::PI);
+    }
+
+    /* q and -q are the same rotation */
+    pub fn approx_eq(&self, q: &Quaternion<T>, epsilon: T) -> bool {
+        let close = |sign: T| {
+            return self
+                .m_data
+                .iter()
+                .zip(q.m_data.iter())
+                .all(|(a, b)| (*a - sign * *b).abs() <= epsilon);
+        };
+        return close(T::ONE) || close(-T::ONE);
+    }
+
+    fn s_lerp(&self, q: &Quaternion<T>, t: T) -> Quaternion<T> {
+        let mut r = *self;
+        for i in 0..4 {
+            r.m_data[i] += (q.m_data[i] - self.m_data[i]) * t;
+        }
+        return r;
+    }
+}
+
+/* Associative functions implementation block */
+impl<T: Float> Quaternion<T> {
+    /* Constructor */
+    pub fn new<A>(args: A) -> Quaternion<T>
+    where
+        A: Instantiator<T>,
+    {
+        return args.into();
+    }
+
+    /* Static Constructors */
+    pub fn identity() -> Quaternion<T> {
+        return Quaternion::new(());
+    }
+
+    /* angle in degrees around axis, same convention as Matrix4::rotate */
+    pub fn from_axis_angle(axis: &Vector3<T>, angle: T) -> Quaternion<T> {
+        let half = angle.to_radians() / T::from_f64(2.0);
+        let axis = axis.unit() * half.sin();
+        return Quaternion::new((axis.get_x(), axis.get_y(), axis.get_z(), half.cos()));
+    }
+
+    /* angles in degrees, x applied first then y then z, same as rotate_z * rotate_y * rotate_x */
+    pub fn from_euler(x: T, y: T, z: T) -> Quaternion<T> {
+        return Quaternion::from_axis_angle(&Vector3::new((T::ZERO, T::ZERO, T::ONE)), z)
+            * Quaternion::from_axis_angle(&Vector3::new((T::ZERO, T::ONE, T::ZERO)), y)
+            * Quaternion::from_axis_angle(&Vector3::new((T::ONE, T::ZERO, T::ZERO)), x);
+    }
+
+    /* m is expected to be a pure rotation */
+    pub fn from_matrix3(m: &Matrix3<T>) -> Quaternion<T> {
+        let m = &m.m_data;
+        let trace = m[0][0] + m[1][1] + m[2][2];
+
+        /* Shepperd's method, divides by the largest of w, x, y, z to stay well conditioned */
+        let one = T::ONE;
+        let two = T::from_f64(2.0);
+        let quarter = T::from_f64(0.25);
+        let q = if trace > T::ZERO {
+            let s = two * (trace + one).sqrt();
+            Quaternion::new((
+                (m[2][1] - m[1][2]) / s,
+                (m[0][2] - m[2][0]) / s,
+                (m[1][0] - m[0][1]) / s,
+                quarter * s,
+            ))
+        } else if m[0][0] > m[1][1] && m[0][0] > m[2][2] {
+            let s = two * (one + m[0][0] - m[1][1] - m[2][2]).sqrt();
+            Quaternion::new((
+                quarter * s,
+                (m[0][1] + m[1][0]) / s,
+                (m[0][2] + m[2][0]) / s,
+                (m[2][1] - m[1][2]) / s,
+            ))
+        } else if m[1][1] > m[2][2] {
+            let s = two * (one + m[1][1] - m[0][0] - m[2][2]).sqrt();
+            Quaternion::new((
+                (m[0][1] + m[1][0]) / s,
+                quarter * s,
+                (m[1][2] + m[2][1]) / s,
+                (m[0][2] - m[2][0]) / s,
+            ))
+        } else {
+            let s = two * (one + m[2][2] - m[0][0] - m[1][1]).sqrt();
+            Quaternion::new((
+                (m[0][2] + m[2][0]) / s,
+                (m[1][2] + m[2][1]) / s,
+                quarter * s,
+                (m[1][0] - m[0][1]) / s,
+            ))
+        };
+        return q.unit();
+    }
+
+    /* uses the upper left 3x3 block, the translation is ignored */
+    pub fn from_matrix4(m: &Matrix4<T>) -> Quaternion<T> {
+        return Quaternion::from_matrix3(&m.linear());
+    }
+
+    /* flips q into the hemisphere of p so interpolation takes the short way round */
+    fn s_shortest(p: &Quaternion<T>, q: &Quaternion<T>) -> (Quaternion<T>, T) {
+        let cos_theta = p.dot(q);
+        if cos_theta < T::ZERO {
+            return (-*q, -cos_theta);
+        }
+        return (*q, cos_theta);
+    }
+}
+
+/* Operator overloads */
+impl<T: Float> Add for Quaternion<T> {
+    type Output = Quaternion<T>;
+
+    fn add(self, q: Quaternion<T>) -> Quaternion<T> {
+        let mut r = self;
+        for i in 0..4 {
+            r.m_data[i] += q.m_data[i];
+        }
+        return r;
+    }
+}
+
+/* Hamilton product, p * q rotates by q first then by p */
+impl<T: Float> Mul for Quaternion<T> {
+    type Output = Quaternion<T>;
+
+    fn mul(self, q: Quaternion<T>) -> Quaternion<T> {
+        let [x1, y1, z1, w1] = self.m_data;
+        let [x2, y2, z2, w2] = q.m_data;
+        return Quaternion::new((
+            w1 * x2 + x1 * w2 + y1 * z2 - z1 * y2,
+            w1 * y2 - x1 * z2 + y1 * w2 + z1 * x2,
+            w1 * z2 + x1 * y2 - y1 * x2 + z1 * w2,
+            w1 * w2 - x1 * x2 - y1 * y2 - z1 * z2,
+        ));
+    }
+}
+
+impl<T: Float> Mul<T> for Quaternion<T> {
+    type Output = Quaternion<T>;
+
+    fn mul(self, s: T) -> Quaternion<T> {
+        let mut r = self;
+        for val in r.m_data.iter_mut() {
+            *val *= s;
+        }
+        return r;
+    }
+}
+
+impl<T: Float> Mul<Vector3<T>> for Quaternion<T> {
+    type Output = Vector3<T>;
+
+    fn mul(self, v: Vector3<T>) -> Vector3<T> {
+        return self.rotate(&v);
+    }
+}
+
+impl<T: Float> MulAssign for Quaternion<T> {
+    fn mul_assign(&mut self, q: Quaternion<T>) {
+        *self = *self * q;
+    }
+}
+
+impl<T: Float> Neg for Quaternion<T> {
+    type Output = Quaternion<T>;
+
+    fn neg(self) -> Quaternion<T> {
+        return self * -T::ONE;
+    }
+}
+
+impl<T: Float> Instantiator<T> for () {
+    fn into(self) -> Quaternion<T> {
+        return Quaternion {
+            m_data: [T::ZERO, T::ZERO, T::ZERO, T::ONE],
+        };
+    }
+}
+
+impl<T: Float> Instantiator<T> for (T, T, T, T) {
+    fn into(self) -> Quaternion<T> {
+        return Quaternion {
+            m_data: [self.0, self.1, self.2, self.3],
+        };
+    }
+}
+
+impl<T: Float> Instantiator<T> for &Quaternion<T> {
+    fn into(self) -> Quaternion<T> {
+        return Quaternion {
+            m_data: self.m_data,
+        };
+    }
+}
+
+/* axis and angle in degrees */
+impl<T: Float> Instantiator<T> for (&Vector3<T>, T) {
+    fn into(self) -> Quaternion<T> {
+        return Quaternion::from_axis_angle(self.0, self.1);
+    }
+}
+
+impl<T: Float> Instantiator<T> for &Matrix3<T> {
+    fn into(self) -> Quaternion<T> {
+        return Quaternion::from_matrix3(self);
+    }
+}
+
+impl<T: Float> Instantiator<T> for &Matrix4<T> {
+    fn into(self) -> Quaternion<T> {
+        return Quaternion::from_matrix4(self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::direction3::Direction3;
+
+    fn axes() -> Vec<Vector3> {
+        return vec![
+            Vector3::new((1.0, 0.0, 0.0)),
+            Vector3::new((0.0, 1.0, 0.0)),
+            Vector3::new((0.0, 0.0, 1.0)),
+            Vector3::new((1.0, 2.0, 3.0)),
+            Vector3::new((-0.3, 0.1, -2.0)),
+        ];
+    }
+
+    const ANGLES: [f32; 7] = [0.0, 15.0, 90.0, 135.0, 180.0, -100.0, 270.0];
+
+    #[test]
+    fn axis_angle_matches_matrix4_rotate() {
+        for axis in axes() {
+            for angle in ANGLES {
+                let q = Quaternion::from_axis_angle(&axis, angle);
+                let m = Matrix4::rotate((&axis, angle));
+                assert!(q.to_matrix4().approx_eq(&m, 1e-5), "{:?} {}", axis, angle);
+
+                let v = Vector3::new((0.5, -1.0, 2.0));
+                let d = m.transform_direction(&Direction3::new(&v));
+                assert!((q * v).approx_eq(&d.vector(), 1e-5));
+            }
+        }
+    }
+
+    #[test]
+    fn matrix_round_trip() {
+        for axis in axes() {
+            for angle in ANGLES {
+                let m = Matrix4::rotate((&axis, angle));
+                let q = Quaternion::from_matrix4(&m);
+                assert!((q.magnitude() - 1.0).abs() < 1e-5);
+                assert!(
+                    q.approx_eq(&Quaternion::from_axis_angle(&axis, angle), 1e-5),
+                    "{:?} {}",
+                    axis,
+                    angle
+                );
+                assert!(q.to_matrix4().approx_eq(&m, 1e-5));
+                assert!(Quaternion::new(&q.to_matrix3()).approx_eq(&q, 1e-5));
+            }
+        }
+    }
+
+    #[test]
+    fn euler_and_composition() {
+        let q = Quaternion::from_euler(30.0, -45.0, 60.0);
+        let m = Matrix4::rotate_z(60.0) * Matrix4::rotate_y(-45.0) * Matrix4::rotate_x(30.0);
+        assert!(q.to_matrix4().approx_eq(&m, 1e-5));
+
+        let p = Quaternion::from_axis_angle(&Vector3::new((1.0, 1.0, 0.0)), 40.0);
+        assert!(
+            (p * q)
+                .to_matrix4()
+                .approx_eq(&(p.to_matrix4() * q.to_matrix4()), 1e-5)
+        );
+        assert!((q * q.inverse()).approx_eq(&Quaternion::identity(), 1e-6));
+        assert!((q * q.conjugate()).approx_eq(&Quaternion::identity(), 1e-6));
+
+        for (x, y, z) in [(0.0, 0.0, 0.0), (-170.0, 45.0, 120.0), (30.0, 90.0, 0.0)] {
+            let euler = Quaternion::from_euler(x, y, z).to_euler();
+            assert!(
+                euler.approx_eq(&Vector3::new((x, y, z)), 1e-2),
+                "{:?}",
+                euler
+            );
+        }
+
+        let (axis, angle) = p.to_axis_angle();
+        assert!(axis.approx_eq(&Vector3::new((1.0, 1.0, 0.0)).unit(), 1e-5));
+        assert!((angle - 40.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn interpolation() {
+        let axis = Vector3::new((0.0, 1.0, 0.0));
+        let a = Quaternion::identity();
+        let b = Quaternion::from_axis_angle(&axis, 120.0);
+
+        assert!(a.slerp(&b, 0.0).approx_eq(&a, 1e-6));
+        assert!(a.slerp(&b, 1.0).approx_eq(&b, 1e-6));
+        for t in [0.25, 0.5, 0.75] {
+            let expected = Quaternion::from_axis_angle(&axis, 120.0 * t);
+            assert!(a.slerp(&b, t).approx_eq(&expected, 1e-5));
+            assert!((a.nlerp(&b, t).magnitude() - 1.0).abs() < 1e-6);
+        }
+        assert!(
+            a.nlerp(&b, 0.5)
+                .approx_eq(&Quaternion::from_axis_angle(&axis, 60.0), 1e-5)
+        );
+
+        /* -b is the same rotation, slerp still takes the short way */
+        assert!(
+            a.slerp(&-b, 0.5)
+                .approx_eq(&Quaternion::from_axis_angle(&axis, 60.0), 1e-5)
+        );
+    }
+}
@@ -0,0 +1,28 @@
+use crate::direction3::Direction3;
+use crate::point3::Point3;
+use crate::scalar::Real;
+
+#[derive(Debug)]
+pub struct Ray {
+    pub m_origin: Point3<Real>,
+    pub m_direction: Direction3<Real>,
+}
+
+/* Associative methods implementation block */
+impl Ray {
+    /* point along the ray at parameter t */
+    pub fn at(&self, t: Real) -> Point3<Real> {
+        return self.m_origin + self.m_direction * t;
+    }
+}
+
+/* Associative functions implementation block */
+impl Ray {
+    /* Constructor */
+    pub fn new(origin: Point3<Real>, direction: Direction3<Real>) -> Ray {
+        return Ray {
+            m_origin: origin,
+            m_direction: direction,
+        };
+    }
+}
@@ -1,36 +1,765 @@
-use image::{ImageBuffer, imageops, Rgba, RgbaImage};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use image::RgbaImage;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::area_light::{QuadLight, SphereLight, TriangleLight};
+use crate::bounds2i::Bounds2i;
+use crate::bvh::Bvh;
+use crate::camera::Camera;
+use crate::direction3::Direction3;
+use crate::emissive::Emissive;
+use crate::film::Film;
+use crate::hittable::{HitRecord, Hittable, Object};
+use crate::lambertian::Lambertian;
+use crate::light::{DirectionalLight, Light, LightSample, PointLight, SpotLight};
+use crate::material::{Lobe, Material};
+use crate::point2i::Point2i;
+use crate::point3::Point3;
+use crate::ray::Ray;
+use crate::scalar::Real;
+use crate::scene::{
+    BackgroundDesc, IntegratorDesc, LightDesc, MaterialTypeDesc, Scene, direction, point, vector,
+};
+use crate::sphere::Sphere;
+use crate::tile::FrameStats;
+use crate::vector2::Vector2;
+use crate::vector3::Vector3;
+
+/* color returned for rays escaping the scene */
+#[derive(Debug)]
+pub enum Background {
+    Solid(Vector3),
+    /* bottom and top colors */
+    Gradient(Vector3, Vector3),
+}
+
+/* how the radiance arriving along camera rays is estimated */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Integrator {
+    /* direct lighting, plus the reflections and refractions of mirrors, glass and glossy
+    surfaces. Diffuse interreflections are left out */
+    Whitted,
+    /* unbiased Monte Carlo path tracing, sampling the lights at every bounce */
+    Path,
+}
 
 pub struct Renderer {
-    pub m_image_data: RgbaImage,
-    pub m_width: u32,
-    pub m_height: u32,
+    pub m_film: Film,
+    pub m_samples: u32,
+    /* reflections and refractions followed from each camera ray, or bounces of a path */
+    pub m_max_depth: u32,
+    pub m_integrator: Integrator,
+    /* bounces after which paths are ended by Russian roulette */
+    pub m_roulette_depth: u32,
+    pub m_camera: Camera,
+    pub m_background: Background,
+    /* every object of the scene, see set_objects */
+    pub m_world: Bvh<Object>,
+    pub m_materials: Vec<Box<dyn Material>>,
+    /* punctual lights and emissive objects, see set_lights */
+    pub m_lights: Vec<Box<dyn Light>>,
+    /* running sum of the powers of the lights that are not delta lights, they are picked
+    proportionally to it */
+    m_light_cdf: Vec<Real>,
+    /* 0 uses every available core */
+    pub m_threads: usize,
+    pub m_tile_size: u32,
+    pub m_stats: FrameStats,
+    /* decorrelates the sample pattern between renders */
+    pub m_seed: u64,
 }
 
 impl Renderer {
     pub fn new(width: u32, height: u32) -> Renderer {
         return Renderer {
-            m_image_data: ImageBuffer::new(width, height),
-            m_width: width,
-            m_height: height,
+            m_film: Film::new(&Point2i::new((width, height))),
+            m_samples: 1,
+            m_max_depth: 8,
+            m_integrator: Integrator::Whitted,
+            m_roulette_depth: 3,
+            m_camera: Camera::perspective(
+                &Point3::origin(),
+                &Point3::new((0.0, 0.0, -1.0)),
+                &Direction3::new((0.0, 1.0, 0.0)),
+                90.0,
+                width as Real / height as Real,
+            )
+            .unwrap_or_default(),
+            m_background: Background::Gradient(
+                Vector3::new((1.0, 1.0, 1.0)),
+                Vector3::new((0.5, 0.7, 1.0)),
+            ),
+            m_world: Bvh::new(Vec::new()),
+            /* objects default to the first material */
+            m_materials: vec![Box::new(Lambertian::new(Vector3::new((0.8, 0.8, 0.8))))],
+            m_lights: Vec::new(),
+            m_light_cdf: Vec::new(),
+            m_threads: 0,
+            m_tile_size: 32,
+            m_stats: FrameStats::default(),
+            m_seed: 0,
         };
     }
+
+    pub fn from_scene(scene: &Scene) -> Renderer {
+        let width = scene.m_image.m_width;
+        let height = scene.m_image.m_height;
+        let mut s_renderer = Renderer::new(width, height);
+
+        s_renderer.m_samples = scene.m_image.m_samples_per_pixel.max(1);
+        s_renderer.m_max_depth = scene.m_image.m_max_depth;
+        s_renderer.m_integrator = match scene.m_image.m_integrator {
+            IntegratorDesc::Whitted => Integrator::Whitted,
+            IntegratorDesc::Path => Integrator::Path,
+        };
+        s_renderer.m_roulette_depth = scene.m_image.m_roulette_depth;
+        match scene.m_camera.build(width as Real / height as Real) {
+            Some(camera) => s_renderer.m_camera = camera,
+            None => log::warn!("degenerate camera, using the default one"),
+        }
+        s_renderer.m_background = match &scene.m_background {
+            BackgroundDesc::Solid { m_color } => Background::Solid(vector(m_color)),
+            BackgroundDesc::Gradient { m_bottom, m_top } => {
+                Background::Gradient(vector(m_bottom), vector(m_top))
+            }
+        };
+
+        s_renderer.m_materials = scene.m_materials.iter().map(|m| m.build()).collect();
+        let mut lights: Vec<Box<dyn Light>> = Vec::new();
+        let mut objects = Vec::new();
+        for light in &scene.m_lights {
+            match light {
+                LightDesc::Point {
+                    m_position,
+                    m_color,
+                    m_intensity,
+                } => lights.push(Box::new(PointLight::new(
+                    point(m_position),
+                    vector(m_color),
+                    *m_intensity,
+                ))),
+                LightDesc::Directional {
+                    m_direction,
+                    m_color,
+                    m_intensity,
+                } => lights.push(Box::new(DirectionalLight::new(
+                    direction(m_direction),
+                    vector(m_color),
+                    *m_intensity,
+                ))),
+                LightDesc::Spot {
+                    m_position,
+                    m_direction,
+                    m_color,
+                    m_intensity,
+                    m_inner_angle,
+                    m_outer_angle,
+                } => lights.push(Box::new(SpotLight::new(
+                    point(m_position),
+                    direction(m_direction),
+                    vector(m_color),
+                    *m_intensity,
+                    *m_inner_angle as Real,
+                    *m_outer_angle as Real,
+                ))),
+                /* area lights are also seen, as objects with an emissive material of their own */
+                LightDesc::Quad {
+                    m_corner,
+                    m_u,
+                    m_v,
+                    m_color,
+                    m_intensity,
+                } => {
+                    let radiance = vector(m_color) * *m_intensity;
+                    let quad =
+                        QuadLight::new(point(m_corner), direction(m_u), direction(m_v), radiance);
+                    if quad.area() <= 0.0 {
+                        log::warn!("degenerate quad light, skipped");
+                        continue;
+                    }
+                    s_renderer
+                        .m_materials
+                        .push(Box::new(Emissive::new(radiance)));
+                    for triangle in quad.triangles() {
+                        let mut object =
+                            Object::new(Box::new(triangle), s_renderer.m_materials.len() - 1);
+                        object.m_light = Some(lights.len());
+                        objects.push(object);
+                    }
+                    lights.push(Box::new(quad));
+                }
+                LightDesc::Sphere {
+                    m_center,
+                    m_radius,
+                    m_color,
+                    m_intensity,
+                } => {
+                    let radiance = vector(m_color) * *m_intensity;
+                    let sphere = SphereLight::new(point(m_center), *m_radius as Real, radiance);
+                    s_renderer
+                        .m_materials
+                        .push(Box::new(Emissive::new(radiance)));
+                    let mut object = Object::new(
+                        Box::new(Sphere::new(sphere.m_center, sphere.m_radius)),
+                        s_renderer.m_materials.len() - 1,
+                    );
+                    object.m_light = Some(lights.len());
+                    objects.push(object);
+                    lights.push(Box::new(sphere));
+                }
+            }
+        }
+        for object in &scene.m_objects {
+            let mut built = object.build(scene, &mut s_renderer.m_materials);
+            let material = scene.material_index(&object.m_material).unwrap_or(0);
+            let emissive = scene.m_materials.get(material);
+            if let Some(desc) = emissive.filter(|m| m.m_type == MaterialTypeDesc::Emissive) {
+                let radiance = vector(&desc.m_color) * desc.m_intensity;
+                let light: Option<Box<dyn Light>> = match object.sphere() {
+                    Some((center, radius)) => {
+                        Some(Box::new(SphereLight::new(center, radius, radiance)))
+                    }
+                    None => object
+                        .triangles()
+                        .and_then(|t| TriangleLight::new(t, radiance))
+                        .map(|light| Box::new(light) as Box<dyn Light>),
+                };
+                match light {
+                    Some(light) => {
+                        for part in built.iter_mut().filter(|o| o.m_material == material) {
+                            part.m_light = Some(lights.len());
+                        }
+                        lights.push(light);
+                    }
+                    None => log::warn!(
+                        "only triangles, boxes, meshes and uniformly scaled spheres are sampled \
+                        as area lights, other emissive shapes are only found by scattering"
+                    ),
+                }
+            }
+            objects.extend(built);
+        }
+        s_renderer.set_objects(objects);
+        s_renderer.set_lights(lights);
+
+        return s_renderer;
+    }
+
+    /* replaces the scene's objects and rebuilds the hierarchy over them */
+    pub fn set_objects(&mut self, objects: Vec<Object>) {
+        self.m_world = Bvh::new(objects);
+        log::debug!("BVH: {}", self.m_world.stats().summary());
+    }
+
+    /* replaces the lights, the emissive objects refer to theirs by index */
+    pub fn set_lights(&mut self, lights: Vec<Box<dyn Light>>) {
+        let mut total = 0.0;
+        self.m_light_cdf = lights
+            .iter()
+            .map(|light| {
+                if !light.is_delta() {
+                    total += light.power();
+                }
+                total
+            })
+            .collect();
+        self.m_lights = lights;
+    }
+
+    /* full render, m_samples samples per pixel from scratch */
     pub fn render(&mut self) -> RgbaImage {
-        for (x, y, pixel) in self.m_image_data.enumerate_pixels_mut() {
-            *pixel = Self::frag(
-                x as f32 / self.m_width as f32,
-                y as f32 / self.m_height as f32,
-            );
+        self.reset();
+        for _ in 0..self.m_samples {
+            self.accumulate();
+        }
+        return self.image();
+    }
+
+    /* drops the accumulated samples, to be called whenever the scene or the camera changes */
+    pub fn reset(&mut self) {
+        self.m_film.reset();
+    }
+
+    /* adds one sample per pixel to the accumulation buffer, rendering the tiles on a pool of
+    threads each pulling the next tile once done with its own */
+    pub fn accumulate(&mut self) {
+        let frame_start = Instant::now();
+        let tiles = self.m_film.bounds().tiles(self.m_tile_size);
+        let threads = self.thread_count().min(tiles.len()).max(1);
+        let next = AtomicUsize::new(0);
+
+        let s_renderer = &*self;
+        let rendered: Vec<(usize, Vec<Vector3>, Duration)> = thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut done = Vec::new();
+                        /* reseeded for every pixel, see render_pixel */
+                        let mut rng = StdRng::seed_from_u64(0);
+                        loop {
+                            let i = next.fetch_add(1, Ordering::Relaxed);
+                            if i >= tiles.len() {
+                                return done;
+                            }
+                            let tile_start = Instant::now();
+                            let pixels = s_renderer.render_tile(&tiles[i], &mut rng);
+                            done.push((i, pixels, tile_start.elapsed()));
+                        }
+                    })
+                })
+                .collect();
+            return workers
+                .into_iter()
+                .flat_map(|worker| worker.join().unwrap())
+                .collect();
+        });
+
+        /* pixels only depend on their position, so the sum is the same for any thread count */
+        let mut tile_times = vec![Duration::ZERO; tiles.len()];
+        for (i, pixels, time) in rendered {
+            self.m_film.add_tile(&tiles[i], &pixels);
+            tile_times[i] = time;
+        }
+        self.m_film.end_pass();
+        self.m_stats = FrameStats {
+            m_tile_times: tile_times,
+            m_total: frame_start.elapsed(),
+            m_threads: threads,
+        };
+    }
+
+    /* running average of the accumulated samples */
+    pub fn image(&self) -> RgbaImage {
+        return self.m_film.image();
+    }
+
+    pub fn thread_count(&self) -> usize {
+        if self.m_threads > 0 {
+            return self.m_threads;
+        }
+        return thread::available_parallelism().map_or(1, |n| n.get());
+    }
+
+    /* next sample of every pixel of the tile in row-major order, rng being the worker's */
+    fn render_tile(&self, tile: &Bounds2i, rng: &mut StdRng) -> Vec<Vector3> {
+        return tile.points().map(|p| self.render_pixel(&p, rng)).collect();
+    }
+
+    /* the random numbers of a sample only depend on the seed, the pixel and the pass, so the
+    image does not change with the tiling or the thread count */
+    fn render_pixel(&self, pixel: &Point2i, rng: &mut StdRng) -> Vector3 {
+        let pass = self.m_film.m_sample_count as u64;
+        let index = self.m_film.bounds().offset(pixel) as u64;
+        *rng = StdRng::seed_from_u64(Self::splitmix64(
+            self.m_seed ^ Self::splitmix64(pass << 32 | index),
+        ));
+
+        let position = Vector2::new(pixel) + self.sample_offset(self.m_film.m_sample_count);
+        let ray = self.m_camera.get_ray(&self.m_film.uv(&position));
+        return self.trace(&ray, 0, rng);
+    }
+
+    /* returns the radiance carried back along the ray, depth counting the reflections and
+    refractions that led to it */
+    pub fn trace(&self, ray: &Ray, depth: u32, rng: &mut StdRng) -> Vector3 {
+        return match self.m_integrator {
+            Integrator::Whitted => self.whitted(ray, depth, true, rng),
+            Integrator::Path => self.path(ray, depth, rng),
+        };
+    }
+
+    /* nearest hit among all objects */
+    pub fn hit(&self, ray: &Ray, t_min: Real, t_max: Real) -> Option<HitRecord> {
+        return self.m_world.hit(ray, t_min, t_max);
+    }
+
+    /* emission counts unless the lights were already sampled for the glossy reflection that led
+    to the ray */
+    fn whitted(&self, ray: &Ray, depth: u32, emission: bool, rng: &mut StdRng) -> Vector3 {
+        let rec = match self.hit(ray, 0.001, Real::MAX) {
+            Some(rec) => rec,
+            None => return self.background(ray),
+        };
+        let material = &self.m_materials[rec.m_material];
+        let mut radiance = self.direct(ray, &rec, false, rng);
+        if emission {
+            radiance += material.emitted(ray, &rec);
+        }
+        if depth < self.m_max_depth {
+            if let Some(scatter) = material.scatter(ray, &rec, rng) {
+                if scatter.m_lobe != Lobe::Diffuse {
+                    let specular = scatter.m_lobe == Lobe::Specular;
+                    radiance += scatter.m_attenuation
+                        * self.whitted(&scatter.m_ray, depth + 1, specular, rng);
+                }
+            }
+        }
+        return radiance;
+    }
+
+    /* follows one path, adding at every bounce the emission it finds and the light sampled
+    from the lights. Both estimate the light of the area lights, so they are weighted against
+    each other with the power heuristic */
+    fn path(&self, ray: &Ray, depth: u32, rng: &mut StdRng) -> Vector3 {
+        let mut radiance = Vector3::zero();
+        let mut throughput = Vector3::new((1.0, 1.0, 1.0));
+        let mut ray = Ray::new(ray.m_origin, ray.m_direction);
+        /* density the ray was scattered with, None for camera rays and specular scattering
+        which the lights are not sampled for */
+        let mut scatter_pdf: Option<Real> = None;
+        for depth in depth..=self.m_max_depth {
+            let rec = match self.hit(&ray, 0.001, Real::MAX) {
+                Some(rec) => rec,
+                None => {
+                    radiance += throughput * self.background(&ray);
+                    break;
+                }
+            };
+            let material = &self.m_materials[rec.m_material];
+            let emitted = material.emitted(&ray, &rec);
+            if emitted != Vector3::zero() {
+                let weight = match (scatter_pdf, rec.m_light) {
+                    (Some(pdf), Some(light)) => {
+                        power_heuristic(pdf, self.light_pdf(light, &ray, &rec))
+                    }
+                    _ => 1.0,
+                };
+                radiance += throughput * emitted * weight as f32;
+            }
+
+            /* the last bounce has no scattered ray to share the lights with */
+            let last = depth == self.m_max_depth;
+            radiance += throughput * self.direct(&ray, &rec, !last, rng);
+            if last {
+                break;
+            }
+            let scatter = match material.scatter(&ray, &rec, rng) {
+                Some(scatter) => scatter,
+                None => break,
+            };
+            scatter_pdf = match scatter.m_lobe {
+                Lobe::Specular => None,
+                _ => Some(material.pdf(&ray, &rec, &scatter.m_ray.m_direction)),
+            };
+            throughput *= scatter.m_attenuation;
+
+            /* ends dim paths early, the survivors carrying the light of the ended ones */
+            if depth + 1 >= self.m_roulette_depth {
+                let survival = throughput
+                    .get_r()
+                    .max(throughput.get_g())
+                    .max(throughput.get_b())
+                    .min(0.95);
+                if rng.gen::<f32>() >= survival {
+                    break;
+                }
+                throughput /= survival;
+            }
+            ray = scatter.m_ray;
+        }
+        return radiance;
+    }
+
+    /* light arriving straight from the lights: every unoccluded delta light, plus one sample of
+    another light picked by power. With mis the sample is weighted against finding the light by
+    scattering */
+    fn direct(&self, ray: &Ray, rec: &HitRecord, mis: bool, rng: &mut StdRng) -> Vector3 {
+        let material = &self.m_materials[rec.m_material];
+        let mut radiance = Vector3::zero();
+        let origin = rec.m_point + Direction3::new(&rec.m_normal) * 1e-4;
+        for light in self.m_lights.iter().filter(|light| light.is_delta()) {
+            if let Some((brdf, sample, cos_theta)) =
+                self.light_sample(light.as_ref(), ray, rec, &origin, rng)
+            {
+                radiance += brdf * sample.m_radiance * cos_theta as f32;
+            }
+        }
+
+        let total = match self.m_light_cdf.last() {
+            Some(&total) if total > 0.0 => total,
+            _ => return radiance,
+        };
+        let target = rng.gen::<Real>() * total;
+        let index = self
+            .m_light_cdf
+            .partition_point(|&power| power <= target)
+            .min(self.m_lights.len() - 1);
+        let Some((brdf, sample, cos_theta)) =
+            self.light_sample(self.m_lights[index].as_ref(), ray, rec, &origin, rng)
+        else {
+            return radiance;
+        };
+        let pdf = self.selection_pdf(index) * sample.m_pdf;
+        let weight = match mis {
+            true => power_heuristic(pdf, material.pdf(ray, rec, &sample.m_direction)),
+            false => 1.0,
+        };
+        return radiance + brdf * sample.m_radiance * (cos_theta / pdf * weight) as f32;
+    }
+
+    /* a sample of light toward which rec's material reflects and that origin sees, with the
+    material's brdf and the cosine at rec */
+    fn light_sample(
+        &self,
+        light: &dyn Light,
+        ray: &Ray,
+        rec: &HitRecord,
+        origin: &Point3<Real>,
+        rng: &mut StdRng,
+    ) -> Option<(Vector3, LightSample, Real)> {
+        let sample = light.sample(origin, rng)?;
+        let cos_theta = rec.m_normal.dot(&sample.m_direction);
+        if cos_theta <= 0.0 || sample.m_pdf <= 0.0 {
+            return None;
+        }
+        let brdf = self.m_materials[rec.m_material].eval(ray, rec, &sample.m_direction);
+        if brdf == Vector3::zero() || !sample.visible(&self.m_world, origin) {
+            return None;
+        }
+        return Some((brdf, sample, cos_theta));
+    }
+
+    /* chance that direct picks the light at index */
+    fn selection_pdf(&self, index: usize) -> Real {
+        let total = self.m_light_cdf.last().copied().unwrap_or(0.0);
+        let below = if index == 0 {
+            0.0
+        } else {
+            self.m_light_cdf[index - 1]
+        };
+        return (self.m_light_cdf[index] - below) / total;
+    }
+
+    /* solid angle density direct samples the point rec found on the light at index with, from
+    where the ray left */
+    fn light_pdf(&self, index: usize, ray: &Ray, rec: &HitRecord) -> Real {
+        return self.selection_pdf(index) * self.m_lights[index].pdf(&ray.m_origin, rec);
+    }
+
+    fn background(&self, ray: &Ray) -> Vector3 {
+        return match &self.m_background {
+            Background::Solid(color) => *color,
+            Background::Gradient(bottom, top) => {
+                let t = 0.5 * (ray.m_direction.get_y() as f32 + 1.0);
+                *bottom * (1.0 - t) + *top * t
+            }
+        };
+    }
+
+    /* sub-pixel position of the i-th sample, R2 low discrepancy sequence shifted by the seed */
+    fn sample_offset(&self, i: u32) -> Vector2<Real> {
+        let hash = Self::splitmix64(self.m_seed);
+        let shift_x = (hash >> 40) as f64 / (1u64 << 24) as f64;
+        let shift_y = (hash & 0xFF_FFFF) as f64 / (1u64 << 24) as f64;
+        let dx = (shift_x + i as f64 * 0.754_877_666_246_692_7).fract();
+        let dy = (shift_y + i as f64 * 0.569_840_290_998_053_2).fract();
+        return Vector2::new((dx as Real, dy as Real));
+    }
+
+    fn splitmix64(seed: u64) -> u64 {
+        let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        return z ^ (z >> 31);
+    }
+}
+
+/* multiple importance sampling weight of a sample of density pdf against another strategy
+that could have produced it with density other */
+fn power_heuristic(pdf: Real, other: Real) -> Real {
+    if pdf <= 0.0 {
+        return 0.0;
+    }
+    return pdf * pdf / (pdf * pdf + other * other);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thread_count_does_not_change_the_image() {
+        let scene = Scene::load("res/scene/primitives.json").unwrap();
+        let mut s_renderer = Renderer::from_scene(&scene);
+        s_renderer.m_film = Film::new(&Point2i::new((64, 48)));
+
+        s_renderer.m_threads = 1;
+        s_renderer.m_tile_size = 64;
+        let reference = s_renderer.render();
+
+        s_renderer.m_threads = 4;
+        s_renderer.m_tile_size = 7;
+        assert_eq!(reference, s_renderer.render());
+        assert_eq!(s_renderer.m_stats.m_tile_times.len(), 10 * 7);
+    }
+
+    #[test]
+    fn gltf_fixtures() {
+        /* the same scene with external, GLB and data URI buffers and images */
+        let mut images = Vec::new();
+        for path in [
+            "res/gltf/fixture.gltf",
+            "res/gltf/fixture.glb",
+            "res/gltf/fixture_embedded.gltf",
+        ] {
+            let mut scene = Scene::load(path).unwrap();
+            scene.m_image.m_width = 96;
+            scene.m_image.m_height = 64;
+            images.push(Renderer::from_scene(&scene).render());
         }
+        assert!(images.iter().all(|image| *image == images[0]));
+
+        /* the blue front of the cube, the green sign and a dark and a light square of the floor */
+        let pixel = |x: u32, y: u32| images[0].get_pixel(x, y).0;
+        let [r, g, b, _] = pixel(48, 32);
+        assert!(b > r + 60 && b > g + 60);
+        let [r, g, b, _] = pixel(62, 28);
+        assert!(g > r + 60 && g > b + 60);
+        assert!(pixel(10, 60)[0] < 100 && pixel(40, 60)[0] > 200);
+    }
 
-        /* correct uv coordinates */
-        let flipped = imageops::flip_vertical(&self.m_image_data.clone());
-        return flipped;
+    #[test]
+    fn specular_materials() {
+        use crate::dielectric::Dielectric;
+        use crate::metal::Metal;
+
+        /* a sphere in front of the camera against a solid background, without lights */
+        let render = |material: Box<dyn Material>, max_depth: u32| {
+            let mut s_renderer = Renderer::new(9, 9);
+            s_renderer.m_background = Background::Solid(Vector3::new((0.2, 0.4, 0.6)));
+            s_renderer.m_materials = vec![material];
+            s_renderer.m_max_depth = max_depth;
+            s_renderer.set_objects(vec![Object::new(
+                Box::new(Sphere::new(Point3::new((0.0, 0.0, -3.0)), 1.0)),
+                0,
+            )]);
+            let pixels =
+                s_renderer.render_tile(&s_renderer.m_film.bounds(), &mut StdRng::seed_from_u64(0));
+            return pixels[4 * 9 + 4];
+        };
+        let background = Vector3::new((0.2, 0.4, 0.6));
+
+        /* the mirror sends the central ray back to the background, scaled by its albedo */
+        let mirror = render(Box::new(Metal::new(Vector3::new((0.5, 0.5, 0.5)), 0.0)), 8);
+        assert!(mirror.approx_eq(&(background * 0.5), 1e-6));
+        /* the glass lets it through, or reflects it, either way it ends on the background */
+        let glass = render(
+            Box::new(Dielectric::new(1.5, Vector3::new((1.0, 1.0, 1.0)))),
+            8,
+        );
+        assert!(glass.approx_eq(&background, 1e-6));
+        /* and the depth limit stops it */
+        let stopped = render(Box::new(Metal::new(Vector3::new((0.5, 0.5, 0.5)), 0.0)), 0);
+        assert_eq!(stopped, Vector3::zero());
     }
 
-    /* this is basically the fragment shader */
-    pub fn frag(width: f32, height: f32) -> Rgba<u8> {
-        let r: u8 = (width * 255.0) as u8;
-        let g: u8 = (height * 255.0) as u8;
-        return image::Rgba([r, g, 0, 255]);
+    #[test]
+    fn fuzzy_metal_direct_light() {
+        use crate::metal::Metal;
+        use crate::sphere::Sphere;
+
+        /* a point light at the camera is reflected about straight back by the front of a large
+        sphere */
+        let render = |fuzz: Real, integrator: Integrator| {
+            let mut s_renderer = Renderer::new(9, 9);
+            s_renderer.m_integrator = integrator;
+            s_renderer.m_background = Background::Solid(Vector3::zero());
+            s_renderer.m_materials =
+                vec![Box::new(Metal::new(Vector3::new((0.5, 0.5, 0.5)), fuzz))];
+            s_renderer.set_lights(vec![Box::new(PointLight::new(
+                Point3::new((0.0, 0.0, 0.0)),
+                Vector3::new((1.0, 1.0, 1.0)),
+                1.0,
+            ))]);
+            s_renderer.set_objects(vec![Object::new(
+                Box::new(Sphere::new(Point3::new((0.0, 0.0, -11.0)), 10.0)),
+                0,
+            )]);
+            let pixels =
+                s_renderer.render_tile(&s_renderer.m_film.bounds(), &mut StdRng::seed_from_u64(0));
+            return pixels[4 * 9 + 4];
+        };
+
+        /* a perfect mirror cannot be lit by a point, a fuzzy one is */
+        for integrator in [Integrator::Whitted, Integrator::Path] {
+            assert_eq!(render(0.0, integrator), Vector3::zero());
+            assert!(render(0.3, integrator).get_r() > 0.0, "{integrator:?}");
+        }
+    }
+
+    #[test]
+    fn area_light_integrators() {
+        use crate::aabox::AaBox;
+        use crate::microfacet::Microfacet;
+        use crate::normal3::Normal3;
+        use crate::triangle::Triangle;
+
+        /* a floor under a 1x1 light at 0.5 facing down, seen from the side at the origin */
+        let corners = [(-0.5, -0.5), (0.5, -0.5), (0.5, 0.5), (-0.5, 0.5)]
+            .map(|(x, z)| Point3::new((x, 0.5, z)));
+        let radiance = Vector3::new((2.0, 2.0, 2.0));
+        let eye = Point3::new((0.0, 0.3, 0.8));
+        let ray = Ray::new(eye, Point3::origin() - eye);
+        let floors: [Box<dyn Material>; 2] = [
+            Box::new(Lambertian::new(Vector3::new((0.8, 0.8, 0.8)))),
+            Box::new(Microfacet::new(Vector3::new((0.9, 0.9, 0.9)), 1.0, 0.5)),
+        ];
+        for floor in floors {
+            /* midpoint rule over the light's area */
+            let rec = HitRecord::new(&ray, 1.0, Normal3::new((0.0, 1.0, 0.0)), Vector2::zero());
+            let steps = 200;
+            let mut reference = 0.0;
+            for i in 0..steps {
+                for j in 0..steps {
+                    let x = (i as Real + 0.5) / steps as Real - 0.5;
+                    let z = (j as Real + 0.5) / steps as Real - 0.5;
+                    let to_light = Direction3::new((x, 0.5, z));
+                    let d2 = to_light.dot(&to_light);
+                    let cos = 0.5 / d2.sqrt();
+                    let brdf = floor.eval(&ray, &rec, &to_light.unit()).get_r() as Real;
+                    reference += brdf * 2.0 * cos * cos / d2 / (steps * steps) as Real;
+                }
+            }
+
+            let mut s_renderer = Renderer::new(1, 1);
+            s_renderer.m_background = Background::Solid(Vector3::zero());
+            s_renderer.m_materials = vec![floor, Box::new(Emissive::new(radiance))];
+            let triangles = vec![
+                Triangle::new(corners[0], corners[1], corners[2]),
+                Triangle::new(corners[0], corners[2], corners[3]),
+            ];
+            let mut objects = vec![Object::new(
+                Box::new(AaBox::new(
+                    Point3::new((-4.0, -1.0, -4.0)),
+                    Point3::new((4.0, 0.0, 4.0)),
+                )),
+                0,
+            )];
+            for t in &triangles {
+                let mut light = Object::new(Box::new(Triangle::new(t.m_v0, t.m_v1, t.m_v2)), 1);
+                light.m_light = Some(0);
+                objects.push(light);
+            }
+            s_renderer.set_objects(objects);
+            s_renderer.set_lights(vec![Box::new(
+                TriangleLight::new(triangles, radiance).unwrap(),
+            )]);
+
+            for integrator in [Integrator::Whitted, Integrator::Path] {
+                s_renderer.m_integrator = integrator;
+                let mut rng = StdRng::seed_from_u64(9);
+                let count = 20000;
+                let mut mean = 0.0;
+                for _ in 0..count {
+                    mean += s_renderer.trace(&ray, 0, &mut rng).get_r() as Real / count as Real;
+                }
+                let error = (mean - reference).abs() / reference;
+                assert!(error < 0.02, "{integrator:?} {mean} {reference}");
+            }
+        }
     }
 }
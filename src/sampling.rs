@@ -0,0 +1,104 @@
+use rand::Rng;
+
+use crate::direction3::Direction3;
+use crate::normal3::Normal3;
+use crate::scalar::{Float, Real};
+
+/* orthonormal basis around a unit normal, the normal being the local z axis */
+#[derive(Debug, Clone, Copy)]
+pub struct Frame {
+    pub m_s: Direction3<Real>,
+    pub m_t: Direction3<Real>,
+    pub m_n: Direction3<Real>,
+}
+
+/* Associative methods implementation block */
+impl Frame {
+    pub fn to_local(&self, d: &Direction3<Real>) -> Direction3<Real> {
+        return Direction3::new((d.dot(&self.m_s), d.dot(&self.m_t), d.dot(&self.m_n)));
+    }
+
+    pub fn to_world(&self, d: &Direction3<Real>) -> Direction3<Real> {
+        return self.m_s * d.get_x() + self.m_t * d.get_y() + self.m_n * d.get_z();
+    }
+}
+
+/* Associative functions implementation block */
+impl Frame {
+    /* Constructor */
+    pub fn new(n: &Normal3<Real>) -> Frame {
+        let n = Direction3::new(n);
+        let s = n.perpendicular();
+        return Frame {
+            m_s: s,
+            m_t: n.cross(&s),
+            m_n: n,
+        };
+    }
+}
+
+/* uniform over the surface of the unit sphere */
+pub fn unit_sphere<R: Rng + ?Sized>(rng: &mut R) -> Direction3<Real> {
+    let z: Real = rng.gen_range(-1.0..1.0);
+    let phi: Real = rng.gen_range(0.0..2.0 * Real::PI);
+    let r = (1.0 - z * z).max(0.0).sqrt();
+    return Direction3::new((r * phi.cos(), r * phi.sin(), z));
+}
+
+/* uniform inside the unit ball */
+pub fn unit_ball<R: Rng + ?Sized>(rng: &mut R) -> Direction3<Real> {
+    let radius = rng.gen::<Real>().cbrt();
+    return unit_sphere(rng) * radius;
+}
+
+/* unit direction around n with a density of cos / pi, n being unit length. A point of the unit
+sphere moved by n is cosine distributed */
+pub fn cosine_hemisphere<R: Rng + ?Sized>(n: &Normal3<Real>, rng: &mut R) -> Direction3<Real> {
+    let n = Direction3::new(n);
+    let d = n + unit_sphere(rng);
+    let length = d.magnitude();
+    if length < 1e-9 {
+        return n;
+    }
+    return d / length;
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    use super::*;
+
+    #[test]
+    fn distributions() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let n = Normal3::new((0.0, 0.0, 1.0));
+        let count = 20000;
+        let (mut mean_cos, mut mean_z) = (0.0, 0.0);
+        for _ in 0..count {
+            let s = unit_sphere(&mut rng);
+            assert!((s.magnitude() - 1.0).abs() < 1e-9);
+            mean_z += s.get_z() / count as Real;
+            assert!(unit_ball(&mut rng).magnitude() <= 1.0);
+
+            let d = cosine_hemisphere(&n, &mut rng);
+            assert!(d.get_z() >= 0.0 && (d.magnitude() - 1.0).abs() < 1e-9);
+            mean_cos += d.get_z() / count as Real;
+        }
+        /* E[cos] is 2/3 under the cosine density, E[z] is 0 on the sphere */
+        assert!((mean_cos - 2.0 / 3.0).abs() < 0.01);
+        assert!(mean_z.abs() < 0.02);
+    }
+
+    #[test]
+    fn frame() {
+        let n = Normal3::new((1.0, 2.0, -2.0)).unit();
+        let frame = Frame::new(&n);
+        let d = Direction3::new((0.3, -0.4, 0.5));
+        let local = frame.to_local(&d);
+        assert!((local.get_z() - Direction3::new(&n).dot(&d)).abs() < 1e-12);
+        assert!(frame.to_world(&local).approx_eq(&d, 1e-12));
+        assert!(frame.m_s.cross(&frame.m_t).approx_eq(&frame.m_n, 1e-12));
+    }
+}
@@ -0,0 +1,260 @@
+use std::fmt::{Debug, Display};
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use crate::simd::{self, Mat4};
+
+/* precision of rays and intersections, colors and image buffers stay f32 */
+pub type Real = f64;
+
+/* floating point type the math module is generic over, implemented for f32 and f64 */
+pub trait Float:
+    Copy
+    + Debug
+    + Display
+    + Default
+    + PartialEq
+    + PartialOrd
+    + Send
+    + Sync
+    + 'static
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+    + AddAssign
+    + SubAssign
+    + MulAssign
+    + DivAssign
+    + Sum
+{
+    const ZERO: Self;
+    const ONE: Self;
+    const PI: Self;
+    const EPSILON: Self;
+    const MAX: Self;
+    const MIN: Self;
+
+    /* Conversions */
+    fn from_f32(v: f32) -> Self;
+    fn from_f64(v: f64) -> Self;
+    fn to_f32(self) -> f32;
+    fn to_f64(self) -> f64;
+
+    fn sqrt(self) -> Self;
+    fn abs(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn tan(self) -> Self;
+    fn asin(self) -> Self;
+    fn acos(self) -> Self;
+    fn atan2(self, x: Self) -> Self;
+    fn powf(self, n: Self) -> Self;
+    fn min(self, v: Self) -> Self;
+    fn max(self, v: Self) -> Self;
+    fn clamp(self, lo: Self, hi: Self) -> Self;
+    fn is_finite(self) -> bool;
+
+    /* degrees to radians, every angle in the public API is in degrees */
+    fn to_radians(self) -> Self {
+        return self * Self::PI / Self::from_f64(180.0);
+    }
+
+    /* Kernels, overridden with the SIMD versions of simd.rs where the precision has them */
+    fn dot4(a: &[Self; 4], b: &[Self; 4]) -> Self {
+        return simd::scalar::dot4(a, b);
+    }
+
+    fn mat4_mul_vec4(m: &Mat4<Self>, v: &[Self; 4]) -> [Self; 4] {
+        return simd::scalar::mat4_mul_vec4(m, v);
+    }
+
+    fn mat4_mul(a: &Mat4<Self>, b: &Mat4<Self>) -> Mat4<Self> {
+        return simd::scalar::mat4_mul(a, b);
+    }
+}
+
+impl Float for f32 {
+    const ZERO: f32 = 0.0;
+    const ONE: f32 = 1.0;
+    const PI: f32 = std::f32::consts::PI;
+    const EPSILON: f32 = f32::EPSILON;
+    const MAX: f32 = f32::MAX;
+    const MIN: f32 = f32::MIN;
+
+    fn from_f32(v: f32) -> f32 {
+        return v;
+    }
+
+    fn from_f64(v: f64) -> f32 {
+        return v as f32;
+    }
+
+    fn to_f32(self) -> f32 {
+        return self;
+    }
+
+    fn to_f64(self) -> f64 {
+        return self as f64;
+    }
+
+    fn sqrt(self) -> f32 {
+        return f32::sqrt(self);
+    }
+
+    fn abs(self) -> f32 {
+        return f32::abs(self);
+    }
+
+    fn sin(self) -> f32 {
+        return f32::sin(self);
+    }
+
+    fn cos(self) -> f32 {
+        return f32::cos(self);
+    }
+
+    fn tan(self) -> f32 {
+        return f32::tan(self);
+    }
+
+    fn asin(self) -> f32 {
+        return f32::asin(self);
+    }
+
+    fn acos(self) -> f32 {
+        return f32::acos(self);
+    }
+
+    fn atan2(self, x: f32) -> f32 {
+        return f32::atan2(self, x);
+    }
+
+    fn powf(self, n: f32) -> f32 {
+        return f32::powf(self, n);
+    }
+
+    fn min(self, v: f32) -> f32 {
+        return f32::min(self, v);
+    }
+
+    fn max(self, v: f32) -> f32 {
+        return f32::max(self, v);
+    }
+
+    fn clamp(self, lo: f32, hi: f32) -> f32 {
+        return f32::clamp(self, lo, hi);
+    }
+
+    fn is_finite(self) -> bool {
+        return f32::is_finite(self);
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    #[inline]
+    fn dot4(a: &[f32; 4], b: &[f32; 4]) -> f32 {
+        return simd::sse::dot4_f32(a, b);
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    #[inline]
+    fn mat4_mul_vec4(m: &Mat4<f32>, v: &[f32; 4]) -> [f32; 4] {
+        return simd::sse::mat4_mul_vec4_f32(m, v);
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    #[inline]
+    fn mat4_mul(a: &Mat4<f32>, b: &Mat4<f32>) -> Mat4<f32> {
+        return simd::sse::mat4_mul_f32(a, b);
+    }
+}
+
+impl Float for f64 {
+    const ZERO: f64 = 0.0;
+    const ONE: f64 = 1.0;
+    const PI: f64 = std::f64::consts::PI;
+    const EPSILON: f64 = f64::EPSILON;
+    const MAX: f64 = f64::MAX;
+    const MIN: f64 = f64::MIN;
+
+    fn from_f32(v: f32) -> f64 {
+        return v as f64;
+    }
+
+    fn from_f64(v: f64) -> f64 {
+        return v;
+    }
+
+    fn to_f32(self) -> f32 {
+        return self as f32;
+    }
+
+    fn to_f64(self) -> f64 {
+        return self;
+    }
+
+    fn sqrt(self) -> f64 {
+        return f64::sqrt(self);
+    }
+
+    fn abs(self) -> f64 {
+        return f64::abs(self);
+    }
+
+    fn sin(self) -> f64 {
+        return f64::sin(self);
+    }
+
+    fn cos(self) -> f64 {
+        return f64::cos(self);
+    }
+
+    fn tan(self) -> f64 {
+        return f64::tan(self);
+    }
+
+    fn asin(self) -> f64 {
+        return f64::asin(self);
+    }
+
+    fn acos(self) -> f64 {
+        return f64::acos(self);
+    }
+
+    fn atan2(self, x: f64) -> f64 {
+        return f64::atan2(self, x);
+    }
+
+    fn powf(self, n: f64) -> f64 {
+        return f64::powf(self, n);
+    }
+
+    fn min(self, v: f64) -> f64 {
+        return f64::min(self, v);
+    }
+
+    fn max(self, v: f64) -> f64 {
+        return f64::max(self, v);
+    }
+
+    fn clamp(self, lo: f64, hi: f64) -> f64 {
+        return f64::clamp(self, lo, hi);
+    }
+
+    fn is_finite(self) -> bool {
+        return f64::is_finite(self);
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    #[inline]
+    fn dot4(a: &[f64; 4], b: &[f64; 4]) -> f64 {
+        return simd::sse::dot4_f64(a, b);
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    #[inline]
+    fn mat4_mul(a: &Mat4<f64>, b: &Mat4<f64>) -> Mat4<f64> {
+        return simd::sse::mat4_mul_f64(a, b);
+    }
+}
@@ -0,0 +1,1232 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::aabb::Aabb;
+use crate::aabox::AaBox;
+use crate::bvh::Bvh;
+use crate::camera::Camera;
+use crate::dielectric::Dielectric;
+use crate::direction3::Direction3;
+use crate::disk::Disk;
+use crate::emissive::Emissive;
+use crate::error::Error;
+use crate::hittable::{Hittable, Object};
+use crate::instance::Instance;
+use crate::lambertian::Lambertian;
+use crate::loader;
+use crate::material::Material;
+use crate::matrix4::Matrix4;
+use crate::mesh::{
+    Mesh, MeshTriangle, Model, ModelCamera, ModelLightKind, ModelPart, ModelProjection,
+};
+use crate::metal::Metal;
+use crate::microfacet::Microfacet;
+use crate::normal3::Normal3;
+use crate::plane::Plane;
+use crate::point3::Point3;
+use crate::quaternion::Quaternion;
+use crate::scalar::{Float, Real};
+use crate::sphere::Sphere;
+use crate::transform::Transform;
+use crate::triangle::Triangle;
+use crate::vector3::Vector3;
+
+/* top level of a scene description file, see res/scene/ */
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Scene {
+    #[serde(rename = "image")]
+    pub m_image: ImageDesc,
+    #[serde(rename = "camera")]
+    pub m_camera: CameraDesc,
+    #[serde(rename = "background", default)]
+    pub m_background: BackgroundDesc,
+    #[serde(rename = "materials", default)]
+    pub m_materials: Vec<MaterialDesc>,
+    #[serde(rename = "lights", default)]
+    pub m_lights: Vec<LightDesc>,
+    #[serde(rename = "objects", default)]
+    pub m_objects: Vec<ObjectDesc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImageDesc {
+    #[serde(rename = "width")]
+    pub m_width: u32,
+    #[serde(rename = "height")]
+    pub m_height: u32,
+    #[serde(rename = "samples_per_pixel", default = "default_samples")]
+    pub m_samples_per_pixel: u32,
+    /* reflections and refractions followed from each camera ray, or bounces of a path */
+    #[serde(rename = "max_depth", default = "default_max_depth")]
+    pub m_max_depth: u32,
+    #[serde(rename = "integrator", default)]
+    pub m_integrator: IntegratorDesc,
+    /* bounces after which the path tracer starts ending paths by Russian roulette */
+    #[serde(rename = "roulette_depth", default = "default_roulette_depth")]
+    pub m_roulette_depth: u32,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IntegratorDesc {
+    /* direct lighting plus mirror reflections and refractions */
+    #[default]
+    Whitted,
+    /* Monte Carlo path tracing of the full light transport */
+    Path,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CameraDesc {
+    #[serde(rename = "position")]
+    pub m_position: [f32; 3],
+    /* looks down -z when absent */
+    #[serde(rename = "target", default)]
+    pub m_target: Option<[f32; 3]>,
+    #[serde(rename = "up", default = "default_up")]
+    pub m_up: [f32; 3],
+    #[serde(rename = "projection", default)]
+    pub m_projection: ProjectionDesc,
+    /* vertical field of view in degrees, perspective only */
+    #[serde(rename = "fov", default = "default_fov")]
+    pub m_fov: f32,
+    /* vertical extent of the view in world units, orthographic only */
+    #[serde(rename = "height", default = "default_view_height")]
+    pub m_height: f32,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProjectionDesc {
+    #[default]
+    Perspective,
+    Orthographic,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum BackgroundDesc {
+    Solid {
+        #[serde(rename = "color")]
+        m_color: [f32; 3],
+    },
+    /* blends from bottom to top with the vertical direction of the ray */
+    Gradient {
+        #[serde(rename = "bottom")]
+        m_bottom: [f32; 3],
+        #[serde(rename = "top")]
+        m_top: [f32; 3],
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MaterialDesc {
+    /* objects refer to their material by this name */
+    #[serde(rename = "name")]
+    pub m_name: String,
+    #[serde(rename = "type", default)]
+    pub m_type: MaterialTypeDesc,
+    /* albedo of lambertian and metal materials, tint of dielectric ones, base color of
+    microfacet ones and emitted color of emissive ones */
+    #[serde(rename = "color", default = "default_color")]
+    pub m_color: [f32; 3],
+    /* multiplies color by the vertex colors of meshes, lambertian and microfacet only */
+    #[serde(rename = "vertex_colors", default)]
+    pub m_vertex_colors: bool,
+    /* blur of metal reflections, from 0 for a mirror to 1 */
+    #[serde(rename = "fuzz", default)]
+    pub m_fuzz: f32,
+    /* index of refraction of dielectric materials */
+    #[serde(rename = "ior", default = "default_ior")]
+    pub m_ior: f32,
+    /* microfacet materials from 0, a dielectric, to 1, a metal */
+    #[serde(rename = "metallic", default)]
+    pub m_metallic: f32,
+    /* perceptual roughness of microfacet materials, from 0 for a mirror to 1 */
+    #[serde(rename = "roughness", default = "default_roughness")]
+    pub m_roughness: f32,
+    /* radiance of emissive materials in W/(sr m^2), scaling color */
+    #[serde(rename = "intensity", default = "default_intensity")]
+    pub m_intensity: f32,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MaterialTypeDesc {
+    #[default]
+    Lambertian,
+    Metal,
+    Dielectric,
+    Microfacet,
+    Emissive,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum LightDesc {
+    Point {
+        #[serde(rename = "position")]
+        m_position: [f32; 3],
+        #[serde(rename = "color", default = "default_color")]
+        m_color: [f32; 3],
+        /* W/sr, times color */
+        #[serde(rename = "intensity", default = "default_intensity")]
+        m_intensity: f32,
+    },
+    Directional {
+        /* the light travels along it */
+        #[serde(rename = "direction")]
+        m_direction: [f32; 3],
+        #[serde(rename = "color", default = "default_color")]
+        m_color: [f32; 3],
+        /* W/m^2 on a surface facing the light, times color */
+        #[serde(rename = "intensity", default = "default_intensity")]
+        m_intensity: f32,
+    },
+    Spot {
+        #[serde(rename = "position")]
+        m_position: [f32; 3],
+        #[serde(rename = "direction")]
+        m_direction: [f32; 3],
+        #[serde(rename = "color", default = "default_color")]
+        m_color: [f32; 3],
+        /* W/sr on the axis, times color */
+        #[serde(rename = "intensity", default = "default_intensity")]
+        m_intensity: f32,
+        /* half angles of the cone in degrees, the light fades out between them */
+        #[serde(rename = "inner_angle", default)]
+        m_inner_angle: f32,
+        #[serde(rename = "outer_angle", default = "default_outer_angle")]
+        m_outer_angle: f32,
+    },
+    /* parallelogram spanned by u and v from corner, emitting toward u x v */
+    Quad {
+        #[serde(rename = "corner")]
+        m_corner: [f32; 3],
+        #[serde(rename = "u")]
+        m_u: [f32; 3],
+        #[serde(rename = "v")]
+        m_v: [f32; 3],
+        #[serde(rename = "color", default = "default_color")]
+        m_color: [f32; 3],
+        /* W/(sr m^2), times color */
+        #[serde(rename = "intensity", default = "default_intensity")]
+        m_intensity: f32,
+    },
+    Sphere {
+        #[serde(rename = "center")]
+        m_center: [f32; 3],
+        #[serde(rename = "radius")]
+        m_radius: f32,
+        #[serde(rename = "color", default = "default_color")]
+        m_color: [f32; 3],
+        /* W/(sr m^2), times color */
+        #[serde(rename = "intensity", default = "default_intensity")]
+        m_intensity: f32,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ObjectDesc {
+    #[serde(flatten)]
+    pub m_shape: ShapeDesc,
+    #[serde(rename = "material")]
+    pub m_material: String,
+    /* row-major, same layout as the "values" of Matrix4::json */
+    #[serde(rename = "transform", default)]
+    pub m_transform: Option<[f32; 16]>,
+    /* alternative to transform, applied as translation * rotation * scale */
+    #[serde(rename = "translation", default)]
+    pub m_translation: Option<[f32; 3]>,
+    #[serde(rename = "rotation", default)]
+    pub m_rotation: Option<RotationDesc>,
+    #[serde(rename = "scale", default)]
+    pub m_scale: Option<ScaleDesc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RotationDesc {
+    /* degrees around x, y then z, see Quaternion::from_euler */
+    Euler([f32; 3]),
+    /* x, y, z, w */
+    Quaternion([f32; 4]),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ScaleDesc {
+    Uniform(f32),
+    Axes([f32; 3]),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ShapeDesc {
+    Sphere {
+        #[serde(rename = "center")]
+        m_center: [f32; 3],
+        #[serde(rename = "radius")]
+        m_radius: f32,
+    },
+    Plane {
+        #[serde(rename = "point")]
+        m_point: [f32; 3],
+        #[serde(rename = "normal")]
+        m_normal: [f32; 3],
+    },
+    Triangle {
+        #[serde(rename = "v0")]
+        m_v0: [f32; 3],
+        #[serde(rename = "v1")]
+        m_v1: [f32; 3],
+        #[serde(rename = "v2")]
+        m_v2: [f32; 3],
+    },
+    Disk {
+        #[serde(rename = "center")]
+        m_center: [f32; 3],
+        #[serde(rename = "normal")]
+        m_normal: [f32; 3],
+        #[serde(rename = "radius")]
+        m_radius: f32,
+    },
+    Box {
+        #[serde(rename = "min")]
+        m_min: [f32; 3],
+        #[serde(rename = "max")]
+        m_max: [f32; 3],
+    },
+    /* triangles of a model file, relative to the scene file. The cameras and lights of glTF
+    files are only used when the file is loaded as the whole scene */
+    Mesh {
+        #[serde(rename = "path")]
+        m_path: String,
+        /* filled in by Scene::load, objects naming the same file share it */
+        #[serde(skip)]
+        m_model: Option<Arc<Model>>,
+    },
+}
+
+fn default_samples() -> u32 {
+    return 1;
+}
+
+fn default_max_depth() -> u32 {
+    return 8;
+}
+
+fn default_roulette_depth() -> u32 {
+    return 3;
+}
+
+fn default_ior() -> f32 {
+    return 1.5;
+}
+
+fn default_roughness() -> f32 {
+    return 0.5;
+}
+
+fn default_up() -> [f32; 3] {
+    return [0.0, 1.0, 0.0];
+}
+
+fn default_fov() -> f32 {
+    return 60.0;
+}
+
+fn default_view_height() -> f32 {
+    return 2.0;
+}
+
+fn default_color() -> [f32; 3] {
+    return [1.0, 1.0, 1.0];
+}
+
+fn default_intensity() -> f32 {
+    return 1.0;
+}
+
+fn default_outer_angle() -> f32 {
+    return 45.0;
+}
+
+impl Default for BackgroundDesc {
+    fn default() -> BackgroundDesc {
+        return BackgroundDesc::Gradient {
+            m_bottom: [1.0, 1.0, 1.0],
+            m_top: [0.5, 0.7, 1.0],
+        };
+    }
+}
+
+/* Associative functions implementation block */
+impl Scene {
+    /* reads a JSON scene description, or a glTF file as a whole scene, see from_model */
+    pub fn load(path: &str) -> Result<Scene, Error> {
+        log::info!("loading {}", path);
+        let extension = Path::new(path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase());
+        if matches!(extension.as_deref(), Some("gltf") | Some("glb")) {
+            return Scene::from_model(path);
+        }
+
+        let mut scene: Scene =
+            serde_json::from_str(&loader::read_file(path)?).map_err(|e| Error::json(path, e))?;
+
+        /* the camera aspect is width over height, an empty image has none */
+        for (field, size) in [
+            ("width", scene.m_image.m_width),
+            ("height", scene.m_image.m_height),
+        ] {
+            if size == 0 {
+                return Err(Error::NotPositive {
+                    m_path: String::from(path),
+                    m_pointer: format!("/image/{}", field),
+                });
+            }
+        }
+
+        let aspect = scene.m_image.m_width as Real / scene.m_image.m_height as Real;
+        if scene.m_camera.build(aspect).is_none() {
+            return Err(Error::Singular {
+                m_path: String::from(path),
+                m_pointer: String::from("/camera"),
+            });
+        }
+
+        for (i, object) in scene.m_objects.iter().enumerate() {
+            if scene.material_index(&object.m_material).is_none() {
+                return Err(Error::UnknownMaterial {
+                    m_path: String::from(path),
+                    m_pointer: format!("/objects/{}/material", i),
+                    m_name: object.m_material.clone(),
+                });
+            }
+            if object.m_transform.is_some() {
+                let trs = [
+                    ("translation", object.m_translation.is_some()),
+                    ("rotation", object.m_rotation.is_some()),
+                    ("scale", object.m_scale.is_some()),
+                ];
+                if let Some((field, _)) = trs.iter().find(|(_, present)| *present) {
+                    return Err(Error::Conflict {
+                        m_path: String::from(path),
+                        m_pointer: format!("/objects/{}/transform", i),
+                        m_other: format!("/objects/{}/{}", i, field),
+                    });
+                }
+            }
+            if object.transform().inverse().is_none() {
+                return Err(Error::Singular {
+                    m_path: String::from(path),
+                    m_pointer: format!("/objects/{}/transform", i),
+                });
+            }
+        }
+
+        let mut models: HashMap<String, Arc<Model>> = HashMap::new();
+        for object in &mut scene.m_objects {
+            if let ShapeDesc::Mesh { m_path, m_model } = &mut object.m_shape {
+                let file = loader::relative_to(path, m_path);
+                let model = match models.get(&file) {
+                    Some(model) => model.clone(),
+                    None => Arc::new(Model::load(&file)?),
+                };
+                models.insert(file, model.clone());
+                *m_model = Some(model);
+            }
+        }
+
+        return Ok(scene);
+    }
+
+    /* the scene of a glTF file: its first camera, or one framing the model when it has none, its
+    point, spot and directional lights, or a point light at the camera when it has none, and its
+    meshes with a grey default material */
+    fn from_model(path: &str) -> Result<Scene, Error> {
+        let model = Arc::new(Model::load(path)?);
+        let bounds = model.bounds();
+        let camera = match model.m_cameras.first() {
+            Some(camera) => CameraDesc::from_model(camera),
+            None => CameraDesc::framing(&bounds),
+        };
+        let aspect = model
+            .m_cameras
+            .first()
+            .and_then(|c| c.m_aspect)
+            .unwrap_or(4.0 / 3.0);
+        /* the longer side of the image gets 640 pixels */
+        let (width, height) = if aspect >= 1.0 {
+            (640.0, 640.0 / aspect)
+        } else {
+            (640.0 * aspect, 640.0)
+        };
+
+        let mut lights: Vec<LightDesc> = model
+            .m_lights
+            .iter()
+            .map(|light| {
+                let m_position = light.m_position.m_data.map(|c| c as f32);
+                let m_direction = light.m_direction.m_data.map(|c| c as f32);
+                let m_color = [
+                    light.m_color.get_r(),
+                    light.m_color.get_g(),
+                    light.m_color.get_b(),
+                ];
+                let m_intensity = light.m_intensity;
+                return match light.m_kind {
+                    ModelLightKind::Point => LightDesc::Point {
+                        m_position,
+                        m_color,
+                        m_intensity,
+                    },
+                    ModelLightKind::Spot(inner, outer) => LightDesc::Spot {
+                        m_position,
+                        m_direction,
+                        m_color,
+                        m_intensity,
+                        m_inner_angle: inner as f32,
+                        m_outer_angle: outer as f32,
+                    },
+                    ModelLightKind::Directional => LightDesc::Directional {
+                        m_direction,
+                        m_color,
+                        m_intensity,
+                    },
+                };
+            })
+            .collect();
+        if lights.is_empty() {
+            /* the middle of the model, facing it, then reflects its albedo */
+            let distance = if bounds.is_empty() {
+                1.0
+            } else {
+                (bounds.centroid() - point(&camera.m_position)).magnitude()
+            };
+            lights.push(LightDesc::Point {
+                m_position: camera.m_position,
+                m_color: default_color(),
+                m_intensity: (Real::PI * distance * distance) as f32,
+            });
+        }
+
+        let scene = Scene {
+            m_image: ImageDesc {
+                m_width: (width.round() as u32).max(1),
+                m_height: (height.round() as u32).max(1),
+                m_samples_per_pixel: default_samples(),
+                m_max_depth: default_max_depth(),
+                m_integrator: IntegratorDesc::default(),
+                m_roulette_depth: default_roulette_depth(),
+            },
+            m_camera: camera,
+            m_background: BackgroundDesc::default(),
+            m_materials: vec![MaterialDesc {
+                m_name: String::from("default"),
+                m_type: MaterialTypeDesc::Lambertian,
+                m_color: [0.8, 0.8, 0.8],
+                m_vertex_colors: true,
+                m_fuzz: 0.0,
+                m_ior: default_ior(),
+                m_metallic: 0.0,
+                m_roughness: default_roughness(),
+                m_intensity: default_intensity(),
+            }],
+            m_lights: lights,
+            m_objects: vec![ObjectDesc {
+                m_shape: ShapeDesc::Mesh {
+                    m_path: Path::new(path)
+                        .file_name()
+                        .map_or(String::from(path), |f| f.to_string_lossy().into_owned()),
+                    m_model: Some(model),
+                },
+                m_material: String::from("default"),
+                m_transform: None,
+                m_translation: None,
+                m_rotation: None,
+                m_scale: None,
+            }],
+        };
+
+        let aspect = scene.m_image.m_width as Real / scene.m_image.m_height as Real;
+        if scene.m_camera.build(aspect).is_none() {
+            return Err(Error::Malformed {
+                m_path: String::from(path),
+                m_message: String::from("the camera has a degenerate view or projection"),
+            });
+        }
+
+        return Ok(scene);
+    }
+
+    /* index of the material called name */
+    pub fn material_index(&self, name: &str) -> Option<usize> {
+        return self.m_materials.iter().position(|m| m.m_name == name);
+    }
+}
+
+/* Associative methods implementation block */
+impl MaterialDesc {
+    pub fn build(&self) -> Box<dyn Material> {
+        let color = vector(&self.m_color);
+        return match self.m_type {
+            MaterialTypeDesc::Lambertian => Box::new(Lambertian {
+                m_albedo: color,
+                m_vertex_colors: self.m_vertex_colors,
+                m_texture: None,
+            }),
+            MaterialTypeDesc::Metal => Box::new(Metal::new(color, self.m_fuzz as Real)),
+            MaterialTypeDesc::Dielectric => Box::new(Dielectric::new(self.m_ior as Real, color)),
+            MaterialTypeDesc::Microfacet => Box::new(Microfacet {
+                m_base_color: color,
+                m_metallic: self.m_metallic.clamp(0.0, 1.0) as Real,
+                m_roughness: self.m_roughness.clamp(0.0, 1.0) as Real,
+                m_vertex_colors: self.m_vertex_colors,
+                m_texture: None,
+            }),
+            MaterialTypeDesc::Emissive => Box::new(Emissive::new(color * self.m_intensity)),
+        };
+    }
+}
+
+/* Associative functions implementation block */
+impl CameraDesc {
+    /* the description of a model camera, placed where its transform puts the origin */
+    pub fn from_model(camera: &ModelCamera) -> CameraDesc {
+        let position = camera.m_transform.transform_point(&Point3::origin());
+        let forward = camera
+            .m_transform
+            .transform_direction(&Direction3::new((0.0, 0.0, -1.0)));
+        let up = camera
+            .m_transform
+            .transform_direction(&Direction3::new((0.0, 1.0, 0.0)));
+        let (projection, fov, height) = match camera.m_projection {
+            ModelProjection::Perspective(fov) => (
+                ProjectionDesc::Perspective,
+                fov as f32,
+                default_view_height(),
+            ),
+            ModelProjection::Orthographic(height) => {
+                (ProjectionDesc::Orthographic, default_fov(), height as f32)
+            }
+        };
+        return CameraDesc {
+            m_position: position.m_data.map(|c| c as f32),
+            m_target: Some((position + forward).m_data.map(|c| c as f32)),
+            m_up: up.m_data.map(|c| c as f32),
+            m_projection: projection,
+            m_fov: fov,
+            m_height: height,
+        };
+    }
+
+    /* perspective camera on the +z side of bounds that fits them in the default field of view */
+    pub fn framing(bounds: &Aabb) -> CameraDesc {
+        let (center, radius) = if bounds.is_empty() {
+            (Point3::origin(), 1.0)
+        } else {
+            (
+                bounds.centroid(),
+                (bounds.diagonal().magnitude() / 2.0).max(1e-3),
+            )
+        };
+        let distance = radius / (default_fov() as Real / 2.0).to_radians().sin();
+        let position = center + Direction3::new((0.0, 0.0, distance));
+        return CameraDesc {
+            m_position: position.m_data.map(|c| c as f32),
+            m_target: Some(center.m_data.map(|c| c as f32)),
+            m_up: default_up(),
+            m_projection: ProjectionDesc::Perspective,
+            m_fov: default_fov(),
+            m_height: default_view_height(),
+        };
+    }
+}
+
+/* Associative methods implementation block */
+impl CameraDesc {
+    /* None when the camera looks along up or at its own position */
+    pub fn build(&self, aspect: Real) -> Option<Camera> {
+        let eye = point(&self.m_position);
+        let target = match &self.m_target {
+            Some(target) => point(target),
+            None => eye - Direction3::new((0.0, 0.0, 1.0)),
+        };
+        let up = direction(&self.m_up);
+        return match self.m_projection {
+            ProjectionDesc::Perspective => {
+                Camera::perspective(&eye, &target, &up, self.m_fov as Real, aspect)
+            }
+            ProjectionDesc::Orthographic => {
+                Camera::orthographic(&eye, &target, &up, self.m_height as Real, aspect)
+            }
+        };
+    }
+}
+
+impl ObjectDesc {
+    pub fn transform(&self) -> Matrix4<Real> {
+        if let Some(values) = self.m_transform {
+            return Matrix4::<f32>::new(values).cast();
+        }
+
+        let translation = self
+            .m_translation
+            .map_or(Vector3::zero(), |t| vector(&t).cast());
+        let rotation = match &self.m_rotation {
+            Some(RotationDesc::Euler([x, y, z])) => {
+                Quaternion::from_euler(*x as Real, *y as Real, *z as Real)
+            }
+            Some(RotationDesc::Quaternion([x, y, z, w])) => {
+                Quaternion::new((*x as Real, *y as Real, *z as Real, *w as Real)).unit()
+            }
+            None => Quaternion::identity(),
+        };
+        let scale = match &self.m_scale {
+            Some(ScaleDesc::Uniform(s)) => Vector3::new((*s as Real, *s as Real, *s as Real)),
+            Some(ScaleDesc::Axes(axes)) => vector(axes).cast(),
+            None => Vector3::new((1.0, 1.0, 1.0)),
+        };
+        return Transform::trs(translation, rotation, scale).to_matrix4();
+    }
+
+    /* the renderer objects of this description, one per part of a mesh. The materials of the
+    mesh's model go after the ones already in materials */
+    pub fn build(&self, scene: &Scene, materials: &mut Vec<Box<dyn Material>>) -> Vec<Object> {
+        let transform = self.transform();
+        /* Scene::load rejects unknown names, fall back to the first material otherwise */
+        let material = scene.material_index(&self.m_material).unwrap_or(0);
+        let model = match &self.m_shape {
+            ShapeDesc::Mesh {
+                m_model: Some(model),
+                ..
+            } => model,
+            shape => return vec![Object::new(shape.build(&transform), material)],
+        };
+
+        let first = materials.len();
+        materials.extend(model.m_materials.iter().map(|m| m.build()));
+        return model
+            .m_parts
+            .iter()
+            .map(|part| {
+                Object::new(
+                    place(
+                        Box::new(Mesh::bvh(&part.m_mesh)),
+                        &(transform * part.m_transform),
+                    ),
+                    part.m_material.map_or(material, |m| first + m),
+                )
+            })
+            .collect();
+    }
+}
+
+impl ObjectDesc {
+    /* world space triangles of the object, facing the way the shape does, for the area lights
+    of emissive objects. Mesh parts with a material of their own are left out, and None for
+    shapes that are not made of triangles */
+    pub fn triangles(&self) -> Option<Vec<Triangle>> {
+        let transform = self.transform();
+        let mut triangles = match &self.m_shape {
+            ShapeDesc::Triangle { m_v0, m_v1, m_v2 } => {
+                vec![Triangle::new(point(m_v0), point(m_v1), point(m_v2))]
+            }
+            ShapeDesc::Box { m_min, m_max } => AaBox::new(point(m_min), point(m_max)).triangles(),
+            ShapeDesc::Mesh {
+                m_model: Some(model),
+                ..
+            } => model
+                .m_parts
+                .iter()
+                .filter(|part| part.m_material.is_none())
+                .flat_map(|part| {
+                    (0..part.m_mesh.m_triangles.len()).map(|i| {
+                        let t = part.m_mesh.triangle(i);
+                        let [v0, v1, v2] =
+                            [t.m_v0, t.m_v1, t.m_v2].map(|v| part.m_transform.transform_point(&v));
+                        return Triangle::new(v0, v1, v2);
+                    })
+                })
+                .collect(),
+            _ => return None,
+        };
+
+        /* mirroring transforms turn the winding around */
+        let axis =
+            |x: Real, y: Real, z: Real| transform.transform_direction(&Direction3::new((x, y, z)));
+        let mirrored = axis(1.0, 0.0, 0.0)
+            .cross(&axis(0.0, 1.0, 0.0))
+            .dot(&axis(0.0, 0.0, 1.0))
+            < 0.0;
+        for t in &mut triangles {
+            let [v0, v1, v2] = [t.m_v0, t.m_v1, t.m_v2].map(|v| transform.transform_point(&v));
+            *t = match mirrored {
+                true => Triangle::new(v0, v2, v1),
+                false => Triangle::new(v0, v1, v2),
+            };
+        }
+        return Some(triangles);
+    }
+
+    /* world center and radius of a sphere shape, None for other shapes or when the transform
+    does not keep it a sphere */
+    pub fn sphere(&self) -> Option<(Point3<Real>, Real)> {
+        let ShapeDesc::Sphere { m_center, m_radius } = &self.m_shape else {
+            return None;
+        };
+        let transform = self.transform();
+        let axes = [(1.0, 0.0, 0.0), (0.0, 1.0, 0.0), (0.0, 0.0, 1.0)]
+            .map(|axis| transform.transform_direction(&Direction3::new(axis)));
+        let scale = axes[0].magnitude();
+        for (i, axis) in axes.iter().enumerate() {
+            let next = &axes[(i + 1) % 3];
+            if (axis.magnitude() - scale).abs() > 1e-6 * scale || axis.dot(next).abs() > 1e-6 {
+                return None;
+            }
+        }
+        let center = transform.transform_point(&point(m_center));
+        return Some((center, *m_radius as Real * scale));
+    }
+}
+
+impl ShapeDesc {
+    /* places the shape with the transform, Scene::load rejects singular ones */
+    pub fn build(&self, transform: &Matrix4<Real>) -> Box<dyn Hittable> {
+        let shape: Box<dyn Hittable> = match self {
+            ShapeDesc::Sphere { m_center, m_radius } => {
+                Box::new(Sphere::new(point(m_center), *m_radius as Real))
+            }
+            ShapeDesc::Plane { m_point, m_normal } => {
+                Box::new(Plane::new(point(m_point), normal(m_normal)))
+            }
+            ShapeDesc::Triangle { m_v0, m_v1, m_v2 } => {
+                Box::new(Triangle::new(point(m_v0), point(m_v1), point(m_v2)))
+            }
+            ShapeDesc::Disk {
+                m_center,
+                m_normal,
+                m_radius,
+            } => Box::new(Disk::new(
+                point(m_center),
+                normal(m_normal),
+                *m_radius as Real,
+            )),
+            ShapeDesc::Box { m_min, m_max } => Box::new(AaBox::new(point(m_min), point(m_max))),
+            /* every part in one hierarchy, see ObjectDesc::build for one per material. The parts
+            a model moves itself are instanced over hierarchies of their own */
+            ShapeDesc::Mesh { m_model, .. } => {
+                let (moved, unmoved): (Vec<&ModelPart>, Vec<&ModelPart>) = m_model
+                    .iter()
+                    .flat_map(|model| model.m_parts.iter())
+                    .partition(|part| part.m_transform != Matrix4::identity());
+                let triangles = Bvh::new(
+                    unmoved
+                        .iter()
+                        .flat_map(|part| {
+                            (0..part.m_mesh.m_triangles.len())
+                                .map(|i| MeshTriangle::new(part.m_mesh.clone(), i))
+                        })
+                        .collect(),
+                );
+                if moved.is_empty() {
+                    Box::new(triangles)
+                } else {
+                    let mut shapes: Vec<Box<dyn Hittable>> = moved
+                        .iter()
+                        .map(|part| place(Box::new(Mesh::bvh(&part.m_mesh)), &part.m_transform))
+                        .collect();
+                    if !triangles.is_empty() {
+                        shapes.push(Box::new(triangles));
+                    }
+                    Box::new(Bvh::new(shapes))
+                }
+            }
+        };
+        return place(shape, transform);
+    }
+}
+
+/* shape moved by transform, untransformed when it is singular */
+fn place(shape: Box<dyn Hittable>, transform: &Matrix4<Real>) -> Box<dyn Hittable> {
+    if *transform == Matrix4::identity() {
+        return shape;
+    }
+    if transform.inverse().is_none() || transform.normal_matrix().is_none() {
+        log::warn!("singular transform, the object is left untransformed");
+        return shape;
+    }
+    return match Instance::new(shape, *transform) {
+        Some(instance) => Box::new(instance),
+        None => unreachable!("checked above"),
+    };
+}
+
+pub fn vector(values: &[f32; 3]) -> Vector3 {
+    return Vector3::new((values[0], values[1], values[2]));
+}
+
+/* positions, directions and normals are intersected in double precision */
+pub fn point(values: &[f32; 3]) -> Point3<Real> {
+    return Point3::new(&vector(values).cast());
+}
+
+pub fn direction(values: &[f32; 3]) -> Direction3<Real> {
+    return Direction3::new(&vector(values).cast());
+}
+
+pub fn normal(values: &[f32; 3]) -> Normal3<Real> {
+    return Normal3::new(&vector(values).cast());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ray::Ray;
+    use crate::testing::temp_file;
+
+    #[test]
+    fn example_scenes_load() {
+        for path in [
+            "res/scene/primitives.json",
+            "res/scene/transforms.json",
+            "res/scene/shadows.json",
+            "res/scene/mesh.json",
+            "res/scene/materials.json",
+            "res/scene/cornell.json",
+            "res/scene/lights.json",
+        ] {
+            let scene = Scene::load(path).unwrap();
+            for object in &scene.m_objects {
+                object.m_shape.build(&object.transform());
+            }
+        }
+    }
+
+    #[test]
+    fn emissive_triangles() {
+        /* the box mirrored in x still has its triangles facing out of it */
+        let object: ObjectDesc = serde_json::from_str(
+            r#"{"type": "box", "min": [-1, -1, -1], "max": [1, 1, 1], "material": "light",
+                "translation": [5, 0, 0], "scale": [-1, 2, 1]}"#,
+        )
+        .unwrap();
+        let triangles = object.triangles().unwrap();
+        assert_eq!(triangles.len(), 12);
+        for t in &triangles {
+            let normal = (t.m_v1 - t.m_v0).cross(&(t.m_v2 - t.m_v0));
+            let center = Point3::new((5.0, 0.0, 0.0));
+            let outward = (t.m_v0 - center) + (t.m_v1 - center) + (t.m_v2 - center);
+            assert!(normal.dot(&outward) > 0.0);
+        }
+        let sphere: ObjectDesc = serde_json::from_str(
+            r#"{"type": "sphere", "center": [0, 0, 0], "radius": 1, "material": "light"}"#,
+        )
+        .unwrap();
+        assert!(sphere.triangles().is_none());
+        assert_eq!(sphere.sphere(), Some((Point3::origin(), 1.0)));
+        let scaled: ObjectDesc = serde_json::from_str(
+            r#"{"type": "sphere", "center": [1, 0, 0], "radius": 1, "material": "light",
+                "translation": [0, 2, 0], "rotation": [0, 90, 0], "scale": 3}"#,
+        )
+        .unwrap();
+        let (center, radius) = scaled.sphere().unwrap();
+        assert!((center - Point3::new((0.0, 2.0, -3.0))).magnitude() < 1e-9);
+        assert!((radius - 3.0).abs() < 1e-9);
+        let squashed: ObjectDesc = serde_json::from_str(
+            r#"{"type": "sphere", "center": [0, 0, 0], "radius": 1, "material": "light",
+                "scale": [1, 2, 1]}"#,
+        )
+        .unwrap();
+        assert!(squashed.sphere().is_none());
+
+        let scene = Scene::load("res/scene/cornell.json").unwrap();
+        assert_eq!(scene.m_image.m_integrator, IntegratorDesc::Path);
+        let light = &scene.m_materials[scene.material_index("light").unwrap()];
+        assert_eq!(light.m_type, MaterialTypeDesc::Emissive);
+        assert_eq!(light.m_intensity, 15.0);
+    }
+
+    #[test]
+    fn lights() {
+        let lights: Vec<LightDesc> = serde_json::from_str(
+            r#"[{"type": "point", "position": [0, 1, 0]},
+                {"type": "directional", "direction": [0, -1, 0], "intensity": 3},
+                {"type": "spot", "position": [0, 1, 0], "direction": [0, -1, 0], "outer_angle": 30},
+                {"type": "quad", "corner": [0, 1, 0], "u": [1, 0, 0], "v": [0, 0, 1]},
+                {"type": "sphere", "center": [0, 1, 0], "radius": 0.5, "color": [1, 0, 0]}]"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            lights[0],
+            LightDesc::Point {
+                m_intensity: 1.0,
+                ..
+            }
+        ));
+        assert!(matches!(
+            lights[1],
+            LightDesc::Directional {
+                m_intensity: 3.0,
+                ..
+            }
+        ));
+        assert!(matches!(
+            lights[2],
+            LightDesc::Spot {
+                m_inner_angle: 0.0,
+                m_outer_angle: 30.0,
+                ..
+            }
+        ));
+        assert!(matches!(
+            lights[3],
+            LightDesc::Quad {
+                m_color: [1.0, 1.0, 1.0],
+                ..
+            }
+        ));
+        assert!(matches!(lights[4], LightDesc::Sphere { m_radius: 0.5, .. }));
+        assert!(
+            serde_json::from_str::<LightDesc>(r#"{"type": "spot", "position": [0, 1, 0]}"#)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn trs_fields() {
+        let object: ObjectDesc = serde_json::from_str(
+            r#"{"type": "sphere", "center": [0, 0, 0], "radius": 1, "material": "grey",
+                "translation": [1, 2, 3], "rotation": [0, 90, 0], "scale": [2, -1, 1]}"#,
+        )
+        .unwrap();
+        let expected = Matrix4::translate(1.0, 2.0, 3.0)
+            * Matrix4::rotate_y(90.0)
+            * Matrix4::scale(2.0, -1.0, 1.0);
+        assert!(object.transform().approx_eq(&expected, 1e-5));
+
+        /* the matrix form decomposes back into the same fields */
+        let t = object.transform().decompose().unwrap();
+        let raw: ObjectDesc = serde_json::from_str(&format!(
+            r#"{{"type": "sphere", "center": [0, 0, 0], "radius": 1, "material": "grey",
+                "translation": [{}, {}, {}], "rotation": [{}, {}, {}, {}], "scale": [{}, {}, {}]}}"#,
+            t.m_translation.get_x(),
+            t.m_translation.get_y(),
+            t.m_translation.get_z(),
+            t.m_rotation.get_x(),
+            t.m_rotation.get_y(),
+            t.m_rotation.get_z(),
+            t.m_rotation.get_w(),
+            t.m_scale.get_x(),
+            t.m_scale.get_y(),
+            t.m_scale.get_z(),
+        ))
+        .unwrap();
+        assert!(raw.transform().approx_eq(&expected, 1e-5));
+
+        let uniform: ObjectDesc = serde_json::from_str(
+            r#"{"type": "sphere", "center": [0, 0, 0], "radius": 1, "material": "grey", "scale": 3}"#,
+        )
+        .unwrap();
+        assert_eq!(uniform.transform(), Matrix4::scale(3.0, 3.0, 3.0));
+    }
+
+    #[test]
+    fn meshes() {
+        let scene = Scene::load("res/scene/mesh.json").unwrap();
+        let mut materials: Vec<Box<dyn Material>> =
+            scene.m_materials.iter().map(|m| m.build()).collect();
+        /* both cubes share the model, each of them is split by material */
+        let cube = &scene.m_objects[1];
+        let objects = cube.build(&scene, &mut materials);
+        assert_eq!(objects.len(), 2);
+        assert_eq!(objects[0].m_material, 3);
+        assert_eq!(objects[1].m_material, 4);
+        assert_eq!(materials.len(), 5);
+        match (&cube.m_shape, &scene.m_objects[3].m_shape) {
+            (
+                ShapeDesc::Mesh {
+                    m_model: Some(a), ..
+                },
+                ShapeDesc::Mesh {
+                    m_model: Some(b), ..
+                },
+            ) => {
+                assert!(Arc::ptr_eq(a, b))
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        /* the vertex colors of the PLY pyramid reach its material */
+        let pyramid = scene.m_objects[4].build(&scene, &mut materials);
+        let ray = Ray::new(
+            Point3::new((0.1, 2.0, 0.5)),
+            Direction3::new((0.0, -1.0, 0.0)),
+        );
+        let rec = pyramid[0].hit(&ray, 0.001, Real::MAX).unwrap();
+        let color = rec.m_color.unwrap();
+        let up = Direction3::new((0.0, 1.0, 0.0));
+        assert_eq!(
+            materials[rec.m_material].eval(&ray, &rec, &up),
+            Vector3::new((0.9, 0.9, 0.9)) * color / std::f32::consts::PI
+        );
+        assert_ne!(color, Vector3::new((1.0, 1.0, 1.0)));
+    }
+
+    #[test]
+    fn gltf_scene() {
+        let scene = Scene::load("res/gltf/fixture.glb").unwrap();
+        /* the camera node at (0, 2, 6) with its 1.5 aspect ratio */
+        assert_eq!((scene.m_image.m_width, scene.m_image.m_height), (640, 427));
+        assert_eq!(scene.m_camera.m_position, [0.0, 2.0, 6.0]);
+        assert!((scene.m_camera.m_fov - 0.8f32.to_degrees()).abs() < 1e-4);
+        assert!(matches!(
+            scene.m_lights[..],
+            [LightDesc::Point {
+                m_intensity: 100.0,
+                ..
+            }]
+        ));
+
+        /* one object per part, each placed by its node */
+        let mut materials: Vec<Box<dyn Material>> =
+            scene.m_materials.iter().map(|m| m.build()).collect();
+        let objects = scene.m_objects[0].build(&scene, &mut materials);
+        assert_eq!((objects.len(), materials.len()), (3, 4));
+        let ray = Ray::new(
+            Point3::new((0.0, 5.0, 0.0)),
+            Direction3::new((0.0, -1.0, 0.0)),
+        );
+        let rec = objects[0].hit(&ray, 0.001, Real::MAX).unwrap();
+        assert!((rec.m_t - 4.0).abs() < 1e-9);
+        /* the top of the cube, with the cube material after the default and checker ones */
+        assert_eq!(rec.m_material, 2);
+
+        /* without a camera, one on +z frames the model */
+        let framing = CameraDesc::framing(&Aabb::new((
+            &Point3::new((-1.0, -1.0, -1.0)),
+            &Point3::new((1.0, 1.0, 1.0)),
+        )));
+        assert_eq!(framing.m_target, Some([0.0, 0.0, 0.0]));
+        assert!((framing.m_position[2] - 2.0 * 3f32.sqrt()).abs() < 1e-5);
+
+        /* a very tall camera keeps the longer side of the image at 640 pixels */
+        let path = temp_file(
+            "rt_rs_tall.gltf",
+            r#"{"asset": {"version": "2.0"}, "nodes": [{"camera": 0}],
+                "cameras": [{"type": "perspective",
+                             "perspective": {"yfov": 0.8, "aspectRatio": 0.0001, "znear": 0.1}}]}"#,
+        );
+        let scene = Scene::load(&path).unwrap();
+        assert_eq!((scene.m_image.m_width, scene.m_image.m_height), (1, 640));
+    }
+
+    #[test]
+    fn gltf_lights() {
+        let path = temp_file(
+            "rt_rs_lights.gltf",
+            r#"{"asset": {"version": "2.0"},
+                "extensions": {"KHR_lights_punctual": {"lights": [
+                    {"type": "spot", "intensity": 5, "spot": {"outerConeAngle": 0.5}},
+                    {"type": "directional", "intensity": 2}]}},
+                "nodes": [{"extensions": {"KHR_lights_punctual": {"light": 0}}},
+                          {"extensions": {"KHR_lights_punctual": {"light": 1}}}]}"#,
+        );
+        let scene = Scene::load(&path).unwrap();
+        assert!(matches!(
+            scene.m_lights[..],
+            [
+                LightDesc::Spot {
+                    m_direction: [0.0, 0.0, -1.0],
+                    m_intensity: 5.0,
+                    m_inner_angle: 0.0,
+                    ..
+                },
+                LightDesc::Directional {
+                    m_intensity: 2.0,
+                    ..
+                }
+            ]
+        ));
+    }
+
+    #[test]
+    fn errors() {
+        let path = String::from("res/scene/missing.json");
+        assert!(matches!(Scene::load(&path), Err(Error::Io { .. })));
+
+        let path = temp_file("rt_rs_syntax.json", "{\n  \"image\": {,\n}");
+        match Scene::load(&path) {
+            Err(Error::Syntax { m_line, .. }) => assert_eq!(m_line, 2),
+            other => panic!("unexpected {:?}", other),
+        }
+
+        let path = temp_file(
+            "rt_rs_empty.json",
+            r#"{"image": {"width": 4, "height": 0}, "camera": {"position": [0, 0, 0], "fov": 90}}"#,
+        );
+        match Scene::load(&path) {
+            Err(Error::NotPositive { m_pointer, .. }) => assert_eq!(m_pointer, "/image/height"),
+            other => panic!("unexpected {:?}", other),
+        }
+
+        let path = temp_file(
+            "rt_rs_material.json",
+            r#"{"image": {"width": 1, "height": 1}, "camera": {"position": [0, 0, 0], "fov": 90},
+                "objects": [{"type": "sphere", "center": [0, 0, 0], "radius": 1, "material": "nope"}]}"#,
+        );
+        match Scene::load(&path) {
+            Err(Error::UnknownMaterial { m_pointer, .. }) => {
+                assert_eq!(m_pointer, "/objects/0/material")
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        let path = temp_file(
+            "rt_rs_singular.json",
+            r#"{"image": {"width": 1, "height": 1}, "camera": {"position": [0, 0, 0], "fov": 90},
+                "materials": [{"name": "grey", "color": [0.5, 0.5, 0.5]}],
+                "objects": [{"type": "sphere", "center": [0, 0, 0], "radius": 1, "material": "grey",
+                             "transform": [1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1]}]}"#,
+        );
+        match Scene::load(&path) {
+            Err(Error::Singular { m_pointer, .. }) => assert_eq!(m_pointer, "/objects/0/transform"),
+            other => panic!("unexpected {:?}", other),
+        }
+
+        let path = temp_file(
+            "rt_rs_conflict.json",
+            r#"{"image": {"width": 1, "height": 1}, "camera": {"position": [0, 0, 0], "fov": 90},
+                "materials": [{"name": "grey", "color": [0.5, 0.5, 0.5]}],
+                "objects": [{"type": "sphere", "center": [0, 0, 0], "radius": 1, "material": "grey",
+                             "transform": [1, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1], "scale": 2}]}"#,
+        );
+        match Scene::load(&path) {
+            Err(Error::Conflict { m_other, .. }) => assert_eq!(m_other, "/objects/0/scale"),
+            other => panic!("unexpected {:?}", other),
+        }
+
+        let path = temp_file(
+            "rt_rs_unsupported.json",
+            r#"{"image": {"width": 1, "height": 1}, "camera": {"position": [0, 0, 0], "fov": 90},
+                "materials": [{"name": "grey", "color": [0.5, 0.5, 0.5]}],
+                "objects": [{"type": "mesh", "path": "model.fbx", "material": "grey"}]}"#,
+        );
+        assert!(matches!(Scene::load(&path), Err(Error::Unsupported { .. })));
+
+        /* a glTF camera whose node flattens its view */
+        let path = temp_file(
+            "rt_rs_flat.gltf",
+            r#"{"asset": {"version": "2.0"}, "nodes": [{"camera": 0, "scale": [1, 0, 1]}],
+                "cameras": [{"type": "perspective", "perspective": {"yfov": 0.8, "znear": 0.1}}]}"#,
+        );
+        assert!(matches!(Scene::load(&path), Err(Error::Malformed { .. })));
+    }
+}
@@ -0,0 +1,198 @@
+/* 4 wide kernels behind Matrix4 and Vector4. Float picks the implementation for each precision:
+SSE on x86_64, where it is part of the baseline and needs no runtime detection, and the portable
+scalar loops everywhere else. Both add the products in the same order so they give the same bits */
+
+pub type Mat4<T> = [[T; 4]; 4];
+
+/* portable versions, also the reference the SIMD ones are tested and benchmarked against */
+pub mod scalar {
+    use super::Mat4;
+    use crate::scalar::Float;
+
+    pub fn dot4<T: Float>(a: &[T; 4], b: &[T; 4]) -> T {
+        return a[0] * b[0] + a[1] * b[1] + a[2] * b[2] + a[3] * b[3];
+    }
+
+    pub fn mat4_mul_vec4<T: Float>(m: &Mat4<T>, v: &[T; 4]) -> [T; 4] {
+        return m.map(|row| row[0] * v[0] + row[1] * v[1] + row[2] * v[2] + row[3] * v[3]);
+    }
+
+    pub fn mat4_mul<T: Float>(a: &Mat4<T>, b: &Mat4<T>) -> Mat4<T> {
+        let mut m = [[T::ZERO; 4]; 4];
+        for i in 0..4 {
+            for j in 0..4 {
+                m[i][j] =
+                    a[i][0] * b[0][j] + a[i][1] * b[1][j] + a[i][2] * b[2][j] + a[i][3] * b[3][j];
+            }
+        }
+        return m;
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+pub mod sse {
+    use std::arch::x86_64::*;
+
+    use super::Mat4;
+
+    #[inline]
+    pub fn dot4_f32(a: &[f32; 4], b: &[f32; 4]) -> f32 {
+        /* SAFETY: SSE is always available on x86_64 and the loads are unaligned */
+        unsafe {
+            let p = _mm_mul_ps(_mm_loadu_ps(a.as_ptr()), _mm_loadu_ps(b.as_ptr()));
+            let y = _mm_shuffle_ps::<0b01_01_01_01>(p, p);
+            let z = _mm_movehl_ps(p, p);
+            let w = _mm_shuffle_ps::<0b11_11_11_11>(p, p);
+            return _mm_cvtss_f32(_mm_add_ss(_mm_add_ss(_mm_add_ss(p, y), z), w));
+        }
+    }
+
+    /* sum of the columns of m scaled by the components of v */
+    #[inline]
+    pub fn mat4_mul_vec4_f32(m: &Mat4<f32>, v: &[f32; 4]) -> [f32; 4] {
+        let mut r = [0.0; 4];
+        /* SAFETY: as above, r is written with an unaligned store */
+        unsafe {
+            let r0 = _mm_loadu_ps(m[0].as_ptr());
+            let r1 = _mm_loadu_ps(m[1].as_ptr());
+            let r2 = _mm_loadu_ps(m[2].as_ptr());
+            let r3 = _mm_loadu_ps(m[3].as_ptr());
+
+            /* transpose the rows into columns */
+            let t0 = _mm_unpacklo_ps(r0, r1);
+            let t1 = _mm_unpacklo_ps(r2, r3);
+            let t2 = _mm_unpackhi_ps(r0, r1);
+            let t3 = _mm_unpackhi_ps(r2, r3);
+            let c0 = _mm_movelh_ps(t0, t1);
+            let c1 = _mm_movehl_ps(t1, t0);
+            let c2 = _mm_movelh_ps(t2, t3);
+            let c3 = _mm_movehl_ps(t3, t2);
+
+            let mut acc = _mm_mul_ps(c0, _mm_set1_ps(v[0]));
+            acc = _mm_add_ps(acc, _mm_mul_ps(c1, _mm_set1_ps(v[1])));
+            acc = _mm_add_ps(acc, _mm_mul_ps(c2, _mm_set1_ps(v[2])));
+            acc = _mm_add_ps(acc, _mm_mul_ps(c3, _mm_set1_ps(v[3])));
+            _mm_storeu_ps(r.as_mut_ptr(), acc);
+        }
+        return r;
+    }
+
+    /* each row of the product is the rows of b scaled by the entries of a's row */
+    #[inline]
+    pub fn mat4_mul_f32(a: &Mat4<f32>, b: &Mat4<f32>) -> Mat4<f32> {
+        let mut m = [[0.0; 4]; 4];
+        /* SAFETY: as above */
+        unsafe {
+            let b0 = _mm_loadu_ps(b[0].as_ptr());
+            let b1 = _mm_loadu_ps(b[1].as_ptr());
+            let b2 = _mm_loadu_ps(b[2].as_ptr());
+            let b3 = _mm_loadu_ps(b[3].as_ptr());
+            for i in 0..4 {
+                let mut acc = _mm_mul_ps(_mm_set1_ps(a[i][0]), b0);
+                acc = _mm_add_ps(acc, _mm_mul_ps(_mm_set1_ps(a[i][1]), b1));
+                acc = _mm_add_ps(acc, _mm_mul_ps(_mm_set1_ps(a[i][2]), b2));
+                acc = _mm_add_ps(acc, _mm_mul_ps(_mm_set1_ps(a[i][3]), b3));
+                _mm_storeu_ps(m[i].as_mut_ptr(), acc);
+            }
+        }
+        return m;
+    }
+
+    /* the f64 kernels work on two lanes, SSE2 being the x86_64 baseline as well. There is none for
+    the matrix vector product, LLVM vectorizes the portable loop better than a 2x2 block transpose */
+    #[inline]
+    pub fn dot4_f64(a: &[f64; 4], b: &[f64; 4]) -> f64 {
+        /* SAFETY: SSE2 is always available on x86_64 and the loads are unaligned */
+        unsafe {
+            let p = _mm_mul_pd(_mm_loadu_pd(a.as_ptr()), _mm_loadu_pd(b.as_ptr()));
+            let xy = _mm_add_sd(p, _mm_unpackhi_pd(p, p));
+            return _mm_cvtsd_f64(xy) + a[2] * b[2] + a[3] * b[3];
+        }
+    }
+
+    #[inline]
+    pub fn mat4_mul_f64(a: &Mat4<f64>, b: &Mat4<f64>) -> Mat4<f64> {
+        let mut m = [[0.0; 4]; 4];
+        /* SAFETY: as above */
+        unsafe {
+            let b_lo = b.map(|row| _mm_loadu_pd(row.as_ptr()));
+            let b_hi = b.map(|row| _mm_loadu_pd(row.as_ptr().add(2)));
+            for i in 0..4 {
+                let s = _mm_set1_pd(a[i][0]);
+                let mut lo = _mm_mul_pd(s, b_lo[0]);
+                let mut hi = _mm_mul_pd(s, b_hi[0]);
+                for k in 1..4 {
+                    let s = _mm_set1_pd(a[i][k]);
+                    lo = _mm_add_pd(lo, _mm_mul_pd(s, b_lo[k]));
+                    hi = _mm_add_pd(hi, _mm_mul_pd(s, b_hi[k]));
+                }
+                _mm_storeu_pd(m[i].as_mut_ptr(), lo);
+                _mm_storeu_pd(m[i].as_mut_ptr().add(2), hi);
+            }
+        }
+        return m;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use super::*;
+    use crate::scalar::Float;
+
+    fn random<T: Float>(rng: &mut StdRng) -> (Mat4<T>, [T; 4]) {
+        let mut value = || T::from_f64(rng.gen_range(-100.0..100.0));
+        let m = [[(); 4]; 4].map(|row| row.map(|_| value()));
+        let v = [(); 4].map(|_| value());
+        return (m, v);
+    }
+
+    /* the dispatched kernels against the portable ones, bit for bit */
+    fn matches_scalar<T: Float>() {
+        let mut rng = StdRng::seed_from_u64(15);
+        for _ in 0..100 {
+            let (a, v) = random::<T>(&mut rng);
+            let (b, w) = random::<T>(&mut rng);
+            assert_eq!(T::mat4_mul(&a, &b), scalar::mat4_mul(&a, &b));
+            assert_eq!(T::mat4_mul_vec4(&a, &v), scalar::mat4_mul_vec4(&a, &v));
+            assert_eq!(T::dot4(&v, &w), scalar::dot4(&v, &w));
+        }
+    }
+
+    #[test]
+    fn single_precision() {
+        matches_scalar::<f32>();
+    }
+
+    #[test]
+    fn double_precision() {
+        matches_scalar::<f64>();
+    }
+
+    #[test]
+    fn scalar_kernels() {
+        let m = [
+            [1.0, 2.0, 3.0, 4.0],
+            [5.0, 6.0, 7.0, 8.0],
+            [9.0, 10.0, 11.0, 12.0],
+            [13.0, 14.0, 15.0, 16.0],
+        ];
+        let identity = [
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ];
+        assert_eq!(scalar::mat4_mul(&m, &identity), m);
+        assert_eq!(
+            scalar::mat4_mul_vec4(&m, &[1.0, 0.0, 0.0, 1.0]),
+            [5.0, 13.0, 21.0, 29.0]
+        );
+        assert_eq!(
+            scalar::dot4(&[1.0, 2.0, 3.0, 4.0], &[1.0, 2.0, 3.0, 4.0]),
+            30.0
+        );
+    }
+}
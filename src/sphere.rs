@@ -0,0 +1,211 @@
+use crate::aabb::Aabb;
+use crate::direction3::Direction3;
+use crate::hittable::{Hittable, HitRecord};
+use crate::normal3::Normal3;
+use crate::packet::{PacketHittable, RayPacket};
+use crate::point3::Point3;
+use crate::ray::Ray;
+use crate::scalar::{Float, Real};
+use crate::vector2::Vector2;
+
+#[derive(Debug)]
+pub struct Sphere {
+    pub m_center: Point3<Real>,
+    pub m_radius: Real,
+}
+
+/* Associative functions implementation block */
+impl Sphere {
+    /* Constructor */
+    pub fn new(center: Point3<Real>, radius: Real) -> Sphere {
+        return Sphere {
+            m_center: center,
+            m_radius: radius,
+        };
+    }
+}
+
+impl Hittable for Sphere {
+    fn hit(&self, ray: &Ray, t_min: Real, t_max: Real) -> Option<HitRecord> {
+        let oc = ray.m_origin - self.m_center;
+        let a = ray.m_direction.dot(&ray.m_direction);
+        let half_b = oc.dot(&ray.m_direction);
+        let c = oc.dot(&oc) - self.m_radius * self.m_radius;
+
+        let discriminant = half_b * half_b - a * c;
+        if discriminant < 0.0 {
+            return None;
+        }
+
+        /* prefer the nearest root that lies within the accepted range */
+        let sqrtd = discriminant.sqrt();
+        let mut t = (-half_b - sqrtd) / a;
+        if t < t_min || t > t_max {
+            t = (-half_b + sqrtd) / a;
+            if t < t_min || t > t_max {
+                return None;
+            }
+        }
+
+        return Some(self.record(ray, t));
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        let r = Direction3::new((self.m_radius, self.m_radius, self.m_radius));
+        return Some(Aabb::new((&(self.m_center - r), &(self.m_center + r))));
+    }
+}
+
+impl PacketHittable for Sphere {
+    fn hit_packet<const N: usize>(
+        &self,
+        packet: &RayPacket<N>,
+        t_min: Real,
+        t_max: &[Real; N],
+    ) -> [Option<HitRecord>; N] {
+        let t = self.distances(packet, t_min, t_max);
+        return std::array::from_fn(|i| {
+            if t[i].is_nan() {
+                return None;
+            }
+            return Some(self.record(&packet.ray(i), t[i]));
+        });
+    }
+
+    fn occluded_packet<const N: usize>(
+        &self,
+        packet: &RayPacket<N>,
+        t_min: Real,
+        t_max: &[Real; N],
+    ) -> [bool; N] {
+        return self.distances(packet, t_min, t_max).map(|t| !t.is_nan());
+    }
+}
+
+/* Associative methods implementation block */
+impl Sphere {
+    /* same arithmetic as hit over every lane, NaN marks the lanes that miss */
+    fn distances<const N: usize>(
+        &self,
+        packet: &RayPacket<N>,
+        t_min: Real,
+        t_max: &[Real; N],
+    ) -> [Real; N] {
+        let [ox, oy, oz] = &packet.m_origin;
+        let [dx, dy, dz] = &packet.m_direction;
+        let [cx, cy, cz] = self.m_center.m_data;
+
+        let mut t = [Real::NAN; N];
+        for i in 0..N {
+            let (ocx, ocy, ocz) = (ox[i] - cx, oy[i] - cy, oz[i] - cz);
+            let a = dx[i] * dx[i] + dy[i] * dy[i] + dz[i] * dz[i];
+            let half_b = ocx * dx[i] + ocy * dy[i] + ocz * dz[i];
+            let c = (ocx * ocx + ocy * ocy + ocz * ocz) - self.m_radius * self.m_radius;
+
+            /* the roots are NaN when the discriminant is negative and fail both range checks */
+            let sqrtd = (half_b * half_b - a * c).sqrt();
+            let near = (-half_b - sqrtd) / a;
+            let far = (-half_b + sqrtd) / a;
+            t[i] = if near >= t_min && near <= t_max[i] {
+                near
+            } else if far >= t_min && far <= t_max[i] {
+                far
+            } else {
+                Real::NAN
+            };
+        }
+
+        return t;
+    }
+
+    fn record(&self, ray: &Ray, t: Real) -> HitRecord {
+        let outward_normal = Normal3::new(&((ray.at(t) - self.m_center) / self.m_radius));
+
+        /* spherical coordinates, u wraps around y and v goes from bottom to top */
+        let theta = (-outward_normal.get_y()).clamp(-1.0, 1.0).acos();
+        let phi = (-outward_normal.get_z()).atan2(outward_normal.get_x()) + Real::PI;
+
+        return HitRecord::new(
+            ray,
+            t,
+            outward_normal,
+            Vector2::new((phi / (2.0 * Real::PI), theta / Real::PI)),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hit() {
+        let sphere = Sphere::new(Point3::new((0.0, 0.0, -5.0)), 1.0);
+        let ray = Ray::new(Point3::origin(), Direction3::new((0.0, 0.0, -1.0)));
+        let rec = sphere.hit(&ray, 0.001, Real::MAX).unwrap();
+        assert!((rec.m_t - 4.0).abs() < 1e-5);
+        assert!(rec.m_front_face);
+        assert!((rec.m_normal.get_z() - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn hit_from_inside() {
+        let sphere = Sphere::new(Point3::origin(), 2.0);
+        let ray = Ray::new(Point3::origin(), Direction3::new((1.0, 0.0, 0.0)));
+        let rec = sphere.hit(&ray, 0.001, Real::MAX).unwrap();
+        assert!((rec.m_t - 2.0).abs() < 1e-5);
+        assert!(!rec.m_front_face);
+        assert!((rec.m_normal.get_x() + 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn miss() {
+        let sphere = Sphere::new(Point3::new((0.0, 0.0, -5.0)), 1.0);
+        let ray = Ray::new(Point3::origin(), Direction3::new((0.0, 1.0, 0.0)));
+        assert!(sphere.hit(&ray, 0.001, Real::MAX).is_none());
+
+        /* behind the origin of the ray */
+        let ray = Ray::new(Point3::origin(), Direction3::new((0.0, 0.0, 1.0)));
+        assert!(sphere.hit(&ray, 0.001, Real::MAX).is_none());
+
+        /* out of the accepted range */
+        let ray = Ray::new(Point3::origin(), Direction3::new((0.0, 0.0, -1.0)));
+        assert!(sphere.hit(&ray, 0.001, 3.0).is_none());
+    }
+
+    #[test]
+    fn grazing() {
+        let sphere = Sphere::new(Point3::origin(), 1.0);
+
+        /* tangent to the top of the sphere */
+        let ray = Ray::new(
+            Point3::new((-5.0, 1.0, 0.0)),
+            Direction3::new((1.0, 0.0, 0.0)),
+        );
+        let rec = sphere.hit(&ray, 0.001, Real::MAX).unwrap();
+        assert!((rec.m_t - 5.0).abs() < 1e-5);
+        assert!((rec.m_normal.get_y() - 1.0).abs() < 1e-5);
+
+        /* just above it */
+        let ray = Ray::new(
+            Point3::new((-5.0, 1.001, 0.0)),
+            Direction3::new((1.0, 0.0, 0.0)),
+        );
+        assert!(sphere.hit(&ray, 0.001, Real::MAX).is_none());
+    }
+
+    #[test]
+    fn far_from_origin() {
+        /* spacing between f32 values is 0.125 out here, more than the tolerance below */
+        let center = Point3::new((1.0e6, -2.0e6, 1.5e6));
+        let sphere = Sphere::new(center, 0.01);
+        let ray = Ray::new(
+            center + Direction3::new((0.0, 0.0, 1.0)),
+            Direction3::new((0.0, 0.0, -1.0)),
+        );
+        let rec = sphere.hit(&ray, 0.001, Real::MAX).unwrap();
+        assert!((rec.m_t - 0.99).abs() < 1e-6);
+        assert!((rec.m_normal.get_z() - 1.0).abs() < 1e-6);
+        assert!(rec.m_point.distance(&center) - 0.01 < 1e-6);
+    }
+}
@@ -0,0 +1,195 @@
+use crate::error::Error;
+use crate::loader;
+use crate::mesh::{Mesh, Model, ModelPart};
+use crate::point3::Point3;
+use crate::scalar::Real;
+
+/* reads an ascii or binary STL file. Its triangles share no vertices and keep their flat normals,
+the facet normals of the file are ignored as the winding gives them */
+pub fn load(path: &str) -> Result<Model, Error> {
+    return parse(&loader::read_bytes(path)?, path);
+}
+
+/* bytes is the content of the file at path, which names it in errors. Binary files are told
+apart by their size, as their 80 byte header may also start with "solid" */
+pub fn parse(bytes: &[u8], path: &str) -> Result<Model, Error> {
+    let count = bytes
+        .get(80..84)
+        .map(|c| u32::from_le_bytes([c[0], c[1], c[2], c[3]]) as usize);
+    let positions = match count {
+        Some(count) if bytes.len() == 84 + 50 * count => binary(bytes, count),
+        _ if bytes.starts_with(b"solid") => {
+            let text = std::str::from_utf8(bytes).map_err(|e| Error::Malformed {
+                m_path: String::from(path),
+                m_message: e.to_string(),
+            })?;
+            ascii(text, path)?
+        }
+        Some(count) => {
+            return Err(Error::Malformed {
+                m_path: String::from(path),
+                m_message: format!(
+                    "a binary STL of {} triangles has {} bytes, found {}",
+                    count,
+                    84 + 50 * count,
+                    bytes.len()
+                ),
+            });
+        }
+        None => {
+            return Err(Error::Malformed {
+                m_path: String::from(path),
+                m_message: String::from("too short for an STL file"),
+            });
+        }
+    };
+
+    let triangles = (0..positions.len() as u32 / 3)
+        .map(|i| [3 * i, 3 * i + 1, 3 * i + 2])
+        .collect();
+    let mesh = Mesh::new(positions, Vec::new(), Vec::new(), triangles);
+    let mut model = Model::default();
+    if mesh.m_triangles.is_empty() {
+        log::warn!("{}: no facets", path);
+    } else {
+        model.m_parts.push(ModelPart::new(mesh, None));
+    }
+    return Ok(model);
+}
+
+/* 50 bytes per triangle, a normal, three vertices and an attribute word */
+fn binary(bytes: &[u8], count: usize) -> Vec<Point3<Real>> {
+    let float = |offset: usize| {
+        let b = &bytes[offset..offset + 4];
+        return f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as Real;
+    };
+    let mut positions = Vec::with_capacity(3 * count);
+    for i in 0..count {
+        let facet = 84 + 50 * i;
+        for v in 0..3 {
+            let vertex = facet + 12 + 12 * v;
+            positions.push(Point3::new((
+                float(vertex),
+                float(vertex + 4),
+                float(vertex + 8),
+            )));
+        }
+    }
+    return positions;
+}
+
+/* solid, then facets of an outer loop of three vertices */
+fn ascii(source: &str, path: &str) -> Result<Vec<Point3<Real>>, Error> {
+    let mut positions = Vec::new();
+    /* vertices of the current facet, None outside of facets */
+    let mut facet: Option<Vec<Point3<Real>>> = None;
+    for (i, line) in source.lines().enumerate() {
+        let number = i + 1;
+        let error = |message: String| Error::parse(path, number, message);
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        match tokens.as_slice() {
+            [] | ["solid", ..] | ["endsolid", ..] | ["outer", "loop"] | ["endloop"] => {}
+            ["facet", "normal", ..] => {
+                if facet.is_some() {
+                    return Err(error(String::from("facet inside a facet")));
+                }
+                facet = Some(Vec::with_capacity(3));
+            }
+            ["vertex", rest @ ..] => {
+                let vertices = facet
+                    .as_mut()
+                    .ok_or_else(|| error(String::from("vertex outside of a facet")))?;
+                if rest.len() != 3 {
+                    return Err(error(format!(
+                        "vertex expects 3 numbers, found {}",
+                        rest.len()
+                    )));
+                }
+                let mut v = [0.0; 3];
+                for (value, token) in v.iter_mut().zip(rest) {
+                    *value = token
+                        .parse::<Real>()
+                        .map_err(|_| error(format!("\"{}\" is not a number", token)))?;
+                }
+                vertices.push(Point3::new((v[0], v[1], v[2])));
+            }
+            ["endfacet"] => {
+                let vertices = facet
+                    .take()
+                    .ok_or_else(|| error(String::from("endfacet outside of a facet")))?;
+                if vertices.len() != 3 {
+                    return Err(error(format!(
+                        "facet with {} vertices, 3 expected",
+                        vertices.len()
+                    )));
+                }
+                positions.extend(vertices);
+            }
+            _ => return Err(error(format!("unexpected \"{}\"", line.trim()))),
+        }
+    }
+    if facet.is_some() {
+        return Err(Error::parse(
+            path,
+            source.lines().count(),
+            String::from("missing endfacet"),
+        ));
+    }
+    return Ok(positions);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::parse_error;
+
+    #[test]
+    fn fixtures() {
+        let ascii = load("res/mesh/tetrahedron_ascii.stl").unwrap();
+        let binary = load("res/mesh/tetrahedron_binary.stl").unwrap();
+        let (a, b) = (&ascii.m_parts[0].m_mesh, &binary.m_parts[0].m_mesh);
+        assert_eq!(a.m_triangles.len(), 4);
+        assert_eq!(a.m_positions, b.m_positions);
+        assert_eq!(a.m_triangles, b.m_triangles);
+        assert!(a.is_valid() && a.m_normals.is_empty());
+
+        /* counter-clockwise facets, every normal points away from the center */
+        let center = Point3::new((0.25, 0.25, 0.25));
+        for t in 0..4 {
+            let triangle = a.triangle(t);
+            let normal = (triangle.m_v1 - triangle.m_v0).cross(&(triangle.m_v2 - triangle.m_v0));
+            assert!(normal.dot(&(triangle.m_v0 - center)) > 0.0);
+        }
+    }
+
+    #[test]
+    fn errors() {
+        let line = |source: &str| parse_error(parse(source.as_bytes(), "bad.stl"));
+        let facet = "facet normal 0 0 1\nouter loop\nvertex 0 0 0\nvertex 1 0 0\n";
+        assert_eq!(
+            line(&format!("solid a\n{}endloop\nendfacet\n", facet)),
+            (7, String::from("facet with 2 vertices, 3 expected"))
+        );
+        assert_eq!(
+            line(&format!("solid a\n{}vertex 0 1\n", facet)).1,
+            "vertex expects 3 numbers, found 2"
+        );
+        assert_eq!(line("solid a\nvertex 0 0 0\n").0, 2);
+        assert_eq!(
+            line(&format!("solid a\n{}vertex 0 1 0\n", facet)).1,
+            "missing endfacet"
+        );
+
+        /* a binary header and count, but a triangle short */
+        let mut bytes = vec![0u8; 84 + 50];
+        bytes[80] = 2;
+        assert!(matches!(
+            parse(&bytes, "short.stl"),
+            Err(Error::Malformed { .. })
+        ));
+        assert!(matches!(
+            parse(b"sol", "tiny.stl"),
+            Err(Error::Malformed { .. })
+        ));
+    }
+}
@@ -3,26 +3,26 @@
 
 use image::{ImageBuffer, RgbaImage};
 
-use crate::matrix3::*;
-use crate::matrix4::*;
-use crate::vector3::*;
-use crate::vector4::*;
+use rt_rs::matrix3::*;
+use rt_rs::matrix4::*;
+use rt_rs::vector3::*;
+use rt_rs::vector4::*;
 
 pub fn test_vector() {
     println!("Vector3 and Vector4 tests: \n");
 
     /* Test Constructors */
     println!("Building Vectors... \n");
-    let vec3_empty = Vector3::new(()); // empty tuple creates a Vec3(0, 0, 0)
-    let _vec3_zero = Vector3::new(0); // any integer also creates an empty Vec3(0, 0, 0)
-    let _vec3_zero = Vector3::zero(); // zero() also creates an empty Vec3(0, 0, 0)
+    let vec3_empty: Vector3 = Vector3::new(()); // empty tuple creates a Vec3(0, 0, 0)
+    let _vec3_zero: Vector3 = Vector3::new(0); // any integer also creates an empty Vec3(0, 0, 0)
+    let _vec3_zero: Vector3 = Vector3::zero(); // zero() also creates an empty Vec3(0, 0, 0)
     let vec3_3f32 = Vector3::new((3.0, 3.0, 3.0)); // a 3-tuple creates a Vec3(f32, f32, f32)
     let _vec3_vec3 = Vector3::new(&vec3_empty); // a Vec3 can create a Vec3
-    let _vec3_vec4 = Vector3::new(&Vector4::new(())); // a Vec4 can also create a Vec3 but 4th component is discarded
+    let _vec3_vec4: Vector3 = Vector3::new(&Vector4::<f32>::new(())); // a Vec4 can also create a Vec3 but 4th component is discarded
 
-    let vec4_empty = Vector4::new(()); // empty tuple creates a Vec4(0, 0, 0, 1)
-    let _vec4_zero = Vector4::new(0); // any integer creates an empty Vec4(0, 0, 0, 0)
-    let _vec4_zero = Vector4::zero(); // zero() also creates an empty Vec4(0, 0, 0, 0)
+    let vec4_empty: Vector4 = Vector4::new(()); // empty tuple creates a Vec4(0, 0, 0, 1)
+    let _vec4_zero: Vector4 = Vector4::new(0); // any integer creates an empty Vec4(0, 0, 0, 0)
+    let _vec4_zero: Vector4 = Vector4::zero(); // zero() also creates an empty Vec4(0, 0, 0, 0)
     let mut vec4_3f32 = Vector4::new((3.0, 3.0, 3.0)); // a 3-tuple creates a Vec4(f32, f32, f32, 1)
     let vec4_4f32 = Vector4::new((4.0, 4.0, 4.0, 4.0)); // a 4-tuple creates a Vec4(f32, f32, f32, f32)
     let _vec4_vec3 = Vector4::new(&vec3_3f32); // a Vec3 can create a Vec4(Vec3, 1)
@@ -79,18 +79,18 @@ pub fn test_matrix() {
 
     /* Text Constructors */
     println!("Building Matrices... \n");
-    let _mat3_identity = Matrix3::new(()); // an empty tuple creates an identity Matrix3
-    let mat3_identity = Matrix3::identity(); // identity() also creates an identity Matrix3
-    let _mat3_zero = Matrix3::new(0); // any integer creates a zero Matrix3
-    let _mat3_zero = Matrix3::zero(); // zero() also creates a zero Matrix3
+    let _mat3_identity: Matrix3 = Matrix3::new(()); // an empty tuple creates an identity Matrix3
+    let mat3_identity: Matrix3 = Matrix3::identity(); // identity() also creates an identity Matrix3
+    let _mat3_zero: Matrix3 = Matrix3::new(0); // any integer creates a zero Matrix3
+    let _mat3_zero: Matrix3 = Matrix3::zero(); // zero() also creates a zero Matrix3
     let _mat3_mat3 = Matrix3::new(&mat3_identity); // this creates a Matrix3 from the provided Matrix3
     let _mat3_9f32 = Matrix3::new((1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0)); // 9 f32's create a Matrix3
     let _mat3_3vec3 = Matrix3::new((&vec3_empty, &vec3_zero, &vec3_3f32)); // 3 Vec3's create a Matrix3
 
-    let _mat4_identity = Matrix4::new(()); // an empty tuple creates an identity Matrix4
-    let mat4_identity = Matrix4::identity(); // identity() also creates an identity Matrix4
-    let _mat4_zero = Matrix4::new(0); // any integer creates a zero Matrix4
-    let mat4_zero = Matrix4::zero(); // zero() also creates a zero Matrix4
+    let _mat4_identity: Matrix4 = Matrix4::new(()); // an empty tuple creates an identity Matrix4
+    let mat4_identity: Matrix4 = Matrix4::identity(); // identity() also creates an identity Matrix4
+    let _mat4_zero: Matrix4 = Matrix4::new(0); // any integer creates a zero Matrix4
+    let mat4_zero: Matrix4 = Matrix4::zero(); // zero() also creates a zero Matrix4
     let _mat4_mat4 = Matrix4::new(&mat4_identity); // this creates a Matrix4 from the provided Matrix4
     let mat4_16f32 = Matrix4::new((
         1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0, 11.0, 12.0, 13.0, 14.0, 15.0, 16.0,
@@ -149,13 +149,18 @@ pub fn test_matrix() {
 
 pub fn test_json_vector() {
     println!("Testing creating Vector4 from its respective json file:");
-    let vec4_json = Vector4::new("res/scene/vector.json");
+    let vec4_json: Vector4 = Vector4::new("res/scene/vector.json");
     println!("{:?}", vec4_json);
+    println!("Testing the fallible counterpart with the matrix file:");
+    match Vector4::<f32>::try_from("res/scene/matrix.json") {
+        Ok(vec4) => println!("{:?}", vec4),
+        Err(e) => println!("{}", e),
+    }
 }
 
 pub fn test_json_matrix() {
     println!("Testing creating Matrix4 from its respective json file:");
-    let mat4_json = Matrix4::new("res/scene/matrix.json");
+    let mat4_json: Matrix4 = Matrix4::new("res/scene/matrix.json");
     println!("{:?}", mat4_json);
 }
 
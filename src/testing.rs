@@ -0,0 +1,28 @@
+use std::fmt::Debug;
+
+use crate::error::Error;
+
+/* writes contents to a file of the temporary directory and returns its path */
+pub fn temp_file(name: &str, contents: impl AsRef<[u8]>) -> String {
+    let path = std::env::temp_dir().join(name);
+    std::fs::write(&path, contents).unwrap();
+    return String::from(path.to_str().unwrap());
+}
+
+/* line and message of a text file that failed to parse, panics on anything else */
+pub fn parse_error<T: Debug>(result: Result<T, Error>) -> (usize, String) {
+    return match result {
+        Err(Error::Parse {
+            m_line, m_message, ..
+        }) => (m_line, m_message),
+        other => panic!("unexpected {:?}", other),
+    };
+}
+
+/* message of a binary or JSON file that does not match its layout, panics on anything else */
+pub fn malformed<T: Debug>(result: Result<T, Error>) -> String {
+    return match result {
+        Err(Error::Malformed { m_message, .. }) => m_message,
+        other => panic!("unexpected {:?}", other),
+    };
+}
@@ -0,0 +1,82 @@
+use image::RgbaImage;
+
+use crate::scalar::Real;
+use crate::vector2::Vector2;
+use crate::vector3::Vector3;
+
+/* image mapped onto surfaces by their uv coordinates, stored as linear rgb */
+#[derive(Debug, PartialEq)]
+pub struct Texture {
+    m_width: u32,
+    m_height: u32,
+    /* rows from top to bottom, as in the image */
+    m_texels: Vec<Vector3>,
+}
+
+/* Associative methods implementation block */
+impl Texture {
+    pub fn width(&self) -> u32 {
+        return self.m_width;
+    }
+
+    pub fn height(&self) -> u32 {
+        return self.m_height;
+    }
+
+    /* bilinear lookup, (0, 0) is the bottom left corner of the image as for the film, and the
+    image repeats outside of [0, 1] */
+    pub fn sample(&self, uv: &Vector2<Real>) -> Vector3 {
+        if self.m_texels.is_empty() {
+            return Vector3::new((1.0, 1.0, 1.0));
+        }
+        let x = uv.get_u() * self.m_width as Real - 0.5;
+        let y = (1.0 - uv.get_v()) * self.m_height as Real - 0.5;
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = ((x - x0) as f32, (y - y0) as f32);
+        let texel = |i: Real, j: Real| {
+            let i = (i as i64).rem_euclid(self.m_width as i64) as usize;
+            let j = (j as i64).rem_euclid(self.m_height as i64) as usize;
+            return self.m_texels[j * self.m_width as usize + i];
+        };
+        let top = texel(x0, y0) * (1.0 - fx) + texel(x0 + 1.0, y0) * fx;
+        let bottom = texel(x0, y0 + 1.0) * (1.0 - fx) + texel(x0 + 1.0, y0 + 1.0) * fx;
+        return top * (1.0 - fy) + bottom * fy;
+    }
+}
+
+/* Associative functions implementation block */
+impl Texture {
+    /* Constructor, undoes the gamma the film encodes with, alpha is dropped */
+    pub fn new(image: &RgbaImage) -> Texture {
+        let decode = |c: u8| (c as f32 / 255.0).powf(2.2);
+        return Texture {
+            m_width: image.width(),
+            m_height: image.height(),
+            m_texels: image
+                .pixels()
+                .map(|p| Vector3::new((decode(p[0]), decode(p[1]), decode(p[2]))))
+                .collect(),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample() {
+        /* black top row, white bottom row */
+        let image = RgbaImage::from_fn(2, 2, |_, y| image::Rgba([255 * y as u8, 0, 0, 255]));
+        let texture = Texture::new(&image);
+        assert_eq!((texture.width(), texture.height()), (2, 2));
+
+        let red = |u: Real, v: Real| texture.sample(&Vector2::new((u, v))).get_r();
+        assert_eq!(red(0.25, 0.25), 1.0);
+        assert_eq!(red(0.75, 0.75), 0.0);
+        /* halfway between the rows, then wrapped around */
+        assert!((red(0.25, 0.5) - 0.5).abs() < 1e-6);
+        assert_eq!(red(1.25, -0.75), 1.0);
+        assert_eq!(red(0.25, 1.0), 0.5);
+    }
+}
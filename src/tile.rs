@@ -0,0 +1,31 @@
+use std::time::Duration;
+
+/* timings of the last rendered frame */
+#[derive(Debug, Default)]
+pub struct FrameStats {
+    /* indexed like the tiles returned by Bounds2i::tiles */
+    pub m_tile_times: Vec<Duration>,
+    pub m_total: Duration,
+    pub m_threads: usize,
+}
+
+/* Associative methods implementation block */
+impl FrameStats {
+    pub fn summary(&self) -> String {
+        if self.m_tile_times.is_empty() {
+            return String::from("no tiles rendered");
+        }
+        let min = self.m_tile_times.iter().min().unwrap();
+        let max = self.m_tile_times.iter().max().unwrap();
+        let sum: Duration = self.m_tile_times.iter().sum();
+        return format!(
+            "{} tiles on {} threads in {:?} (tile min {:?}, avg {:?}, max {:?})",
+            self.m_tile_times.len(),
+            self.m_threads,
+            self.m_total,
+            min,
+            sum / self.m_tile_times.len() as u32,
+            max
+        );
+    }
+}
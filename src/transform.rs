@@ -0,0 +1,264 @@
+use std::fmt;
+
+use crate::matrix3::Matrix3;
+use crate::matrix4::Matrix4;
+use crate::quaternion::Quaternion;
+use crate::scalar::Float;
+use crate::vector3::Vector3;
+
+/* an affine transform split into the parts that are edited by hand, applied as T * R * H * S */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform<T: Float = f32> {
+    pub m_translation: Vector3<T>,
+    pub m_rotation: Quaternion<T>,
+    /* a reflection shows up as a negative x scale */
+    pub m_scale: Vector3<T>,
+    /* xy, xz and yz factors of the unit upper triangular shear H, zero for TRS transforms */
+    pub m_shear: Vector3<T>,
+}
+
+/* Associative methods implementation block */
+impl<T: Float> Transform<T> {
+    /* recomposes T * R * H * S */
+    pub fn to_matrix4(&self) -> Matrix4<T> {
+        let (zero, one) = (T::ZERO, T::ONE);
+        let shear = Matrix3::new((
+            one,
+            self.m_shear.get_x(),
+            self.m_shear.get_y(),
+            zero,
+            one,
+            self.m_shear.get_z(),
+            zero,
+            zero,
+            one,
+        ));
+        let scale = Matrix3::new((
+            self.m_scale.get_x(),
+            zero,
+            zero,
+            zero,
+            self.m_scale.get_y(),
+            zero,
+            zero,
+            zero,
+            self.m_scale.get_z(),
+        ));
+        let linear = self.m_rotation.to_matrix3() * shear * scale;
+
+        let mut m = Matrix4::translate(
+            self.m_translation.get_x(),
+            self.m_translation.get_y(),
+            self.m_translation.get_z(),
+        );
+        for i in 0..3 {
+            for j in 0..3 {
+                m[(i, j)] = linear.m_data[i][j];
+            }
+        }
+        return m;
+    }
+
+    /* true when the transform cannot be written as translation, rotation and scale alone */
+    pub fn has_shear(&self, epsilon: T) -> bool {
+        return !self.m_shear.approx_eq(&Vector3::zero(), epsilon);
+    }
+}
+
+/* Associative functions implementation block */
+impl<T: Float> Transform<T> {
+    /* Static Constructors */
+    pub fn identity() -> Transform<T> {
+        return Transform::trs(
+            Vector3::zero(),
+            Quaternion::identity(),
+            Vector3::new((T::ONE, T::ONE, T::ONE)),
+        );
+    }
+
+    pub fn trs(
+        translation: Vector3<T>,
+        rotation: Quaternion<T>,
+        scale: Vector3<T>,
+    ) -> Transform<T> {
+        return Transform {
+            m_translation: translation,
+            m_rotation: rotation,
+            m_scale: scale,
+            m_shear: Vector3::zero(),
+        };
+    }
+
+    /* None for singular and projective matrices */
+    pub fn decompose(m: &Matrix4<T>) -> Option<Transform<T>> {
+        if !(m[(3, 0)] == T::ZERO
+            && m[(3, 1)] == T::ZERO
+            && m[(3, 2)] == T::ZERO
+            && m[(3, 3)] == T::ONE)
+        {
+            return None;
+        }
+        let linear = m.linear();
+        let det = linear.det();
+        if det == T::ZERO || !det.is_finite() {
+            return None;
+        }
+
+        /* Gram-Schmidt on the columns, linear = R * U with U upper triangular */
+        let column = |j: usize| {
+            return Vector3::new((
+                linear.m_data[0][j],
+                linear.m_data[1][j],
+                linear.m_data[2][j],
+            ));
+        };
+        let mut r0 = column(0);
+        let mut sx = r0.magnitude();
+        r0 /= sx;
+
+        let c1 = column(1);
+        let mut u01 = r0.dot(&c1);
+        let mut r1 = c1 - r0 * u01;
+        let sy = r1.magnitude();
+        r1 /= sy;
+
+        let c2 = column(2);
+        let mut u02 = r0.dot(&c2);
+        let u12 = r1.dot(&c2);
+        let mut r2 = c2 - r0 * u02 - r1 * u12;
+        let sz = r2.magnitude();
+        r2 /= sz;
+
+        /* R is a reflection when the determinant is negative, move it into the x scale. Flipping
+        the first column of R flips the first row of U with it */
+        if det < T::ZERO {
+            sx = -sx;
+            r0 = -r0;
+            u01 = -u01;
+            u02 = -u02;
+        }
+
+        let rotation = Matrix3::new((&r0, &r1, &r2));
+        return Some(Transform {
+            m_translation: Vector3::new((m[(0, 3)], m[(1, 3)], m[(2, 3)])),
+            m_rotation: Quaternion::from_matrix3(&rotation),
+            m_scale: Vector3::new((sx, sy, sz)),
+            m_shear: Vector3::new((u01 / sy, u02 / sz, u12 / sz)),
+        });
+    }
+}
+
+impl<T: Float> fmt::Display for Transform<T> {
+    /* rotation as Euler angles in degrees, see Quaternion::from_euler */
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let t = &self.m_translation;
+        let r = self.m_rotation.to_euler();
+        let s = &self.m_scale;
+        write!(
+            f,
+            "translation ({}, {}, {}) rotation ({}, {}, {}) scale ({}, {}, {})",
+            t.get_x(),
+            t.get_y(),
+            t.get_z(),
+            r.get_x(),
+            r.get_y(),
+            r.get_z(),
+            s.get_x(),
+            s.get_y(),
+            s.get_z()
+        )?;
+        if self.has_shear(T::from_f64(1e-6)) {
+            let h = &self.m_shear;
+            write!(f, " shear ({}, {}, {})", h.get_x(), h.get_y(), h.get_z())?;
+        }
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(m: &Matrix4) -> Transform {
+        let t = Transform::decompose(m).unwrap();
+        assert!(t.to_matrix4().approx_eq(m, 1e-5), "{:?}\n{:?}", m, t);
+        return t;
+    }
+
+    #[test]
+    fn trs() {
+        let rotation = Quaternion::from_euler(10.0, 20.0, 30.0);
+        let m = Matrix4::translate(1.0, 2.0, 3.0) * rotation.to_matrix4();
+        let t = round_trip(&(m * Matrix4::scale(2.0, 2.0, 2.0)));
+        assert!(
+            t.m_translation
+                .approx_eq(&Vector3::new((1.0, 2.0, 3.0)), 1e-6)
+        );
+        assert!(t.m_rotation.approx_eq(&rotation, 1e-6));
+        assert!(t.m_scale.approx_eq(&Vector3::new((2.0, 2.0, 2.0)), 1e-5));
+        assert!(!t.has_shear(1e-5));
+        assert!(
+            t.m_rotation
+                .to_euler()
+                .approx_eq(&Vector3::new((10.0, 20.0, 30.0)), 1e-3)
+        );
+    }
+
+    #[test]
+    fn non_uniform_scale() {
+        let rotation = Quaternion::from_axis_angle(&Vector3::new((1.0, 1.0, 0.0)), 50.0);
+        let m = rotation.to_matrix4() * Matrix4::scale(0.5, 3.0, 1.5);
+        let t = round_trip(&m);
+        assert!(t.m_scale.approx_eq(&Vector3::new((0.5, 3.0, 1.5)), 1e-5));
+        assert!(t.m_rotation.approx_eq(&rotation, 1e-5));
+        assert!(!t.has_shear(1e-5));
+    }
+
+    #[test]
+    fn negative_scale() {
+        let t = round_trip(&Matrix4::scale(-2.0, 3.0, 4.0));
+        assert!(t.m_scale.approx_eq(&Vector3::new((-2.0, 3.0, 4.0)), 1e-6));
+        assert!(t.m_rotation.approx_eq(&Quaternion::identity(), 1e-6));
+
+        /* the reflection moves to x, the rest becomes a half turn */
+        let t = round_trip(&(Matrix4::rotate_y(30.0) * Matrix4::scale(1.0, -1.0, 2.0)));
+        assert!(t.m_scale.approx_eq(&Vector3::new((-1.0, 1.0, 2.0)), 1e-5));
+        assert!(!t.has_shear(1e-5));
+
+        /* an even number of flips is a rotation */
+        let t = round_trip(&Matrix4::scale(-1.0, -1.0, 1.0));
+        assert!(t.m_scale.approx_eq(&Vector3::new((1.0, 1.0, 1.0)), 1e-6));
+    }
+
+    #[test]
+    fn shear() {
+        let m = Matrix4::new([
+            1.0, 0.5, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0,
+        ]);
+        let t = round_trip(&(Matrix4::rotate_z(20.0) * m));
+        assert!(t.has_shear(1e-5));
+        assert!(t.m_shear.approx_eq(&Vector3::new((0.5, 0.0, 0.0)), 1e-5));
+        assert!(format!("{}", t).contains("shear"));
+    }
+
+    #[test]
+    fn reflection_and_shear() {
+        let m = Matrix4::new([
+            1.0, 0.5, 0.25, 0.0, 0.0, 1.0, 0.75, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0,
+        ]);
+        let t = round_trip(&(Matrix4::scale(-1.0, 1.0, 1.0) * m));
+        assert!(t.m_scale.approx_eq(&Vector3::new((-1.0, 1.0, 1.0)), 1e-5));
+        assert!(t.m_shear.approx_eq(&Vector3::new((-0.5, -0.25, 0.75)), 1e-5));
+        round_trip(&(Matrix4::rotate_x(40.0) * Matrix4::scale(1.0, 2.0, -3.0) * m));
+    }
+
+    #[test]
+    fn rejects() {
+        assert!(Transform::decompose(&Matrix4::scale(1.0, 0.0, 1.0)).is_none());
+        assert!(Transform::decompose(&Matrix4::perspective(60.0, 1.0, 0.1, 10.0)).is_none());
+        assert_eq!(
+            Transform::<f32>::identity().to_matrix4(),
+            Matrix4::identity()
+        );
+    }
+}
@@ -0,0 +1,235 @@
+use crate::aabb::Aabb;
+use crate::hittable::{Hittable, HitRecord};
+use crate::normal3::Normal3;
+use crate::packet::{PacketHittable, RayPacket};
+use crate::point3::Point3;
+use crate::ray::Ray;
+use crate::scalar::Real;
+use crate::vector2::Vector2;
+
+/* counter-clockwise winding defines the front face */
+#[derive(Debug)]
+pub struct Triangle {
+    pub m_v0: Point3<Real>,
+    pub m_v1: Point3<Real>,
+    pub m_v2: Point3<Real>,
+}
+
+/* Associative functions implementation block */
+impl Triangle {
+    /* Constructor */
+    pub fn new(v0: Point3<Real>, v1: Point3<Real>, v2: Point3<Real>) -> Triangle {
+        return Triangle {
+            m_v0: v0,
+            m_v1: v1,
+            m_v2: v2,
+        };
+    }
+}
+
+impl Hittable for Triangle {
+    /* Möller–Trumbore, u and v of the record are the barycentrics of v1 and v2 */
+    fn hit(&self, ray: &Ray, t_min: Real, t_max: Real) -> Option<HitRecord> {
+        let edge1 = self.m_v1 - self.m_v0;
+        let edge2 = self.m_v2 - self.m_v0;
+
+        let p = ray.m_direction.cross(&edge2);
+        let det = edge1.dot(&p);
+        /* the ray is parallel to the triangle */
+        if det.abs() < 1e-8 {
+            return None;
+        }
+        let inv_det = 1.0 / det;
+
+        let s = ray.m_origin - self.m_v0;
+        let u = s.dot(&p) * inv_det;
+        if !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+
+        let q = s.cross(&edge1);
+        let v = ray.m_direction.dot(&q) * inv_det;
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+
+        let t = edge2.dot(&q) * inv_det;
+        if t < t_min || t > t_max {
+            return None;
+        }
+
+        return Some(HitRecord::new(
+            ray,
+            t,
+            Normal3::new(&edge1.cross(&edge2).unit()),
+            Vector2::new((u, v)),
+        ));
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        return Some(Aabb::new((&self.m_v0, &self.m_v1)).union_point(&self.m_v2));
+    }
+}
+
+impl PacketHittable for Triangle {
+    fn hit_packet<const N: usize>(
+        &self,
+        packet: &RayPacket<N>,
+        t_min: Real,
+        t_max: &[Real; N],
+    ) -> [Option<HitRecord>; N] {
+        let (t, u, v) = self.distances(packet, t_min, t_max);
+        let normal = Normal3::new(
+            &(self.m_v1 - self.m_v0)
+                .cross(&(self.m_v2 - self.m_v0))
+                .unit(),
+        );
+        return std::array::from_fn(|i| {
+            if t[i].is_nan() {
+                return None;
+            }
+            return Some(HitRecord::new(
+                &packet.ray(i),
+                t[i],
+                normal,
+                Vector2::new((u[i], v[i])),
+            ));
+        });
+    }
+
+    fn occluded_packet<const N: usize>(
+        &self,
+        packet: &RayPacket<N>,
+        t_min: Real,
+        t_max: &[Real; N],
+    ) -> [bool; N] {
+        return self.distances(packet, t_min, t_max).0.map(|t| !t.is_nan());
+    }
+}
+
+/* Associative methods implementation block */
+impl Triangle {
+    /* same arithmetic as hit over every lane, t and the barycentrics u and v, NaN t for a miss */
+    fn distances<const N: usize>(
+        &self,
+        packet: &RayPacket<N>,
+        t_min: Real,
+        t_max: &[Real; N],
+    ) -> ([Real; N], [Real; N], [Real; N]) {
+        let edge1 = self.m_v1 - self.m_v0;
+        let edge2 = self.m_v2 - self.m_v0;
+        let [e1x, e1y, e1z] = edge1.m_data;
+        let [e2x, e2y, e2z] = edge2.m_data;
+        let [ox, oy, oz] = &packet.m_origin;
+        let [dx, dy, dz] = &packet.m_direction;
+
+        let mut t = [Real::NAN; N];
+        let mut u = [0.0; N];
+        let mut v = [0.0; N];
+        for i in 0..N {
+            let (px, py, pz) = (
+                dy[i] * e2z - dz[i] * e2y,
+                dz[i] * e2x - dx[i] * e2z,
+                dx[i] * e2y - dy[i] * e2x,
+            );
+            let det = e1x * px + e1y * py + e1z * pz;
+            let inv_det = 1.0 / det;
+
+            let (sx, sy, sz) = (
+                ox[i] - self.m_v0.m_data[0],
+                oy[i] - self.m_v0.m_data[1],
+                oz[i] - self.m_v0.m_data[2],
+            );
+            u[i] = (sx * px + sy * py + sz * pz) * inv_det;
+
+            let (qx, qy, qz) = (
+                sy * e1z - sz * e1y,
+                sz * e1x - sx * e1z,
+                sx * e1y - sy * e1x,
+            );
+            v[i] = (dx[i] * qx + dy[i] * qy + dz[i] * qz) * inv_det;
+
+            let distance = (e2x * qx + e2y * qy + e2z * qz) * inv_det;
+            let inside = det.abs() >= 1e-8
+                && (0.0..=1.0).contains(&u[i])
+                && v[i] >= 0.0
+                && u[i] + v[i] <= 1.0
+                && distance >= t_min
+                && distance <= t_max[i];
+            t[i] = if inside { distance } else { Real::NAN };
+        }
+
+        return (t, u, v);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::direction3::Direction3;
+
+    fn triangle() -> Triangle {
+        return Triangle::new(
+            Point3::new((-1.0, -1.0, -2.0)),
+            Point3::new((1.0, -1.0, -2.0)),
+            Point3::new((0.0, 1.0, -2.0)),
+        );
+    }
+
+    #[test]
+    fn hit() {
+        let ray = Ray::new(Point3::origin(), Direction3::new((0.0, 0.0, -1.0)));
+        let rec = triangle().hit(&ray, 0.001, Real::MAX).unwrap();
+        assert!((rec.m_t - 2.0).abs() < 1e-5);
+        assert!(rec.m_front_face);
+        assert!((rec.m_normal.get_z() - 1.0).abs() < 1e-5);
+        assert!((rec.m_uv.get_u() - 0.25).abs() < 1e-5);
+        assert!((rec.m_uv.get_v() - 0.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn hit_back_face() {
+        let ray = Ray::new(
+            Point3::new((0.0, 0.0, -4.0)),
+            Direction3::new((0.0, 0.0, 1.0)),
+        );
+        let rec = triangle().hit(&ray, 0.001, Real::MAX).unwrap();
+        assert!(!rec.m_front_face);
+    }
+
+    #[test]
+    fn miss() {
+        let ray = Ray::new(
+            Point3::new((2.0, 0.0, 0.0)),
+            Direction3::new((0.0, 0.0, -1.0)),
+        );
+        assert!(triangle().hit(&ray, 0.001, Real::MAX).is_none());
+        let ray = Ray::new(Point3::origin(), Direction3::new((0.0, 0.0, 1.0)));
+        assert!(triangle().hit(&ray, 0.001, Real::MAX).is_none());
+    }
+
+    #[test]
+    fn grazing() {
+        /* parallel to the triangle's plane */
+        let ray = Ray::new(
+            Point3::new((-5.0, 0.0, -2.0)),
+            Direction3::new((1.0, 0.0, 0.0)),
+        );
+        assert!(triangle().hit(&ray, 0.001, Real::MAX).is_none());
+
+        /* right on the bottom edge */
+        let ray = Ray::new(
+            Point3::new((0.0, -1.0, 0.0)),
+            Direction3::new((0.0, 0.0, -1.0)),
+        );
+        let rec = triangle().hit(&ray, 0.001, Real::MAX).unwrap();
+        assert!(rec.m_uv.get_v().abs() < 1e-5);
+
+        /* just outside the bottom edge */
+        let ray = Ray::new(
+            Point3::new((0.0, -1.001, 0.0)),
+            Direction3::new((0.0, 0.0, -1.0)),
+        );
+        assert!(triangle().hit(&ray, 0.001, Real::MAX).is_none());
+    }
+}
@@ -0,0 +1,344 @@
+use std::ops::{
+    Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign,
+};
+
+use crate::point2i::Point2i;
+use crate::scalar::Float;
+use crate::vector3::Vector3;
+use crate::vector4::Vector4;
+
+/* film positions and texture coordinates, x and y doubling as u and v */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Vector2<T: Float = f32> {
+    pub m_data: [T; 2],
+}
+
+pub type Vector2d = Vector2<f64>;
+
+pub trait Instantiator<T: Float> {
+    fn into(self) -> Vector2<T>;
+}
+
+/* Associative methods implementation block */
+impl<T: Float> Vector2<T> {
+    /* Accessors */
+    pub fn get_x(&self) -> T {
+        return self.m_data[0];
+    }
+
+    pub fn get_y(&self) -> T {
+        return self.m_data[1];
+    }
+
+    pub fn get_u(&self) -> T {
+        return self.m_data[0];
+    }
+
+    pub fn get_v(&self) -> T {
+        return self.m_data[1];
+    }
+
+    /* Mutators */
+    pub fn set_x(&mut self, x: T) {
+        self.m_data[0] = x;
+    }
+
+    pub fn set_y(&mut self, y: T) {
+        self.m_data[1] = y;
+    }
+
+    pub fn set_u(&mut self, u: T) {
+        self.m_data[0] = u;
+    }
+
+    pub fn set_v(&mut self, v: T) {
+        self.m_data[1] = v;
+    }
+
+    pub fn magnitude(&self) -> T {
+        return self.dot(self).sqrt();
+    }
+
+    pub fn dot(&self, v: &Vector2<T>) -> T {
+        return self.get_x() * v.get_x() + self.get_y() * v.get_y();
+    }
+
+    pub fn normalize(&mut self) {
+        let mag: T = T::ONE / self.magnitude();
+        self.set_x(self.get_x() * mag);
+        self.set_y(self.get_y() * mag);
+    }
+
+    /* non-mutating counterpart of normalize() */
+    pub fn unit(&self) -> Vector2<T> {
+        let mut v = *self;
+        v.normalize();
+        return v;
+    }
+
+    /* same vector in another precision */
+    pub fn cast<U: Float>(&self) -> Vector2<U> {
+        return Vector2 {
+            m_data: self.m_data.map(|v| U::from_f64(v.to_f64())),
+        };
+    }
+
+    /* true when every component is within epsilon of v's */
+    pub fn approx_eq(&self, v: &Vector2<T>, epsilon: T) -> bool {
+        return self
+            .m_data
+            .iter()
+            .zip(v.m_data.iter())
+            .all(|(a, b)| (*a - *b).abs() <= epsilon);
+    }
+}
+
+/* Associative functions implementation block */
+impl<T: Float> Vector2<T> {
+    /* Constructor */
+    pub fn new<A>(args: A) -> Vector2<T>
+    where
+        A: Instantiator<T>,
+    {
+        return args.into();
+    }
+
+    /* Static Constructors */
+    pub fn zero() -> Vector2<T> {
+        return Vector2::new(0i64);
+    }
+}
+
+/* Operator overloads */
+impl<T: Float> Add for Vector2<T> {
+    type Output = Vector2<T>;
+
+    fn add(self, v: Vector2<T>) -> Vector2<T> {
+        return Vector2 {
+            m_data: [self.m_data[0] + v.m_data[0], self.m_data[1] + v.m_data[1]],
+        };
+    }
+}
+
+impl<T: Float> Sub for Vector2<T> {
+    type Output = Vector2<T>;
+
+    fn sub(self, v: Vector2<T>) -> Vector2<T> {
+        return Vector2 {
+            m_data: [self.m_data[0] - v.m_data[0], self.m_data[1] - v.m_data[1]],
+        };
+    }
+}
+
+/* component-wise product */
+impl<T: Float> Mul for Vector2<T> {
+    type Output = Vector2<T>;
+
+    fn mul(self, v: Vector2<T>) -> Vector2<T> {
+        return Vector2 {
+            m_data: [self.m_data[0] * v.m_data[0], self.m_data[1] * v.m_data[1]],
+        };
+    }
+}
+
+/* component-wise quotient, maps pixel positions to [0, 1] given the resolution */
+impl<T: Float> Div for Vector2<T> {
+    type Output = Vector2<T>;
+
+    fn div(self, v: Vector2<T>) -> Vector2<T> {
+        return Vector2 {
+            m_data: [self.m_data[0] / v.m_data[0], self.m_data[1] / v.m_data[1]],
+        };
+    }
+}
+
+impl<T: Float> Mul<T> for Vector2<T> {
+    type Output = Vector2<T>;
+
+    fn mul(self, s: T) -> Vector2<T> {
+        return Vector2 {
+            m_data: [self.m_data[0] * s, self.m_data[1] * s],
+        };
+    }
+}
+
+impl<T: Float> Div<T> for Vector2<T> {
+    type Output = Vector2<T>;
+
+    fn div(self, s: T) -> Vector2<T> {
+        return Vector2 {
+            m_data: [self.m_data[0] / s, self.m_data[1] / s],
+        };
+    }
+}
+
+impl Mul<Vector2<f32>> for f32 {
+    type Output = Vector2<f32>;
+
+    fn mul(self, v: Vector2<f32>) -> Vector2<f32> {
+        return v * self;
+    }
+}
+
+impl Mul<Vector2<f64>> for f64 {
+    type Output = Vector2<f64>;
+
+    fn mul(self, v: Vector2<f64>) -> Vector2<f64> {
+        return v * self;
+    }
+}
+
+impl<T: Float> Neg for Vector2<T> {
+    type Output = Vector2<T>;
+
+    fn neg(self) -> Vector2<T> {
+        return Vector2 {
+            m_data: [-self.m_data[0], -self.m_data[1]],
+        };
+    }
+}
+
+impl<T: Float> AddAssign for Vector2<T> {
+    fn add_assign(&mut self, v: Vector2<T>) {
+        *self = *self + v;
+    }
+}
+
+impl<T: Float> SubAssign for Vector2<T> {
+    fn sub_assign(&mut self, v: Vector2<T>) {
+        *self = *self - v;
+    }
+}
+
+impl<T: Float> MulAssign<T> for Vector2<T> {
+    fn mul_assign(&mut self, s: T) {
+        *self = *self * s;
+    }
+}
+
+impl<T: Float> DivAssign<T> for Vector2<T> {
+    fn div_assign(&mut self, s: T) {
+        *self = *self / s;
+    }
+}
+
+impl<T: Float> Index<usize> for Vector2<T> {
+    type Output = T;
+
+    fn index(&self, i: usize) -> &T {
+        return &self.m_data[i];
+    }
+}
+
+impl<T: Float> IndexMut<usize> for Vector2<T> {
+    fn index_mut(&mut self, i: usize) -> &mut T {
+        return &mut self.m_data[i];
+    }
+}
+
+impl<T: Float> Instantiator<T> for () {
+    fn into(self) -> Vector2<T> {
+        return Vector2 {
+            m_data: [T::ZERO; 2],
+        };
+    }
+}
+
+impl<T: Float> Instantiator<T> for i64 {
+    fn into(self) -> Vector2<T> {
+        return Vector2 {
+            m_data: [T::ZERO; 2],
+        };
+    }
+}
+
+impl<T: Float> Instantiator<T> for (T, T) {
+    fn into(self) -> Vector2<T> {
+        return Vector2 {
+            m_data: [self.0, self.1],
+        };
+    }
+}
+
+impl<T: Float> Instantiator<T> for &Vector2<T> {
+    fn into(self) -> Vector2<T> {
+        return Vector2 {
+            m_data: { self.m_data },
+        };
+    }
+}
+
+/* z is discarded */
+impl<T: Float> Instantiator<T> for &Vector3<T> {
+    fn into(self) -> Vector2<T> {
+        return Vector2 {
+            m_data: [self.m_data[0], self.m_data[1]],
+        };
+    }
+}
+
+/* z and w are discarded */
+impl<T: Float> Instantiator<T> for &Vector4<T> {
+    fn into(self) -> Vector2<T> {
+        return Vector2 {
+            m_data: [self.m_data[0], self.m_data[1]],
+        };
+    }
+}
+
+/* minimum corner of the pixel, add 0.5 for its center */
+impl<T: Float> Instantiator<T> for &Point2i {
+    fn into(self) -> Vector2<T> {
+        return Vector2 {
+            m_data: [
+                T::from_f64(self.m_data[0] as f64),
+                T::from_f64(self.m_data[1] as f64),
+            ],
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operators() {
+        let a = Vector2::new((1.0, 2.0));
+        let b = Vector2::new((4.0, 8.0));
+        assert_eq!(a + b, Vector2::new((5.0, 10.0)));
+        assert_eq!(b - a, Vector2::new((3.0, 6.0)));
+        assert_eq!(a * b, Vector2::new((4.0, 16.0)));
+        assert_eq!(b / a, Vector2::new((4.0, 4.0)));
+        assert_eq!(a * 2.0, 2.0 * a);
+        assert_eq!(-a, Vector2::new((-1.0, -2.0)));
+        assert_eq!(a.dot(&b), 20.0);
+        assert_eq!(Vector2::new((3.0, 4.0)).magnitude(), 5.0);
+
+        let mut v = a;
+        v += b;
+        v *= 0.5;
+        v[1] = 1.0;
+        assert_eq!(v, Vector2::new((2.5, 1.0)));
+    }
+
+    #[test]
+    fn conversions() {
+        let v: Vector2 = Vector2::new(&Vector3::new((1.0, 2.0, 3.0)));
+        assert_eq!(v, Vector2::new((1.0, 2.0)));
+        assert_eq!(Vector3::new((&v, 5.0)), Vector3::new((1.0, 2.0, 5.0)));
+        assert_eq!(
+            Vector4::new((&v, 5.0, 0.0)),
+            Vector4::new((1.0, 2.0, 5.0, 0.0))
+        );
+        assert_eq!(Vector2::new(&Vector4::new((1.0, 2.0, 3.0, 1.0))), v);
+
+        let p = Point2i::new((3, -2));
+        assert_eq!(Vector2d::new(&p), Vector2::new((3.0, -2.0)));
+        assert_eq!(
+            Point2i::new(&Vector2::new((2.7, -0.5))),
+            Point2i::new((2, -1))
+        );
+        assert_eq!(v.cast::<f64>(), Vector2d::new((1.0, 2.0)));
+    }
+}
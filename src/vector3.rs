@@ -1,78 +1,84 @@
-#![allow(dead_code)]
+use std::ops::{
+    Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign,
+};
 
-use crate::DEBUG;
+use crate::scalar::Float;
+use crate::vector2::Vector2;
 use crate::vector4::Vector4;
 
-#[derive(Debug)]
-pub struct Vector3 {
-    pub m_data: [f32; 3],
+/* defaults to f32 for colors, geometry uses Vector3<Real>, see scalar::Real */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Vector3<T: Float = f32> {
+    pub m_data: [T; 3],
 }
 
-pub trait Instantiator {
-    fn into(self) -> Vector3;
+pub type Vector3d = Vector3<f64>;
+
+pub trait Instantiator<T: Float> {
+    fn into(self) -> Vector3<T>;
 }
 
 /* Associative methods implementation block */
-impl Vector3 {
+impl<T: Float> Vector3<T> {
     /* Accessors */
-    pub fn get_x(&self) -> f32 {
+    pub fn get_x(&self) -> T {
         return self.m_data[0];
     }
 
-    pub fn get_y(&self) -> f32 {
+    pub fn get_y(&self) -> T {
         return self.m_data[1];
     }
 
-    pub fn get_z(&self) -> f32 {
+    pub fn get_z(&self) -> T {
         return self.m_data[2];
     }
 
-    pub fn get_r(&self) -> f32 {
+    pub fn get_r(&self) -> T {
         return self.m_data[0];
     }
 
-    pub fn get_g(&self) -> f32 {
+    pub fn get_g(&self) -> T {
         return self.m_data[1];
     }
 
-    pub fn get_b(&self) -> f32 {
+    pub fn get_b(&self) -> T {
         return self.m_data[2];
     }
 
     /* Mutators */
-    pub fn set_x(&mut self, x: f32) {
+    pub fn set_x(&mut self, x: T) {
         self.m_data[0] = x;
     }
 
-    pub fn set_y(&mut self, y: f32) {
+    pub fn set_y(&mut self, y: T) {
         self.m_data[1] = y;
     }
 
-    pub fn set_z(&mut self, z: f32) {
+    pub fn set_z(&mut self, z: T) {
         self.m_data[2] = z;
     }
 
-    pub fn set_r(&mut self, r: f32) {
+    pub fn set_r(&mut self, r: T) {
         self.m_data[0] = r;
     }
 
-    pub fn set_g(&mut self, g: f32) {
+    pub fn set_g(&mut self, g: T) {
         self.m_data[1] = g;
     }
 
-    pub fn set_b(&mut self, b: f32) {
+    pub fn set_b(&mut self, b: T) {
         self.m_data[2] = b;
     }
 
-    pub fn magnitude(&self) -> f32 {
-        return (self.get_x().powf(2.0) + self.get_y().powf(2.0) + self.get_z().powf(2.0)).sqrt();
+    pub fn magnitude(&self) -> T {
+        return self.dot(self).sqrt();
     }
 
-    pub fn dot(&self, v: &Vector3) -> f32 {
+    pub fn dot(&self, v: &Vector3<T>) -> T {
         return self.get_x() * v.get_x() + self.get_y() * v.get_y() + self.get_z() * v.get_z();
     }
 
-    pub fn cross(&self, v: &Vector3) -> Vector3 {
+    pub fn cross(&self, v: &Vector3<T>) -> Vector3<T> {
         return Vector3::new((
             (self.get_y() * v.get_z()) - (self.get_z() * v.get_y()), //x
             (self.get_z() * v.get_x()) - (self.get_x() * v.get_z()), //y
@@ -81,81 +87,303 @@ impl Vector3 {
     }
 
     pub fn normalize(&mut self) {
-        let mag: f32 = 1.0 / self.magnitude();
+        let mag: T = T::ONE / self.magnitude();
         self.set_x(self.get_x() * mag);
         self.set_y(self.get_y() * mag);
         self.set_z(self.get_z() * mag);
     }
+
+    /* non-mutating counterpart of normalize() */
+    pub fn unit(&self) -> Vector3<T> {
+        let mut v = *self;
+        v.normalize();
+        return v;
+    }
+
+    /* any unit vector orthogonal to this one */
+    pub fn perpendicular(&self) -> Vector3<T> {
+        let helper = if self.get_x().abs() > T::from_f64(0.9) {
+            Vector3::new((T::ZERO, T::ONE, T::ZERO))
+        } else {
+            Vector3::new((T::ONE, T::ZERO, T::ZERO))
+        };
+        return self.cross(&helper).unit();
+    }
+
+    /* brightness of a linear rgb color as perceived, Rec. 709 weights */
+    pub fn luminance(&self) -> T {
+        return T::from_f64(0.2126) * self.get_r()
+            + T::from_f64(0.7152) * self.get_g()
+            + T::from_f64(0.0722) * self.get_b();
+    }
+
+    /* same vector in another precision */
+    pub fn cast<U: Float>(&self) -> Vector3<U> {
+        return Vector3 {
+            m_data: self.m_data.map(|v| U::from_f64(v.to_f64())),
+        };
+    }
+
+    /* true when every component is within epsilon of v's */
+    pub fn approx_eq(&self, v: &Vector3<T>, epsilon: T) -> bool {
+        return self
+            .m_data
+            .iter()
+            .zip(v.m_data.iter())
+            .all(|(a, b)| (*a - *b).abs() <= epsilon);
+    }
 }
 
 /* Associative functions implementation block */
-impl Vector3 {
+impl<T: Float> Vector3<T> {
     /* Constructor */
-    pub fn new<A>(args: A) -> Vector3
+    pub fn new<A>(args: A) -> Vector3<T>
     where
-        A: Instantiator,
+        A: Instantiator<T>,
     {
         return args.into();
     }
 
     /* Static Constructors */
-    pub fn zero() -> Vector3 {
+    pub fn zero() -> Vector3<T> {
         return Vector3::new(0i64);
     }
 }
 
+/* Operator overloads */
+impl<T: Float> Add for Vector3<T> {
+    type Output = Vector3<T>;
+
+    fn add(self, v: Vector3<T>) -> Vector3<T> {
+        return Vector3 {
+            m_data: [
+                self.m_data[0] + v.m_data[0],
+                self.m_data[1] + v.m_data[1],
+                self.m_data[2] + v.m_data[2],
+            ],
+        };
+    }
+}
+
+impl<T: Float> Sub for Vector3<T> {
+    type Output = Vector3<T>;
+
+    fn sub(self, v: Vector3<T>) -> Vector3<T> {
+        return Vector3 {
+            m_data: [
+                self.m_data[0] - v.m_data[0],
+                self.m_data[1] - v.m_data[1],
+                self.m_data[2] - v.m_data[2],
+            ],
+        };
+    }
+}
+
+/* component-wise product */
+impl<T: Float> Mul for Vector3<T> {
+    type Output = Vector3<T>;
+
+    fn mul(self, v: Vector3<T>) -> Vector3<T> {
+        return Vector3 {
+            m_data: [
+                self.m_data[0] * v.m_data[0],
+                self.m_data[1] * v.m_data[1],
+                self.m_data[2] * v.m_data[2],
+            ],
+        };
+    }
+}
+
+impl<T: Float> Mul<T> for Vector3<T> {
+    type Output = Vector3<T>;
+
+    fn mul(self, s: T) -> Vector3<T> {
+        return Vector3 {
+            m_data: [self.m_data[0] * s, self.m_data[1] * s, self.m_data[2] * s],
+        };
+    }
+}
+
+impl<T: Float> Div<T> for Vector3<T> {
+    type Output = Vector3<T>;
+
+    fn div(self, s: T) -> Vector3<T> {
+        return Vector3 {
+            m_data: [self.m_data[0] / s, self.m_data[1] / s, self.m_data[2] / s],
+        };
+    }
+}
+
+impl Mul<Vector3<f32>> for f32 {
+    type Output = Vector3<f32>;
+
+    fn mul(self, v: Vector3<f32>) -> Vector3<f32> {
+        return v * self;
+    }
+}
+
+impl Mul<Vector3<f64>> for f64 {
+    type Output = Vector3<f64>;
+
+    fn mul(self, v: Vector3<f64>) -> Vector3<f64> {
+        return v * self;
+    }
+}
+
+impl<T: Float> Neg for Vector3<T> {
+    type Output = Vector3<T>;
+
+    fn neg(self) -> Vector3<T> {
+        return Vector3 {
+            m_data: [-self.m_data[0], -self.m_data[1], -self.m_data[2]],
+        };
+    }
+}
+
+impl<T: Float> AddAssign for Vector3<T> {
+    fn add_assign(&mut self, v: Vector3<T>) {
+        *self = *self + v;
+    }
+}
+
+impl<T: Float> SubAssign for Vector3<T> {
+    fn sub_assign(&mut self, v: Vector3<T>) {
+        *self = *self - v;
+    }
+}
+
+impl<T: Float> MulAssign for Vector3<T> {
+    fn mul_assign(&mut self, v: Vector3<T>) {
+        *self = *self * v;
+    }
+}
+
+impl<T: Float> MulAssign<T> for Vector3<T> {
+    fn mul_assign(&mut self, s: T) {
+        *self = *self * s;
+    }
+}
+
+impl<T: Float> DivAssign<T> for Vector3<T> {
+    fn div_assign(&mut self, s: T) {
+        *self = *self / s;
+    }
+}
+
+impl<T: Float> Index<usize> for Vector3<T> {
+    type Output = T;
+
+    fn index(&self, i: usize) -> &T {
+        return &self.m_data[i];
+    }
+}
+
+impl<T: Float> IndexMut<usize> for Vector3<T> {
+    fn index_mut(&mut self, i: usize) -> &mut T {
+        return &mut self.m_data[i];
+    }
+}
+
 /* Associative functions implementation block */
-impl Instantiator for () {
-    fn into(self) -> Vector3 {
-        if DEBUG {
-            println!("Vector3::Instantiator: Invoked () overload!");
-        }
+impl<T: Float> Instantiator<T> for () {
+    fn into(self) -> Vector3<T> {
         return Vector3 {
-            m_data: [0.0, 0.0, 0.0],
+            m_data: [T::ZERO; 3],
         };
     }
 }
 
-impl Instantiator for i64 {
-    fn into(self) -> Vector3 {
-        if DEBUG {
-            println!("Vector3::Instantiator: Invoked (i64) Zero overload!");
-        }
+impl<T: Float> Instantiator<T> for i64 {
+    fn into(self) -> Vector3<T> {
         return Vector3 {
-            m_data: [0.0, 0.0, 0.0],
+            m_data: [T::ZERO; 3],
         };
     }
 }
 
-impl Instantiator for (f32, f32, f32) {
-    fn into(self) -> Vector3 {
-        if DEBUG {
-            println!("Vector3::Instantiator: Invoked (f32, f32, f32) overload!");
-        }
+impl<T: Float> Instantiator<T> for (T, T, T) {
+    fn into(self) -> Vector3<T> {
         return Vector3 {
             m_data: [self.0, self.1, self.2],
         };
     }
 }
 
-impl Instantiator for &Vector3 {
-    fn into(self) -> Vector3 {
-        if DEBUG {
-            println!("Vector3::Instantiator: Invoked (&Vector3) overload!");
-        }
+/* x and y from the Vector2, z given */
+impl<T: Float> Instantiator<T> for (&Vector2<T>, T) {
+    fn into(self) -> Vector3<T> {
+        return Vector3 {
+            m_data: [self.0.m_data[0], self.0.m_data[1], self.1],
+        };
+    }
+}
+
+impl<T: Float> Instantiator<T> for &Vector3<T> {
+    fn into(self) -> Vector3<T> {
         return Vector3 {
             m_data: { self.m_data },
         };
     }
 }
 
-impl Instantiator for &Vector4 {
-    fn into(self) -> Vector3 {
-        if DEBUG {
-            println!("Vector3::Instantiator: Invoked (&Vector4) overload!");
-        }
+impl<T: Float> Instantiator<T> for &Vector4<T> {
+    fn into(self) -> Vector3<T> {
         return Vector3 {
             m_data: { [self.m_data[0], self.m_data[1], self.m_data[2]] },
         };
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operators() {
+        let a = Vector3::new((1.0, 2.0, 3.0));
+        let b = Vector3::new((4.0, 5.0, 6.0));
+        assert_eq!(a + b, Vector3::new((5.0, 7.0, 9.0)));
+        assert_eq!(b - a, Vector3::new((3.0, 3.0, 3.0)));
+        assert_eq!(a * b, Vector3::new((4.0, 10.0, 18.0)));
+        assert_eq!(a * 2.0, 2.0 * a);
+        assert_eq!(b / 2.0, Vector3::new((2.0, 2.5, 3.0)));
+        assert_eq!(-a, Vector3::new((-1.0, -2.0, -3.0)));
+        assert_eq!(a[2], 3.0);
+    }
+
+    #[test]
+    fn assign_operators() {
+        let mut v = Vector3::new((1.0, 2.0, 3.0));
+        v += Vector3::new((1.0, 1.0, 1.0));
+        v -= Vector3::new((0.0, 1.0, 0.0));
+        v *= 2.0;
+        v /= 4.0;
+        v[0] = 10.0;
+        assert_eq!(v, Vector3::new((10.0, 1.0, 2.0)));
+        assert!(v.approx_eq(&Vector3::new((10.0, 1.0, 2.000001)), 1e-5));
+        assert!(!v.approx_eq(&Vector3::new((10.0, 1.0, 2.1)), 1e-5));
+    }
+
+    #[test]
+    fn double_precision() {
+        let a = Vector3d::new((1.0e6, 0.0, 0.0));
+        let b = a + Vector3::new((1.0e-4, 0.0, 0.0));
+        assert!(((b - a).get_x() - 1.0e-4).abs() < 1e-9);
+        assert!(
+            (b - a)
+                .cast::<f32>()
+                .approx_eq(&Vector3::new((1.0e-4, 0.0, 0.0)), 1e-9)
+        );
+        assert_eq!(b.cast::<f32>() - a.cast::<f32>(), Vector3::zero());
+        assert_eq!(2.0 * a, a * 2.0);
+    }
+
+    #[test]
+    fn luminance() {
+        let white: Vector3 = Vector3::new((1.0, 1.0, 1.0));
+        assert!((white.luminance() - 1.0).abs() < 1e-6);
+        assert!(
+            Vector3::new((0.0, 1.0, 0.0)).luminance() > Vector3::new((1.0, 0.0, 1.0)).luminance()
+        );
+    }
+}
@@ -1,239 +1,403 @@
-#![allow(dead_code)]
-
-use std::fs::File;
-use std::io::Read;
+use std::ops::{
+    Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign,
+};
 
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
 
-use crate::DEBUG;
+use crate::error::Error;
+use crate::loader;
+use crate::scalar::Float;
+use crate::vector2::Vector2;
 use crate::vector3::Vector3;
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct Vector4 {
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Vector4<T: Float = f32> {
     #[serde(rename = "values")]
-    pub m_data: [f32; 4],
+    pub m_data: [T; 4],
 }
 
-pub trait Instantiator {
-    fn into(self) -> Vector4;
+pub type Vector4d = Vector4<f64>;
+
+pub trait Instantiator<T: Float> {
+    fn into(self) -> Vector4<T>;
 }
 
 /* Associative methods implementation block */
-impl Vector4 {
+impl<T: Float> Vector4<T> {
     /* Accessors */
-    pub fn get_x(&self) -> f32 {
+    pub fn get_x(&self) -> T {
         return self.m_data[0];
     }
 
-    pub fn get_y(&self) -> f32 {
+    pub fn get_y(&self) -> T {
         return self.m_data[1];
     }
 
-    pub fn get_z(&self) -> f32 {
+    pub fn get_z(&self) -> T {
         return self.m_data[2];
     }
 
-    pub fn get_w(&self) -> f32 {
+    pub fn get_w(&self) -> T {
         return self.m_data[3];
     }
 
-    pub fn get_r(&self) -> f32 {
+    pub fn get_r(&self) -> T {
         return self.m_data[0];
     }
 
-    pub fn get_g(&self) -> f32 {
+    pub fn get_g(&self) -> T {
         return self.m_data[1];
     }
 
-    pub fn get_b(&self) -> f32 {
+    pub fn get_b(&self) -> T {
         return self.m_data[2];
     }
 
-    pub fn get_a(&self) -> f32 {
+    pub fn get_a(&self) -> T {
         return self.m_data[3];
     }
 
     /* Mutators */
-    pub fn set_x(&mut self, x: f32) {
+    pub fn set_x(&mut self, x: T) {
         self.m_data[0] = x;
     }
 
-    pub fn set_y(&mut self, y: f32) {
+    pub fn set_y(&mut self, y: T) {
         self.m_data[1] = y;
     }
 
-    pub fn set_z(&mut self, z: f32) {
+    pub fn set_z(&mut self, z: T) {
         self.m_data[2] = z;
     }
 
-    pub fn set_w(&mut self, w: f32) {
+    pub fn set_w(&mut self, w: T) {
         self.m_data[3] = w;
     }
 
-    pub fn set_r(&mut self, r: f32) {
+    pub fn set_r(&mut self, r: T) {
         self.m_data[0] = r;
     }
 
-    pub fn set_g(&mut self, g: f32) {
+    pub fn set_g(&mut self, g: T) {
         self.m_data[1] = g;
     }
 
-    pub fn set_b(&mut self, b: f32) {
+    pub fn set_b(&mut self, b: T) {
         self.m_data[2] = b;
     }
 
-    pub fn set_a(&mut self, a: f32) {
+    pub fn set_a(&mut self, a: T) {
         self.m_data[3] = a;
     }
 
-    pub fn magnitude(&self) -> f32 {
-        return (self.get_x().powf(2.0) + self.get_y().powf(2.0) + self.get_z().powf(2.0)).sqrt();
+    pub fn magnitude(&self) -> T {
+        return self.dot(self).sqrt();
     }
 
-    pub fn dot(&self, v: &Vector4) -> f32 {
-        return (self.get_x() * v.get_x())
-            + (self.get_y() * v.get_y())
-            + (self.get_z() * v.get_z());
+    /* over all four components, w included. Use Point3 and Direction3 for 3D geometry */
+    pub fn dot(&self, v: &Vector4<T>) -> T {
+        return T::dot4(&self.m_data, &v.m_data);
     }
 
-    pub fn cross(&self, v: &Vector4) -> Vector4 {
+    /* cross product of the x, y and z parts, a direction so w = 0 whatever the inputs */
+    pub fn cross(&self, v: &Vector4<T>) -> Vector4<T> {
         return Vector4::new((
             (self.get_y() * v.get_z()) - (self.get_z() * v.get_y()), //x
             (self.get_z() * v.get_x()) - (self.get_x() * v.get_z()), //y
             (self.get_x() * v.get_y()) - (self.get_y() * v.get_x()), //z
-            self.get_w(),                                            //w
+            T::ZERO,                                                 //w
         ));
     }
 
-    pub fn cross_zero(&self, v: &Vector4) -> Vector4 {
-        return Vector4::new((
-            (self.get_y() * v.get_z()) - (self.get_z() * v.get_y()), //x
-            (self.get_z() * v.get_x()) - (self.get_x() * v.get_z()), //y
-            (self.get_x() * v.get_y()) - (self.get_y() * v.get_x()), //z
-            0.0,                                                     //w
-        ));
+    pub fn normalize(&mut self) {
+        let mag: T = T::ONE / self.magnitude();
+        *self *= mag;
     }
 
-    pub fn normalize(&mut self) {
-        let mag: f32 = 1.0 / self.magnitude();
-        self.set_x(self.get_x() * mag);
-        self.set_y(self.get_y() * mag);
-        self.set_z(self.get_z() * mag);
-        //self.set_w(self.get_w() * mag);
+    /* same vector in another precision */
+    pub fn cast<U: Float>(&self) -> Vector4<U> {
+        return Vector4 {
+            m_data: self.m_data.map(|v| U::from_f64(v.to_f64())),
+        };
+    }
+
+    /* true when every component, w included, is within epsilon of v's */
+    pub fn approx_eq(&self, v: &Vector4<T>, epsilon: T) -> bool {
+        return self
+            .m_data
+            .iter()
+            .zip(v.m_data.iter())
+            .all(|(a, b)| (*a - *b).abs() <= epsilon);
     }
 }
 
 /* Associative functions implementation block */
-impl Vector4 {
+impl<T: Float> Vector4<T> {
     /* Constructor */
-    pub fn new<A>(args: A) -> Vector4
+    pub fn new<A>(args: A) -> Vector4<T>
     where
-        A: Instantiator,
+        A: Instantiator<T>,
     {
         return args.into();
     }
 
-    pub fn json(path: &str) -> Vector4 {
-        if DEBUG {
-            println!("Vector4::Instantiator: Invoked json overload!");
-        }
-        let mut file = File::open(path).unwrap();
-        let mut data = String::new();
-        file.read_to_string(&mut data).unwrap();
+    pub fn json(path: &str) -> Result<Vector4<T>, Error> {
+        let arr = loader::read_values(path, 4)?;
 
-        let json: Value = serde_json::from_str(&data).expect("JSON was not well-formatted");
+        return Ok(Vector4::new((
+            T::from_f32(arr[0]),
+            T::from_f32(arr[1]),
+            T::from_f32(arr[2]),
+            T::from_f32(arr[3]),
+        )));
+    }
 
-        let vals = json.get("values");
+    /* Static Constructors */
+    pub fn zero() -> Vector4<T> {
+        return Vector4::new(0i64);
+    }
+}
 
-        assert_eq!(4, vals.unwrap().as_array().unwrap().len());
+/* Operator overloads */
+impl<T: Float> Add for Vector4<T> {
+    type Output = Vector4<T>;
+
+    fn add(self, v: Vector4<T>) -> Vector4<T> {
+        return Vector4 {
+            m_data: [
+                self.m_data[0] + v.m_data[0],
+                self.m_data[1] + v.m_data[1],
+                self.m_data[2] + v.m_data[2],
+                self.m_data[3] + v.m_data[3],
+            ],
+        };
+    }
+}
 
-        let vals: Option<&Vec<Value>> = vals.unwrap().as_array();
-        let mut arr = [0.0f32; 4];
-        for i in 0..4 {
-            arr[i] = vals.unwrap().get(i).unwrap().as_f64().unwrap() as f32;
-        }
+impl<T: Float> Sub for Vector4<T> {
+    type Output = Vector4<T>;
 
-        return Vector4::new((arr[0], arr[1], arr[2], arr[3]));
+    fn sub(self, v: Vector4<T>) -> Vector4<T> {
+        return Vector4 {
+            m_data: [
+                self.m_data[0] - v.m_data[0],
+                self.m_data[1] - v.m_data[1],
+                self.m_data[2] - v.m_data[2],
+                self.m_data[3] - v.m_data[3],
+            ],
+        };
     }
+}
 
-    /* Static Constructors */
-    pub fn zero() -> Vector4 {
-        return Vector4::new(0i64);
+/* component-wise product */
+impl<T: Float> Mul for Vector4<T> {
+    type Output = Vector4<T>;
+
+    fn mul(self, v: Vector4<T>) -> Vector4<T> {
+        return Vector4 {
+            m_data: [
+                self.m_data[0] * v.m_data[0],
+                self.m_data[1] * v.m_data[1],
+                self.m_data[2] * v.m_data[2],
+                self.m_data[3] * v.m_data[3],
+            ],
+        };
     }
 }
 
-impl Instantiator for () {
-    fn into(self) -> Vector4 {
-        if DEBUG {
-            println!("Vector4::Instantiator: Invoked () overload!");
-        }
+impl<T: Float> Mul<T> for Vector4<T> {
+    type Output = Vector4<T>;
+
+    fn mul(self, s: T) -> Vector4<T> {
         return Vector4 {
-            m_data: [0.0, 0.0, 0.0, 1.0],
+            m_data: [
+                self.m_data[0] * s,
+                self.m_data[1] * s,
+                self.m_data[2] * s,
+                self.m_data[3] * s,
+            ],
         };
     }
 }
 
-impl Instantiator for i64 {
-    fn into(self) -> Vector4 {
-        if DEBUG {
-            println!("Vector4::Instantiator: Invoked (i64) Zero overload!");
-        }
+impl<T: Float> Div<T> for Vector4<T> {
+    type Output = Vector4<T>;
+
+    fn div(self, s: T) -> Vector4<T> {
         return Vector4 {
-            m_data: [0.0, 0.0, 0.0, 0.0],
+            m_data: [
+                self.m_data[0] / s,
+                self.m_data[1] / s,
+                self.m_data[2] / s,
+                self.m_data[3] / s,
+            ],
         };
     }
 }
 
-impl Instantiator for (f32, f32, f32) {
-    fn into(self) -> Vector4 {
-        if DEBUG {
-            println!("Vector4::Instantiator: Invoked (f32, f32, f32) overload!");
-        }
+impl Mul<Vector4<f32>> for f32 {
+    type Output = Vector4<f32>;
+
+    fn mul(self, v: Vector4<f32>) -> Vector4<f32> {
+        return v * self;
+    }
+}
+
+impl Mul<Vector4<f64>> for f64 {
+    type Output = Vector4<f64>;
+
+    fn mul(self, v: Vector4<f64>) -> Vector4<f64> {
+        return v * self;
+    }
+}
+
+impl<T: Float> Neg for Vector4<T> {
+    type Output = Vector4<T>;
+
+    fn neg(self) -> Vector4<T> {
         return Vector4 {
-            m_data: [self.0, self.1, self.2, 1.0],
+            m_data: [
+                -self.m_data[0],
+                -self.m_data[1],
+                -self.m_data[2],
+                -self.m_data[3],
+            ],
         };
     }
 }
 
-impl Instantiator for (f32, f32, f32, f32) {
-    fn into(self) -> Vector4 {
-        if DEBUG {
-            println!("Vector4::Instantiator: Invoked (f32, f32, f32, f32) overload!");
-        }
+impl<T: Float> AddAssign for Vector4<T> {
+    fn add_assign(&mut self, v: Vector4<T>) {
+        *self = *self + v;
+    }
+}
+
+impl<T: Float> SubAssign for Vector4<T> {
+    fn sub_assign(&mut self, v: Vector4<T>) {
+        *self = *self - v;
+    }
+}
+
+impl<T: Float> MulAssign for Vector4<T> {
+    fn mul_assign(&mut self, v: Vector4<T>) {
+        *self = *self * v;
+    }
+}
+
+impl<T: Float> MulAssign<T> for Vector4<T> {
+    fn mul_assign(&mut self, s: T) {
+        *self = *self * s;
+    }
+}
+
+impl<T: Float> DivAssign<T> for Vector4<T> {
+    fn div_assign(&mut self, s: T) {
+        *self = *self / s;
+    }
+}
+
+impl<T: Float> Index<usize> for Vector4<T> {
+    type Output = T;
+
+    fn index(&self, i: usize) -> &T {
+        return &self.m_data[i];
+    }
+}
+
+impl<T: Float> IndexMut<usize> for Vector4<T> {
+    fn index_mut(&mut self, i: usize) -> &mut T {
+        return &mut self.m_data[i];
+    }
+}
+
+impl<T: Float> Instantiator<T> for () {
+    fn into(self) -> Vector4<T> {
+        return Vector4 {
+            m_data: [T::ZERO, T::ZERO, T::ZERO, T::ONE],
+        };
+    }
+}
+
+impl<T: Float> Instantiator<T> for i64 {
+    fn into(self) -> Vector4<T> {
+        return Vector4 {
+            m_data: [T::ZERO; 4],
+        };
+    }
+}
+
+impl<T: Float> Instantiator<T> for (T, T, T) {
+    fn into(self) -> Vector4<T> {
+        return Vector4 {
+            m_data: [self.0, self.1, self.2, T::ONE],
+        };
+    }
+}
+
+impl<T: Float> Instantiator<T> for (T, T, T, T) {
+    fn into(self) -> Vector4<T> {
         return Vector4 {
             m_data: [self.0, self.1, self.2, self.3],
         };
     }
 }
 
-impl Instantiator for &Vector3 {
-    fn into(self) -> Vector4 {
-        if DEBUG {
-            println!("Vector4::Instantiator: Invoked (&Vector3) overload!");
-        }
+/* x and y from the Vector2, z and w given */
+impl<T: Float> Instantiator<T> for (&Vector2<T>, T, T) {
+    fn into(self) -> Vector4<T> {
+        return Vector4 {
+            m_data: [self.0.m_data[0], self.0.m_data[1], self.1, self.2],
+        };
+    }
+}
+
+impl<T: Float> Instantiator<T> for &Vector3<T> {
+    fn into(self) -> Vector4<T> {
         return Vector4 {
-            m_data: { [self.m_data[0], self.m_data[1], self.m_data[2], 1.0] },
+            m_data: { [self.m_data[0], self.m_data[1], self.m_data[2], T::ONE] },
         };
     }
 }
 
-impl Instantiator for &Vector4 {
-    fn into(self) -> Vector4 {
-        if DEBUG {
-            println!("Vector4::Instantiator: Invoked (&Vector4) overload!");
-        }
+impl<T: Float> Instantiator<T> for &Vector4<T> {
+    fn into(self) -> Vector4<T> {
         return Vector4 {
             m_data: { self.m_data },
         };
     }
 }
 
-impl Instantiator for &str {
-    fn into(self) -> Vector4 {
-        if DEBUG {
-            println!("Vector4::Instantiator: Invoked (&str) json overload!");
-        }
-        return Vector4::json(self);
+impl<T: Float> Instantiator<T> for &str {
+    fn into(self) -> Vector4<T> {
+        return Vector4::json(self).unwrap_or_else(|e| panic!("{}", e));
+    }
+}
+
+impl<T: Float> TryFrom<&str> for Vector4<T> {
+    type Error = Error;
+
+    fn try_from(path: &str) -> Result<Vector4<T>, Error> {
+        return Vector4::json(path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn homogeneous_semantics() {
+        /* every component counts, a point is no longer a unit vector just because xyz is */
+        let point = Vector4::new((1.0, 0.0, 0.0, 1.0));
+        assert_eq!(point.dot(&point), 2.0);
+        assert_eq!(point.magnitude(), 2.0f32.sqrt());
+        let mut v = Vector4::new((0.0, 3.0, 0.0, 4.0));
+        v.normalize();
+        assert!(v.approx_eq(&Vector4::new((0.0, 0.6, 0.0, 0.8)), 1e-6));
+
+        /* the cross product is a direction whatever w the inputs have */
+        let x = Vector4::new((1.0, 0.0, 0.0, 1.0));
+        let y = Vector4::new((0.0, 1.0, 0.0, 1.0));
+        assert_eq!(x.cross(&y), Vector4::new((0.0, 0.0, 1.0, 0.0)));
     }
 }